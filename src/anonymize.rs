@@ -0,0 +1,167 @@
+//! Strip identifying and online bound data from courses before they are shared again.
+
+#[cfg(all(target_arch = "wasm32", feature = "with-serde"))]
+use crate::JsResult;
+use crate::{constants2::*, errors::Smm2Error, Course, Course2, Result};
+
+use chrono::{Datelike, NaiveDateTime, Timelike};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+/// Controls how a course is anonymized.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AnonymizeOptions {
+    /// Replace the "last modified" timestamp with this Unix timestamp.
+    ///
+    /// Super Mario Maker 2 stores the timestamp with minute precision, so seconds are dropped.
+    pub modified: Option<u64>,
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl AnonymizeOptions {
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(constructor))]
+    pub fn new(modified: Option<u64>) -> Self {
+        AnonymizeOptions { modified }
+    }
+}
+
+/// A field which has been rewritten during anonymization.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AnonymizedField {
+    UploadId,
+    CreationId,
+    /// Clear check tries, clear check time and the cleared management flag.
+    ClearCheck,
+    SmmdbId,
+    Modified,
+    /// Name of the maker.
+    ///
+    /// Super Mario Maker only.
+    Maker,
+}
+
+impl Course2 {
+    /// Get a copy of this course without upload ID, creation ID, clear check and SMMDB ID.
+    ///
+    /// Fields are rewritten in the header and in the course data, if present.
+    /// Only fields which actually changed are reported.
+    ///
+    /// This fails, if the new "last modified" timestamp is out of range.
+    pub fn anonymize(&self, options: AnonymizeOptions) -> Result<(Course2, Vec<AnonymizedField>)> {
+        let mut course = self.clone();
+        let changes = course.anonymize_in_place(options)?;
+        Ok((course, changes))
+    }
+
+    fn anonymize_in_place(&mut self, options: AnonymizeOptions) -> Result<Vec<AnonymizedField>> {
+        let modified = options
+            .modified
+            .map(|modified| {
+                i64::try_from(modified)
+                    .ok()
+                    .and_then(|modified| NaiveDateTime::from_timestamp_opt(modified, 0))
+                    .filter(|time| (0..=u16::MAX as i32).contains(&time.year()))
+                    .ok_or(Smm2Error::InvalidTimestamp(modified))
+            })
+            .transpose()?;
+
+        let mut changes = vec![];
+        let header = self.get_course().get_header();
+        if header.upload_id != 0 {
+            changes.push(AnonymizedField::UploadId);
+        }
+        if header.creation_id != 0 {
+            changes.push(AnonymizedField::CreationId);
+        }
+        if self.is_cleared() || header.clear_check_tries != 0 || header.clear_check_time != u32::MAX
+        {
+            changes.push(AnonymizedField::ClearCheck);
+        }
+        if self.has_course_data() && self.get_smmdb_id().is_some() {
+            changes.push(AnonymizedField::SmmdbId);
+        }
+        if let Some(modified) = modified {
+            if header.modified != modified.timestamp() as u64 - modified.second() as u64 {
+                changes.push(AnonymizedField::Modified);
+            }
+        }
+
+        let header = self.get_course_mut().mut_header();
+        header.set_upload_id(0);
+        header.set_creation_id(0);
+        if let Some(modified) = modified {
+            header.set_modified(modified.timestamp() as u64 - modified.second() as u64);
+        }
+        self.reset_clear_check();
+        if self.has_course_data() {
+            let data = self.get_course_data_mut();
+            data[UPLOAD_ID_OFFSET..UPLOAD_ID_OFFSET + 8].fill(0);
+            data[CREATION_ID_OFFSET..CREATION_ID_OFFSET + 4].fill(0);
+            data[SMMDB_OFFSET..SMMDB_OFFSET_END].fill(0);
+            if let Some(modified) = modified {
+                data[YEAR_OFFSET..YEAR_OFFSET + 2]
+                    .copy_from_slice(&(modified.year() as u16).to_le_bytes());
+                data[MONTH_OFFSET] = modified.month() as u8;
+                data[DAY_OFFSET] = modified.day() as u8;
+                data[HOUR_OFFSET] = modified.hour() as u8;
+                data[MINUTE_OFFSET] = modified.minute() as u8;
+            }
+        }
+        Ok(changes)
+    }
+}
+
+impl Course {
+    /// Get a copy of this course without the name of its maker.
+    ///
+    /// Only fields which actually changed are reported.
+    pub fn anonymize(&self, options: AnonymizeOptions) -> (Course, Vec<AnonymizedField>) {
+        let mut course = self.clone();
+        let changes = course.anonymize_in_place(options);
+        (course, changes)
+    }
+
+    fn anonymize_in_place(&mut self, options: AnonymizeOptions) -> Vec<AnonymizedField> {
+        let mut changes = vec![];
+        if !self.get_course().maker.is_empty() {
+            changes.push(AnonymizedField::Maker);
+            self.get_course_mut().clear_maker();
+        }
+        if let Some(modified) = options.modified {
+            if self.get_course().modified != modified {
+                changes.push(AnonymizedField::Modified);
+                self.set_modified(modified);
+            }
+        }
+        changes
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Course2 {
+    /// Anonymize this course in place and return the changed fields.
+    #[cfg(target_arch = "wasm32")]
+    #[cfg(feature = "with-serde")]
+    #[wasm_bindgen(js_name = anonymize)]
+    pub fn anonymize_js(&mut self, options: AnonymizeOptions) -> JsResult<JsValue> {
+        Ok(serde_wasm_bindgen::to_value(
+            &self.anonymize_in_place(options)?,
+        )?)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Course {
+    /// Anonymize this course in place and return the changed fields.
+    #[cfg(target_arch = "wasm32")]
+    #[cfg(feature = "with-serde")]
+    #[wasm_bindgen(js_name = anonymize)]
+    pub fn anonymize_js(&mut self, options: AnonymizeOptions) -> JsResult<JsValue> {
+        Ok(serde_wasm_bindgen::to_value(
+            &self.anonymize_in_place(options),
+        )?)
+    }
+}
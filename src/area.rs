@@ -0,0 +1,199 @@
+//! Super Mario Maker course area entities.
+//!
+//! Tiles of a [SMMCourse](crate::proto::SMMCourse::SMMCourse) are stored as raw 32 byte big endian blobs.
+//! This module decodes these blobs into typed objects.
+
+use crate::{
+    area2::AreaType,
+    constants::TILE_SIZE,
+    proto::{Sound::Sound, Tile::Tile},
+    Course,
+};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+/// An object placed in a course area.
+///
+/// Positions are stored in tenths of a pixel, so one tile equals
+/// [OBJECT_TILE_SIZE](crate::area2::OBJECT_TILE_SIZE) units.
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Object {
+    pub x: u32,
+    pub z: u32,
+    pub y: i16,
+    pub width: i8,
+    pub height: i8,
+    pub flags: u32,
+    pub child_flags: u32,
+    pub extended_data: u32,
+    pub object_type: i8,
+    pub child_type: i8,
+    pub link_id: i16,
+    pub effect_index: i16,
+    pub unknown: i8,
+    pub child_transformation: i8,
+}
+
+impl Object {
+    /// Decode the raw tile data of the given tile.
+    ///
+    /// Returns `None`, if the tile does not hold raw tile data.
+    pub fn from_tile(tile: &Tile) -> Option<Object> {
+        if !tile.has_tile_data() || tile.get_tile_data().len() != TILE_SIZE {
+            return None;
+        }
+        Some(Object::from_bytes(tile.get_tile_data()))
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Object {
+        Object {
+            x: u32::from_be_bytes(*array_ref!(bytes, 0x0, 4)),
+            z: u32::from_be_bytes(*array_ref!(bytes, 0x4, 4)),
+            y: i16::from_be_bytes(*array_ref!(bytes, 0x8, 2)),
+            width: bytes[0xA] as i8,
+            height: bytes[0xB] as i8,
+            flags: u32::from_be_bytes(*array_ref!(bytes, 0xC, 4)),
+            child_flags: u32::from_be_bytes(*array_ref!(bytes, 0x10, 4)),
+            extended_data: u32::from_be_bytes(*array_ref!(bytes, 0x14, 4)),
+            object_type: bytes[0x18] as i8,
+            child_type: bytes[0x19] as i8,
+            link_id: i16::from_be_bytes(*array_ref!(bytes, 0x1A, 2)),
+            effect_index: i16::from_be_bytes(*array_ref!(bytes, 0x1C, 2)),
+            unknown: bytes[0x1E] as i8,
+            child_transformation: bytes[0x1F] as i8,
+        }
+    }
+
+    /// Returns `None`, if the object type is unknown.
+    pub fn object_id(&self) -> Option<ObjectId> {
+        ObjectId::from_id(self.object_type)
+    }
+}
+
+macro_rules! object_ids {
+    ($($name:ident = $id:literal,)*) => {
+        /// Known object types of Super Mario Maker.
+        ///
+        /// Super Mario Maker 2 kept these IDs for the same objects,
+        /// see [ObjectId2](crate::area2::ObjectId2).
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+        #[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum ObjectId {
+            $($name = $id,)*
+        }
+
+        impl ObjectId {
+            /// Returns `None`, if the given ID is unknown.
+            pub fn from_id(id: i8) -> Option<ObjectId> {
+                match id {
+                    $($id => Some(ObjectId::$name),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+object_ids! {
+    Goomba = 0,
+    Koopa = 1,
+    PiranhaFlower = 2,
+    HammerBro = 3,
+    Block = 4,
+    QuestionBlock = 5,
+    HardBlock = 6,
+    Ground = 7,
+    Coin = 8,
+    Pipe = 9,
+    Spring = 10,
+    Lift = 11,
+    Thwomp = 12,
+    BulletBillBlaster = 13,
+    MushroomPlatform = 14,
+    BobOmb = 15,
+    SemisolidPlatform = 16,
+    Bridge = 17,
+    PSwitch = 18,
+    Pow = 19,
+    SuperMushroom = 20,
+    DonutBlock = 21,
+    Cloud = 22,
+    NoteBlock = 23,
+    FireBar = 24,
+    Spiny = 25,
+    GoalGround = 26,
+    Goal = 27,
+    BuzzyBeetle = 28,
+    HiddenBlock = 29,
+    Lakitu = 30,
+    LakituCloud = 31,
+    BanzaiBill = 32,
+    OneUp = 33,
+    FireFlower = 34,
+    SuperStar = 35,
+    LavaLift = 36,
+    StartingBrick = 37,
+    StartingArrow = 38,
+    Magikoopa = 39,
+    SpikeTop = 40,
+    Boo = 41,
+    ClownCar = 42,
+    Spikes = 43,
+    BigMushroom = 44,
+    ShoeGoomba = 45,
+    DryBones = 46,
+    Cannon = 47,
+    Blooper = 48,
+    CastleBridge = 49,
+    JumpingMachine = 50,
+    Skipsqueak = 51,
+    Wiggler = 52,
+    ConveyorBelt = 53,
+    Burner = 54,
+    Door = 55,
+    CheepCheep = 56,
+    Muncher = 57,
+    RockyWrench = 58,
+    Track = 59,
+    LavaBubble = 60,
+    ChainChomp = 61,
+    Bowser = 62,
+    IceBlock = 63,
+    Vine = 64,
+    Stingby = 65,
+    Arrow = 66,
+    OneWay = 67,
+    Saw = 68,
+    Player = 69,
+}
+
+impl Course {
+    /// Get the tiles of the given area.
+    pub fn get_area_tiles(&self, area: AreaType) -> &[Tile] {
+        match area {
+            AreaType::Main => &self.get_course().tiles,
+            AreaType::Sub => &self.get_course().tiles_sub,
+        }
+    }
+
+    /// Get the sounds of the given area.
+    pub fn get_area_sounds(&self, area: AreaType) -> &[Sound] {
+        match area {
+            AreaType::Main => &self.get_course().sounds,
+            AreaType::Sub => &self.get_course().sounds_sub,
+        }
+    }
+
+    /// Decode all tiles of the given area into objects.
+    ///
+    /// Tiles without raw tile data are skipped.
+    pub fn get_objects(&self, area: AreaType) -> Vec<Object> {
+        self.get_area_tiles(area)
+            .iter()
+            .filter_map(Object::from_tile)
+            .collect()
+    }
+}
@@ -0,0 +1,704 @@
+//! Super Mario Maker 2 course area entities.
+//!
+//! Every course has a main area and a sub area.
+//! Next to the settings which are exposed via [SMM2CourseArea](crate::proto::SMM2Course::SMM2CourseArea),
+//! each area stores fixed size tables for all placed entities.
+//! The number of used entries of each table is stored in the area header.
+
+use crate::{constants2::*, proto::SMM2Course::SMM2CourseHeader_GameStyle};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+/// Identifies one of the two areas of a course.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AreaType {
+    Main = 0,
+    Sub = 1,
+}
+
+impl AreaType {
+    pub const ALL: [AreaType; 2] = [AreaType::Main, AreaType::Sub];
+
+    /// Index into the area offset tables of [constants2](crate::constants2).
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Size of a single tile in the coordinate system of objects.
+pub const OBJECT_TILE_SIZE: i32 = 160;
+
+/// An object placed in a course area.
+///
+/// Positions are stored in tenths of a pixel, so one tile equals [OBJECT_TILE_SIZE] units.
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Object2 {
+    pub x: i32,
+    pub y: i32,
+    pub unknown: i16,
+    pub width: u8,
+    pub height: u8,
+    pub flags: u32,
+    pub child_flags: u32,
+    pub extended_data: u32,
+    pub id: i16,
+    pub child_id: i16,
+    pub link_id: i16,
+    pub sound_id: i16,
+}
+
+impl Object2 {
+    fn from_bytes(bytes: &[u8]) -> Object2 {
+        Object2 {
+            x: i32::from_le_bytes(*array_ref!(bytes, 0x0, 4)),
+            y: i32::from_le_bytes(*array_ref!(bytes, 0x4, 4)),
+            unknown: i16::from_le_bytes(*array_ref!(bytes, 0x8, 2)),
+            width: bytes[0xA],
+            height: bytes[0xB],
+            flags: u32::from_le_bytes(*array_ref!(bytes, 0xC, 4)),
+            child_flags: u32::from_le_bytes(*array_ref!(bytes, 0x10, 4)),
+            extended_data: u32::from_le_bytes(*array_ref!(bytes, 0x14, 4)),
+            id: i16::from_le_bytes(*array_ref!(bytes, 0x18, 2)),
+            child_id: i16::from_le_bytes(*array_ref!(bytes, 0x1A, 2)),
+            link_id: i16::from_le_bytes(*array_ref!(bytes, 0x1C, 2)),
+            sound_id: i16::from_le_bytes(*array_ref!(bytes, 0x1E, 2)),
+        }
+    }
+
+    /// Returns `None`, if the object type is unknown.
+    pub fn object_id(&self) -> Option<ObjectId2> {
+        ObjectId2::from_id(self.id)
+    }
+}
+
+macro_rules! object_ids {
+    ($($name:ident = $id:literal,)*) => {
+        /// Known object types of Super Mario Maker 2.
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+        #[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum ObjectId2 {
+            $($name = $id,)*
+        }
+
+        impl ObjectId2 {
+            /// Returns `None`, if the given ID is unknown.
+            pub fn from_id(id: i16) -> Option<ObjectId2> {
+                match id {
+                    $($id => Some(ObjectId2::$name),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+object_ids! {
+    Goomba = 0,
+    Koopa = 1,
+    PiranhaFlower = 2,
+    HammerBro = 3,
+    Block = 4,
+    QuestionBlock = 5,
+    HardBlock = 6,
+    Ground = 7,
+    Coin = 8,
+    Pipe = 9,
+    Spring = 10,
+    Lift = 11,
+    Thwomp = 12,
+    BulletBillBlaster = 13,
+    MushroomPlatform = 14,
+    BobOmb = 15,
+    SemisolidPlatform = 16,
+    Bridge = 17,
+    PSwitch = 18,
+    Pow = 19,
+    SuperMushroom = 20,
+    DonutBlock = 21,
+    Cloud = 22,
+    NoteBlock = 23,
+    FireBar = 24,
+    Spiny = 25,
+    GoalGround = 26,
+    Goal = 27,
+    BuzzyBeetle = 28,
+    HiddenBlock = 29,
+    Lakitu = 30,
+    LakituCloud = 31,
+    BanzaiBill = 32,
+    OneUp = 33,
+    FireFlower = 34,
+    SuperStar = 35,
+    LavaLift = 36,
+    StartingBrick = 37,
+    StartingArrow = 38,
+    Magikoopa = 39,
+    SpikeTop = 40,
+    Boo = 41,
+    ClownCar = 42,
+    Spikes = 43,
+    BigMushroom = 44,
+    ShoeGoomba = 45,
+    DryBones = 46,
+    Cannon = 47,
+    Blooper = 48,
+    CastleBridge = 49,
+    JumpingMachine = 50,
+    Skipsqueak = 51,
+    Wiggler = 52,
+    FastConveyorBelt = 53,
+    Burner = 54,
+    Door = 55,
+    CheepCheep = 56,
+    Muncher = 57,
+    RockyWrench = 58,
+    Track = 59,
+    LavaBubble = 60,
+    ChainChomp = 61,
+    Bowser = 62,
+    IceBlock = 63,
+    Vine = 64,
+    Stingby = 65,
+    Arrow = 66,
+    OneWay = 67,
+    Saw = 68,
+    Player = 69,
+    BigCoin = 70,
+    HalfCollisionPlatform = 71,
+    KoopaCar = 72,
+    Cinobio = 73,
+    SpikeBall = 74,
+    Stone = 75,
+    Twister = 76,
+    BoomBoom = 77,
+    Pokey = 78,
+    PBlock = 79,
+    SprintPlatform = 80,
+    Smb2Mushroom = 81,
+    Donut = 82,
+    Skewer = 83,
+    SnakeBlock = 84,
+    TrackBlock = 85,
+    Charvaargh = 86,
+    SlightSlope = 87,
+    SteepSlope = 88,
+    ReelCamera = 89,
+    CheckpointFlag = 90,
+    Seesaw = 91,
+    RedCoin = 92,
+    ClearPipe = 93,
+    ConveyorBelt = 94,
+    Key = 95,
+    AntTrooper = 96,
+    WarpBox = 97,
+    BowserJr = 98,
+    OnOffBlock = 99,
+    DottedLineBlock = 100,
+    WaterMarker = 101,
+    MontyMole = 102,
+    FishBone = 103,
+    AngrySun = 104,
+    SwingingClaw = 105,
+    Tree = 106,
+    PiranhaCreeper = 107,
+    BlinkingBlock = 108,
+    SoundEffect = 109,
+    SpikeBlock = 110,
+    Mechakoopa = 111,
+    Crate = 112,
+    MushroomTrampoline = 113,
+    Porkupuffer = 114,
+    Cinobic = 115,
+    SuperHammer = 116,
+    Bully = 117,
+    Icicle = 118,
+    ExclamationBlock = 119,
+    Lemmy = 120,
+    Morton = 121,
+    Larry = 122,
+    Wendy = 123,
+    Iggy = 124,
+    Roy = 125,
+    Ludwig = 126,
+    CannonBox = 127,
+    PropellerBox = 128,
+    GoombaMask = 129,
+    BulletBillMask = 130,
+    RedPowBox = 131,
+    OnOffTrampoline = 132,
+}
+
+impl ObjectId2 {
+    /// Returns `false`, if the object can not be placed in courses of the given game style.
+    pub fn is_available(self, game_style: SMM2CourseHeader_GameStyle) -> bool {
+        use ObjectId2::*;
+
+        match self {
+            Smb2Mushroom => game_style == SMM2CourseHeader_GameStyle::M1,
+            Skipsqueak | Stingby | KoopaCar | SprintPlatform | TrackBlock | Charvaargh
+            | ClearPipe | AntTrooper | WarpBox | Tree | PiranhaCreeper | BlinkingBlock | Crate
+            | MushroomTrampoline | Porkupuffer | SuperHammer | Bully | ExclamationBlock => {
+                game_style == SMM2CourseHeader_GameStyle::W3
+            }
+            ClownCar | ShoeGoomba | Track | Lemmy | Morton | Larry | Wendy | Iggy | Roy
+            | Ludwig | CannonBox | PropellerBox | GoombaMask | BulletBillMask | RedPowBox => {
+                game_style != SMM2CourseHeader_GameStyle::W3
+            }
+            _ => true,
+        }
+    }
+}
+
+/// A sound effect attached to a position in a course area.
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SoundEffect2 {
+    pub sound_type: u8,
+    pub x: u8,
+    pub y: u8,
+    pub unknown: u8,
+}
+
+impl SoundEffect2 {
+    fn from_bytes(bytes: &[u8]) -> SoundEffect2 {
+        SoundEffect2 {
+            sound_type: bytes[0],
+            x: bytes[1],
+            y: bytes[2],
+            unknown: bytes[3],
+        }
+    }
+}
+
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SnakeBlockNode2 {
+    pub index: u16,
+    pub direction: u16,
+    pub unknown: u32,
+}
+
+/// Path of a snake block.
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SnakeBlock2 {
+    pub index: u8,
+    pub unknown: u16,
+    pub nodes: Vec<SnakeBlockNode2>,
+}
+
+impl SnakeBlock2 {
+    fn from_bytes(bytes: &[u8]) -> SnakeBlock2 {
+        let node_count = (bytes[1] as usize).min(SNAKE_BLOCK_NODE_MAX);
+        let nodes = bytes[4..4 + node_count * SNAKE_BLOCK_NODE_SIZE]
+            .chunks_exact(SNAKE_BLOCK_NODE_SIZE)
+            .map(|node| SnakeBlockNode2 {
+                index: u16::from_le_bytes(*array_ref!(node, 0, 2)),
+                direction: u16::from_le_bytes(*array_ref!(node, 2, 2)),
+                unknown: u32::from_le_bytes(*array_ref!(node, 4, 4)),
+            })
+            .collect();
+        SnakeBlock2 {
+            index: bytes[0],
+            unknown: u16::from_le_bytes(*array_ref!(bytes, 2, 2)),
+            nodes,
+        }
+    }
+}
+
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ClearPipeNode2 {
+    pub node_type: u8,
+    pub index: u8,
+    pub x: u8,
+    pub y: u8,
+    pub width: u8,
+    pub height: u8,
+    pub unknown: u8,
+    pub direction: u8,
+}
+
+/// Segments of a clear pipe.
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ClearPipe2 {
+    pub index: u8,
+    pub unknown: u16,
+    pub nodes: Vec<ClearPipeNode2>,
+}
+
+impl ClearPipe2 {
+    fn from_bytes(bytes: &[u8]) -> ClearPipe2 {
+        let node_count = (bytes[1] as usize).min(CLEAR_PIPE_NODE_MAX);
+        let nodes = bytes[4..4 + node_count * CLEAR_PIPE_NODE_SIZE]
+            .chunks_exact(CLEAR_PIPE_NODE_SIZE)
+            .map(|node| ClearPipeNode2 {
+                node_type: node[0],
+                index: node[1],
+                x: node[2],
+                y: node[3],
+                width: node[4],
+                height: node[5],
+                unknown: node[6],
+                direction: node[7],
+            })
+            .collect();
+        ClearPipe2 {
+            index: bytes[0],
+            unknown: u16::from_le_bytes(*array_ref!(bytes, 2, 2)),
+            nodes,
+        }
+    }
+}
+
+/// Node of a path used by piranha creepers, exclamation blocks and track blocks.
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PathNode2 {
+    pub unknown_0: u8,
+    pub direction: u8,
+    pub unknown_1: u16,
+}
+
+/// Path of a piranha creeper, an exclamation block or a track block.
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Path2 {
+    pub unknown_0: u8,
+    pub index: u8,
+    pub unknown_1: u8,
+    pub nodes: Vec<PathNode2>,
+}
+
+impl Path2 {
+    fn from_bytes(bytes: &[u8], node_max: usize) -> Path2 {
+        let node_count = (bytes[2] as usize).min(node_max);
+        let nodes = bytes[4..4 + node_count * PATH_NODE_SIZE]
+            .chunks_exact(PATH_NODE_SIZE)
+            .map(|node| PathNode2 {
+                unknown_0: node[0],
+                direction: node[1],
+                unknown_1: u16::from_le_bytes(*array_ref!(node, 2, 2)),
+            })
+            .collect();
+        Path2 {
+            unknown_0: bytes[0],
+            index: bytes[1],
+            unknown_1: bytes[3],
+            nodes,
+        }
+    }
+}
+
+/// A single ground tile.
+///
+/// Positions are measured in tiles.
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Ground2 {
+    pub x: u8,
+    pub y: u8,
+    pub id: u8,
+    pub background_id: u8,
+}
+
+impl Ground2 {
+    fn from_bytes(bytes: &[u8]) -> Ground2 {
+        Ground2 {
+            x: bytes[0],
+            y: bytes[1],
+            id: bytes[2],
+            background_id: bytes[3],
+        }
+    }
+}
+
+/// A single track segment.
+///
+/// Positions are measured in tiles.
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Track2 {
+    pub unknown_0: u16,
+    pub flags: u8,
+    pub x: u8,
+    pub y: u8,
+    pub track_type: u8,
+    pub link_id: u16,
+    pub unknown_1: u16,
+    pub unknown_2: u16,
+}
+
+impl Track2 {
+    fn from_bytes(bytes: &[u8]) -> Track2 {
+        Track2 {
+            unknown_0: u16::from_le_bytes(*array_ref!(bytes, 0x0, 2)),
+            flags: bytes[0x2],
+            x: bytes[0x3],
+            y: bytes[0x4],
+            track_type: bytes[0x5],
+            link_id: u16::from_le_bytes(*array_ref!(bytes, 0x6, 2)),
+            unknown_1: u16::from_le_bytes(*array_ref!(bytes, 0x8, 2)),
+            unknown_2: u16::from_le_bytes(*array_ref!(bytes, 0xA, 2)),
+        }
+    }
+}
+
+/// A single icicle.
+///
+/// Positions are measured in tiles.
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Icicle2 {
+    pub x: u8,
+    pub y: u8,
+    pub icicle_type: u8,
+    pub unknown: u8,
+}
+
+impl Icicle2 {
+    fn from_bytes(bytes: &[u8]) -> Icicle2 {
+        Icicle2 {
+            x: bytes[0],
+            y: bytes[1],
+            icicle_type: bytes[2],
+            unknown: bytes[3],
+        }
+    }
+}
+
+/// Identifies one of the fixed size entity tables of a course area.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EntityTable2 {
+    Objects,
+    SoundEffects,
+    SnakeBlocks,
+    ClearPipes,
+    PiranhaCreepers,
+    ExclamationBlocks,
+    TrackBlocks,
+    Ground,
+    Tracks,
+    Icicles,
+}
+
+impl EntityTable2 {
+    pub const ALL: [EntityTable2; 10] = [
+        EntityTable2::Objects,
+        EntityTable2::SoundEffects,
+        EntityTable2::SnakeBlocks,
+        EntityTable2::ClearPipes,
+        EntityTable2::PiranhaCreepers,
+        EntityTable2::ExclamationBlocks,
+        EntityTable2::TrackBlocks,
+        EntityTable2::Ground,
+        EntityTable2::Tracks,
+        EntityTable2::Icicles,
+    ];
+
+    /// Offset of the first entry in decrypted course data.
+    pub fn offset(self, area: AreaType) -> usize {
+        let offsets = match self {
+            EntityTable2::Objects => OBJECTS_OFFSET,
+            EntityTable2::SoundEffects => SOUND_EFFECTS_OFFSET,
+            EntityTable2::SnakeBlocks => SNAKE_BLOCKS_OFFSET,
+            EntityTable2::ClearPipes => CLEAR_PIPES_OFFSET,
+            EntityTable2::PiranhaCreepers => PIRANHA_CREEPERS_OFFSET,
+            EntityTable2::ExclamationBlocks => EXCLAMATION_BLOCKS_OFFSET,
+            EntityTable2::TrackBlocks => TRACK_BLOCKS_OFFSET,
+            EntityTable2::Ground => GROUND_OFFSET,
+            EntityTable2::Tracks => TRACKS_OFFSET,
+            EntityTable2::Icicles => ICICLES_OFFSET,
+        };
+        offsets[area.index()]
+    }
+
+    /// Offset of the count field in decrypted course data.
+    pub fn count_offset(self, area: AreaType) -> usize {
+        let offsets = match self {
+            EntityTable2::Objects => OBJECT_COUNT_OFFSET,
+            EntityTable2::SoundEffects => SOUND_EFFECT_COUNT_OFFSET,
+            EntityTable2::SnakeBlocks => SNAKE_BLOCK_COUNT_OFFSET,
+            EntityTable2::ClearPipes => CLEAR_PIPE_COUNT_OFFSET,
+            EntityTable2::PiranhaCreepers => PIRANHA_CREEPER_COUNT_OFFSET,
+            EntityTable2::ExclamationBlocks => EXCLAMATION_BLOCK_COUNT_OFFSET,
+            EntityTable2::TrackBlocks => TRACK_BLOCK_COUNT_OFFSET,
+            EntityTable2::Ground => TILE_COUNT_OFFSET,
+            EntityTable2::Tracks => TRACK_COUNT_OFFSET,
+            EntityTable2::Icicles => ICICLE_COUNT_OFFSET,
+        };
+        offsets[area.index()]
+    }
+
+    /// Size of a single entry in bytes.
+    pub fn entry_size(self) -> usize {
+        match self {
+            EntityTable2::Objects => OBJECT_SIZE,
+            EntityTable2::SoundEffects => SOUND_EFFECT_SIZE,
+            EntityTable2::SnakeBlocks => SNAKE_BLOCK_SIZE,
+            EntityTable2::ClearPipes => CLEAR_PIPE_SIZE,
+            EntityTable2::PiranhaCreepers => PIRANHA_CREEPER_SIZE,
+            EntityTable2::ExclamationBlocks => EXCLAMATION_BLOCK_SIZE,
+            EntityTable2::TrackBlocks => TRACK_BLOCK_SIZE,
+            EntityTable2::Ground => GROUND_SIZE,
+            EntityTable2::Tracks => TRACK_SIZE,
+            EntityTable2::Icicles => ICICLE_SIZE,
+        }
+    }
+
+    /// Maximum amount of entries.
+    pub fn max(self) -> usize {
+        match self {
+            EntityTable2::Objects => OBJECT_MAX,
+            EntityTable2::SoundEffects => SOUND_EFFECT_MAX,
+            EntityTable2::SnakeBlocks => SNAKE_BLOCK_MAX,
+            EntityTable2::ClearPipes => CLEAR_PIPE_MAX,
+            EntityTable2::PiranhaCreepers => PIRANHA_CREEPER_MAX,
+            EntityTable2::ExclamationBlocks => EXCLAMATION_BLOCK_MAX,
+            EntityTable2::TrackBlocks => TRACK_BLOCK_MAX,
+            EntityTable2::Ground => GROUND_MAX,
+            EntityTable2::Tracks => TRACK_MAX,
+            EntityTable2::Icicles => ICICLE_MAX,
+        }
+    }
+
+    /// Returns `true` for tables whose entries store their own slot index.
+    ///
+    /// The game does not compact these tables, so there might be gaps between used entries.
+    /// Removed entries are cleared though, so every non-empty entry is in use
+    /// and the count field holds the amount of non-empty entries.
+    pub fn is_indexed(self) -> bool {
+        matches!(
+            self,
+            EntityTable2::SnakeBlocks
+                | EntityTable2::ClearPipes
+                | EntityTable2::PiranhaCreepers
+                | EntityTable2::ExclamationBlocks
+                | EntityTable2::TrackBlocks
+        )
+    }
+
+    /// Read the unclamped count field from decrypted course data.
+    pub fn read_count(self, course_data: &[u8], area: AreaType) -> u32 {
+        u32::from_le_bytes(*array_ref!(course_data, self.count_offset(area), 4))
+    }
+
+    /// Count the used entries of this table in decrypted course data.
+    ///
+    /// Indexed tables might contain gaps, so every non-empty entry is counted.
+    /// For all courses of the test saves, this matches the count fields stored by the game.
+    /// All other tables are compacted, so all entries up to the last non-empty entry are counted.
+    pub fn count_entries(self, course_data: &[u8], area: AreaType) -> u32 {
+        let offset = self.offset(area);
+        let size = self.entry_size();
+        let mut entries = course_data[offset..offset + self.max() * size]
+            .chunks_exact(size)
+            .map(|entry| entry.iter().any(|byte| *byte != 0));
+        let count = if self.is_indexed() {
+            entries.filter(|used| *used).count()
+        } else {
+            entries.rposition(|used| used).map_or(0, |index| index + 1)
+        };
+        count as u32
+    }
+
+    /// Slots of the entries in use according to the count field of decrypted course data.
+    ///
+    /// Compacted tables use the first entries up to the count field.
+    /// Indexed tables use the non-empty entries, of which at most the amount
+    /// in the count field is taken.
+    /// The count field is clamped to the capacity of the table.
+    pub fn used_entries(self, course_data: &[u8], area: AreaType) -> Vec<usize> {
+        let count = (self.read_count(course_data, area) as usize).min(self.max());
+        if !self.is_indexed() {
+            return (0..count).collect();
+        }
+        let offset = self.offset(area);
+        let size = self.entry_size();
+        (0..self.max())
+            .filter(|index| {
+                let entry = offset + index * size;
+                course_data[entry..entry + size]
+                    .iter()
+                    .any(|byte| *byte != 0)
+            })
+            .take(count)
+            .collect()
+    }
+}
+
+/// All decoded entities of a single course area.
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Area2 {
+    pub objects: Vec<Object2>,
+    pub sound_effects: Vec<SoundEffect2>,
+    pub snake_blocks: Vec<SnakeBlock2>,
+    pub clear_pipes: Vec<ClearPipe2>,
+    pub piranha_creepers: Vec<Path2>,
+    pub exclamation_blocks: Vec<Path2>,
+    pub track_blocks: Vec<Path2>,
+    pub ground: Vec<Ground2>,
+    pub tracks: Vec<Track2>,
+    pub icicles: Vec<Icicle2>,
+}
+
+impl Area2 {
+    /// Decode all entities of the given area from decrypted course data.
+    ///
+    /// Entries are selected by [`EntityTable2::used_entries`].
+    pub(crate) fn from_course_data(course_data: &[u8], area: AreaType) -> Area2 {
+        let read = |table| Area2::read_table(course_data, table, area);
+        Area2 {
+            objects: read(EntityTable2::Objects)
+                .map(Object2::from_bytes)
+                .collect(),
+            sound_effects: read(EntityTable2::SoundEffects)
+                .map(SoundEffect2::from_bytes)
+                .collect(),
+            snake_blocks: read(EntityTable2::SnakeBlocks)
+                .map(SnakeBlock2::from_bytes)
+                .collect(),
+            clear_pipes: read(EntityTable2::ClearPipes)
+                .map(ClearPipe2::from_bytes)
+                .collect(),
+            piranha_creepers: read(EntityTable2::PiranhaCreepers)
+                .map(|bytes| Path2::from_bytes(bytes, PIRANHA_CREEPER_NODE_MAX))
+                .collect(),
+            exclamation_blocks: read(EntityTable2::ExclamationBlocks)
+                .map(|bytes| Path2::from_bytes(bytes, MOVING_BLOCK_NODE_MAX))
+                .collect(),
+            track_blocks: read(EntityTable2::TrackBlocks)
+                .map(|bytes| Path2::from_bytes(bytes, MOVING_BLOCK_NODE_MAX))
+                .collect(),
+            ground: read(EntityTable2::Ground)
+                .map(Ground2::from_bytes)
+                .collect(),
+            tracks: read(EntityTable2::Tracks).map(Track2::from_bytes).collect(),
+            icicles: read(EntityTable2::Icicles)
+                .map(Icicle2::from_bytes)
+                .collect(),
+        }
+    }
+
+    fn read_table(
+        course_data: &[u8],
+        table: EntityTable2,
+        area: AreaType,
+    ) -> impl Iterator<Item = &[u8]> {
+        let offset = table.offset(area);
+        let size = table.entry_size();
+        table
+            .used_entries(course_data, area)
+            .into_iter()
+            .map(move |index| &course_data[offset + index * size..offset + (index + 1) * size])
+    }
+}
@@ -0,0 +1,228 @@
+//! Swap, clear and copy whole areas of Super Mario Maker 2 courses.
+//!
+//! Each area is a fixed size block in the decrypted course data,
+//! which starts with the area settings followed by all entity tables.
+//!
+//! Warp pipes are the only entities which connect both areas.
+//! Both ends of a warp share a link number in the object flags, which is unique within a course.
+//! Whenever an area is replaced, pipes of the other area which led into it are turned into regular pipes.
+//! Doors always connect to a door of the same area, so they move together with their area.
+
+#[cfg(target_arch = "wasm32")]
+use crate::JsResult;
+use crate::{
+    area2::{Area2, AreaType, ObjectId2},
+    constants2::*,
+    errors::Smm2Error,
+    proto::SMM2Course::{SMM2CourseArea, SMM2CourseArea_Orientation},
+    validation2::BOUNDARY_TILE_SIZE,
+    Course2, Result,
+};
+
+use std::collections::{HashMap, HashSet};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+/// Offset of the first entity table relative to the start of an area.
+const AREA_ENTITIES_OFFSET: usize = 0x48;
+
+/// Bits of the object flags of a pipe, which store its link number.
+///
+/// A link number of zero means, that the pipe is not a warp pipe.
+const PIPE_LINK_MASK: u32 = 0xF << 20;
+
+fn area_offset(area: AreaType) -> usize {
+    match area {
+        AreaType::Main => LEVEL_AREA_OFFSET,
+        AreaType::Sub => LEVEL_AREA_SUB_OFFSET,
+    }
+}
+
+impl Course2 {
+    /// Swap the main area with the sub area, including their settings.
+    ///
+    /// Warp pipes keep their link numbers, so they still connect both areas.
+    /// Start and goal positions of the course header are left untouched.
+    ///
+    /// Fails, if the sub area is vertical or too small for the start and goal positions.
+    pub fn swap_areas(&mut self) -> Result<()> {
+        if !self.has_course_data() {
+            return Err(Smm2Error::CourseDataRequired.into());
+        }
+        self.check_main_area(self.get_course().get_course_sub_area())?;
+        let data = self.get_course_data_mut();
+        let (main, sub) =
+            data[LEVEL_AREA_OFFSET..LEVEL_AREA_SUB_OFFSET + AREA_SIZE].split_at_mut(AREA_SIZE);
+        main.swap_with_slice(sub);
+        self.update_areas()
+    }
+
+    /// Remove all entities from an area, but keep its settings.
+    ///
+    /// Warp pipes of the other area, which lead into the cleared area, are turned into regular pipes.
+    pub fn clear_area(&mut self, area: AreaType) -> Result<()> {
+        if !self.has_course_data() {
+            return Err(Smm2Error::CourseDataRequired.into());
+        }
+        let offset = area_offset(area);
+        self.get_course_data_mut()[offset + AREA_ENTITIES_OFFSET..offset + AREA_SIZE].fill(0);
+        self.update_areas()
+    }
+
+    /// Replace an area of this course with an area of another course, including its settings.
+    ///
+    /// Warp pipes of the copied area, which are linked to each other, get link numbers
+    /// which are not used in the other area of this course, so that they stay connected.
+    /// All other warp pipes of the copied area led into the other area of `other`
+    /// and are turned into regular pipes.
+    ///
+    /// Objects are copied as is, even if they are not available in the game style of this course.
+    ///
+    /// Fails, if the area would become the main area and is vertical
+    /// or too small for the start and goal positions.
+    pub fn copy_area_from(&mut self, other: &Course2, from: AreaType, to: AreaType) -> Result<()> {
+        if !self.has_course_data() || !other.has_course_data() {
+            return Err(Smm2Error::CourseDataRequired.into());
+        }
+        if to == AreaType::Main {
+            let settings = match from {
+                AreaType::Main => other.get_course().get_course_area(),
+                AreaType::Sub => other.get_course().get_course_sub_area(),
+            };
+            self.check_main_area(settings)?;
+        }
+        let kept = match to {
+            AreaType::Main => AreaType::Sub,
+            AreaType::Sub => AreaType::Main,
+        };
+        let from_offset = area_offset(from);
+        let to_offset = area_offset(to);
+        self.get_course_data_mut()[to_offset..to_offset + AREA_SIZE]
+            .copy_from_slice(&other.get_course_data()[from_offset..from_offset + AREA_SIZE]);
+        self.renumber_pipe_links(to, kept);
+        self.update_areas()
+    }
+
+    /// Fail, if an area with the given settings can not become the main area.
+    fn check_main_area(&self, settings: &SMM2CourseArea) -> Result<()> {
+        if settings.orientation == SMM2CourseArea_Orientation::VERTICAL {
+            return Err(Smm2Error::VerticalMainArea.into());
+        }
+        let header = self.get_course().get_header();
+        let height = settings.top_boundary / BOUNDARY_TILE_SIZE;
+        let width = settings.right_boundary / BOUNDARY_TILE_SIZE;
+        if header.start_y >= height || header.finish_y >= height || header.finish_x >= width * 10 {
+            return Err(Smm2Error::StartGoalOutOfBounds.into());
+        }
+        Ok(())
+    }
+
+    /// Warp pipes of an area as offsets of their object flags in the course data.
+    fn get_pipes(&self, area: AreaType) -> Vec<(usize, u32)> {
+        let objects = Area2::from_course_data(self.get_course_data(), area).objects;
+        objects
+            .iter()
+            .enumerate()
+            .filter(|(_, object)| {
+                object.object_id() == Some(ObjectId2::Pipe) && object.flags & PIPE_LINK_MASK != 0
+            })
+            .map(|(index, object)| {
+                let offset = OBJECTS_OFFSET[area.index()] + index * OBJECT_SIZE;
+                (offset + OBJECT_FLAGS_OFFSET, object.flags)
+            })
+            .collect()
+    }
+
+    /// Give pipes of `area`, which are linked to each other, link numbers unused in `kept`
+    /// and turn all other warp pipes of `area` into regular pipes.
+    fn renumber_pipe_links(&mut self, area: AreaType, kept: AreaType) {
+        let kept_links: HashSet<u32> = self
+            .get_pipes(kept)
+            .iter()
+            .map(|(_, flags)| flags & PIPE_LINK_MASK)
+            .collect();
+        let mut unused = (1..=PIPE_LINK_MASK >> 20)
+            .map(|link| link << 20)
+            .filter(|link| !kept_links.contains(link));
+        let pipes = self.get_pipes(area);
+        let mut links: HashMap<u32, usize> = HashMap::new();
+        for (_, flags) in pipes.iter() {
+            *links.entry(flags & PIPE_LINK_MASK).or_default() += 1;
+        }
+        let mut renumbered: HashMap<u32, u32> = HashMap::new();
+        for (_, flags) in pipes.iter() {
+            let link = flags & PIPE_LINK_MASK;
+            if links[&link] == 2 && !renumbered.contains_key(&link) {
+                if let Some(new_link) = unused.next() {
+                    renumbered.insert(link, new_link);
+                }
+            }
+        }
+        let data = self.get_course_data_mut();
+        for (offset, flags) in pipes {
+            let link = renumbered
+                .get(&(flags & PIPE_LINK_MASK))
+                .copied()
+                .unwrap_or(0);
+            let flags = (flags & !PIPE_LINK_MASK) | link;
+            data[offset..offset + 4].copy_from_slice(&flags.to_le_bytes());
+        }
+    }
+
+    /// Bring counts, pipe links and the proto areas in line with the course data.
+    pub(crate) fn update_areas(&mut self) -> Result<()> {
+        Course2::recount_course_data(self.get_course_data_mut());
+        self.unlink_pipes();
+        let course_area = Course2::get_course_area(self.get_course_data(), AreaType::Main.index())?;
+        let course_sub_area =
+            Course2::get_course_area(self.get_course_data(), AreaType::Sub.index())?;
+        let course = self.get_course_mut();
+        course.course_area = course_area;
+        course.course_sub_area = course_sub_area;
+        Ok(())
+    }
+
+    /// Turn all warp pipes without a matching pipe into regular pipes.
+    fn unlink_pipes(&mut self) {
+        let pipes: Vec<(usize, u32)> = AreaType::ALL
+            .iter()
+            .flat_map(|area| self.get_pipes(*area))
+            .collect();
+        let mut links: HashMap<u32, usize> = HashMap::new();
+        for (_, flags) in pipes.iter() {
+            *links.entry(flags & PIPE_LINK_MASK).or_default() += 1;
+        }
+        let data = self.get_course_data_mut();
+        for (offset, flags) in pipes {
+            if links[&(flags & PIPE_LINK_MASK)] != 2 {
+                data[offset..offset + 4].copy_from_slice(&(flags & !PIPE_LINK_MASK).to_le_bytes());
+            }
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Course2 {
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = swapAreas)]
+    pub fn swap_areas_js(&mut self) -> JsResult<()> {
+        Ok(self.swap_areas()?)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = clearArea)]
+    pub fn clear_area_js(&mut self, area: AreaType) -> JsResult<()> {
+        Ok(self.clear_area(area)?)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = copyAreaFrom)]
+    pub fn copy_area_from_js(
+        &mut self,
+        other: &Course2,
+        from: AreaType,
+        to: AreaType,
+    ) -> JsResult<()> {
+        Ok(self.copy_area_from(other, from, to)?)
+    }
+}
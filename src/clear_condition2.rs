@@ -0,0 +1,343 @@
+//! Super Mario Maker 2 clear conditions.
+//!
+//! The header of a course stores the clear condition as a CRC32 hash,
+//! together with a coarse [SMM2CourseHeader_ClearConditionType] and an amount.
+//! This module maps these hashes to [ClearCondition2].
+
+#[cfg(target_arch = "wasm32")]
+use crate::JsResult;
+use crate::{
+    area2::ObjectId2, constants2::*, errors::Smm2Error,
+    proto::SMM2Course::SMM2CourseHeader_ClearConditionType, Course2, Result,
+};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+use SMM2CourseHeader_ClearConditionType::{ACTION, PARTS, STATUS};
+
+macro_rules! clear_conditions {
+    ($($name:ident = ($hash:literal, $condition_type:ident, $template:literal),)*) => {
+        /// Known clear conditions of Super Mario Maker 2.
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+        #[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum ClearCondition2 {
+            $($name,)*
+        }
+
+        impl ClearCondition2 {
+            pub const ALL: &'static [ClearCondition2] = &[$(ClearCondition2::$name,)*];
+
+            /// CRC32 hash which is stored in the course header.
+            pub fn hash(self) -> u32 {
+                match self {
+                    $(ClearCondition2::$name => $hash,)*
+                }
+            }
+
+            pub fn condition_type(self) -> SMM2CourseHeader_ClearConditionType {
+                match self {
+                    $(ClearCondition2::$name => $condition_type,)*
+                }
+            }
+
+            /// Human readable description, where `{n}` is replaced by the amount.
+            pub fn template(self) -> &'static str {
+                match self {
+                    $(ClearCondition2::$name => $template,)*
+                }
+            }
+        }
+    };
+}
+
+clear_conditions! {
+    NoLanding = (137525990, ACTION, "Reach the goal without landing after leaving the ground"),
+    DefeatMechakoopa = (199585683, PARTS, "Defeat {n} Mechakoopas"),
+    DefeatCheepCheep = (272349836, PARTS, "Defeat {n} Cheep Cheeps"),
+    NoDamage = (375673178, ACTION, "Reach the goal without taking damage"),
+    BoomerangMario = (426197923, STATUS, "Reach the goal as Boomerang Mario"),
+    Shoe = (436833616, STATUS, "Reach the goal while wearing a Shoe"),
+    FireMario = (713979835, STATUS, "Reach the goal as Fire Mario"),
+    FrogMario = (744927294, STATUS, "Reach the goal as Frog Mario"),
+    DefeatLarry = (751004331, PARTS, "Defeat {n} Larrys"),
+    RaccoonMario = (900050759, STATUS, "Reach the goal as Raccoon Mario"),
+    DefeatBlooper = (947659466, PARTS, "Defeat {n} Bloopers"),
+    PropellerMario = (976173462, STATUS, "Reach the goal as Propeller Mario"),
+    PropellerBox = (994686866, STATUS, "Reach the goal while wearing a Propeller Box"),
+    DefeatSpike = (998904081, PARTS, "Defeat {n} Spikes"),
+    DefeatBoomBoom = (1008094897, PARTS, "Defeat {n} Boom Booms"),
+    HoldKoopaShell = (1051433633, ACTION, "Reach the goal while holding a Koopa Shell"),
+    DefeatPorcupuffer = (1061233896, PARTS, "Defeat {n} Porcupuffers"),
+    DefeatCharvaargh = (1062253843, PARTS, "Defeat {n} Charvaarghs"),
+    DefeatBulletBill = (1079889509, PARTS, "Defeat {n} Bullet Bills"),
+    DefeatBully = (1080535886, PARTS, "Defeat {n} Bullies"),
+    GoombaMask = (1151250770, STATUS, "Reach the goal while wearing a Goomba Mask"),
+    DefeatHopChops = (1182464856, PARTS, "Defeat {n} Hop-Chops"),
+    ActivateRedPowBlock = (1219761531, PARTS, "Activate {n} Red POW Blocks"),
+    HoldRedPowBlock = (1219761531, ACTION, "Reach the goal while holding a Red POW Block"),
+    DefeatBobOmb = (1221661152, PARTS, "Defeat {n} Bob-ombs"),
+    DefeatSpiny = (1259427138, PARTS, "Defeat {n} Spinies"),
+    DefeatBowser = (1268255615, PARTS, "Defeat {n} Bowsers/Meowsers"),
+    DefeatAntTrooper = (1279580818, PARTS, "Defeat {n} Ant Troopers"),
+    LakituCloud = (1283945123, STATUS, "Reach the goal on a Lakitu's Cloud"),
+    DefeatBoo = (1344044032, PARTS, "Defeat {n} Boos"),
+    DefeatRoy = (1425973877, PARTS, "Defeat {n} Roys"),
+    HoldTrampoline = (1429902736, ACTION, "Reach the goal while holding a Trampoline"),
+    DefeatMorton = (1431944825, PARTS, "Defeat {n} Mortons"),
+    DefeatFishBone = (1446467058, PARTS, "Defeat {n} Fish Bones"),
+    DefeatMontyMole = (1510495760, PARTS, "Defeat {n} Monty Moles"),
+    PickUpOneUpMushroom = (1656179347, PARTS, "Pick up {n} 1-Up Mushrooms"),
+    DefeatHammerBro = (1665820273, PARTS, "Defeat {n} Hammer Bros."),
+    HitPSwitch = (1676924210, PARTS, "Hit {n} P Switches"),
+    HoldPSwitch = (1676924210, ACTION, "Reach the goal while holding a P Switch"),
+    ActivatePowBlock = (1715960804, PARTS, "Activate {n} POW Blocks"),
+    HoldPowBlock = (1715960804, ACTION, "Reach the goal while holding a POW Block"),
+    DefeatAngrySun = (1724036958, PARTS, "Defeat {n} Angry Suns"),
+    DefeatPokey = (1730095541, PARTS, "Defeat {n} Pokeys"),
+    SuperballMario = (1780278293, STATUS, "Reach the goal as Superball Mario"),
+    DefeatPomPom = (1839897151, PARTS, "Defeat {n} Pom Poms"),
+    DefeatPeepa = (1969299694, PARTS, "Defeat {n} Peepas"),
+    DefeatLakitu = (2035052211, PARTS, "Defeat {n} Lakitus"),
+    DefeatLemmy = (2038503215, PARTS, "Defeat {n} Lemmys"),
+    DefeatLavaBubble = (2048033177, PARTS, "Defeat {n} Lava Bubbles"),
+    BulletBillMask = (2076496776, STATUS, "Reach the goal while wearing a Bullet Bill Mask"),
+    BigMario = (2089161429, STATUS, "Reach the goal as Big Mario"),
+    CatMario = (2111528319, STATUS, "Reach the goal as Cat Mario"),
+    DefeatGoomba = (2131209407, PARTS, "Defeat {n} Goombas/Galoombas"),
+    DefeatThwomp = (2139645066, PARTS, "Defeat {n} Thwomps"),
+    DefeatIggy = (2259346429, PARTS, "Defeat {n} Iggys"),
+    DryBonesShell = (2549654281, STATUS, "Reach the goal while wearing a Dry Bones Shell"),
+    DefeatSledgeBro = (2694559007, PARTS, "Defeat {n} Sledge Bros."),
+    DefeatRockyWrench = (2746139466, PARTS, "Defeat {n} Rocky Wrenches"),
+    GrabFiftyCoin = (2749601092, PARTS, "Grab {n} 50-Coins"),
+    FlyingSquirrelMario = (2855236681, STATUS, "Reach the goal as Flying Squirrel Mario"),
+    BuzzyMario = (3036298571, STATUS, "Reach the goal as Buzzy Mario"),
+    BuilderMario = (3074433106, STATUS, "Reach the goal as Builder Mario"),
+    CapeMario = (3146932243, STATUS, "Reach the goal as Cape Mario"),
+    DefeatWendy = (3174413484, PARTS, "Defeat {n} Wendys"),
+    CannonBox = (3206222275, STATUS, "Reach the goal while wearing a Cannon Box"),
+    Link = (3314955857, STATUS, "Reach the goal as Link"),
+    SuperStar = (3342591980, STATUS, "Reach the goal while you have Super Star invincibility"),
+    DefeatGoombrat = (3346433512, PARTS, "Defeat {n} Goombrats/Goombuds"),
+    GrabTenCoin = (3348058176, PARTS, "Grab {n} 10-Coins"),
+    DefeatBuzzyBeetle = (3353006607, PARTS, "Defeat {n} Buzzy Beetles"),
+    DefeatBowserJr = (3392229961, PARTS, "Defeat {n} Bowser Jr.s"),
+    DefeatKoopaTroopa = (3437308486, PARTS, "Defeat {n} Koopa Troopas"),
+    DefeatChainChomp = (3459144213, PARTS, "Defeat {n} Chain Chomps"),
+    DefeatMuncher = (3466227835, PARTS, "Defeat {n} Munchers"),
+    DefeatWiggler = (3481362698, PARTS, "Defeat {n} Wigglers"),
+    Smb2Mario = (3513732174, STATUS, "Reach the goal as SMB2 Mario"),
+    ClownCar = (3649647177, STATUS, "Reach the goal in a Koopa Clown Car/Junior Clown Car"),
+    SpinyMario = (3725246406, STATUS, "Reach the goal as Spiny Mario"),
+    KoopaTroopaCar = (3730243509, STATUS, "Reach the goal in a Koopa Troopa Car"),
+    DefeatPiranhaPlant = (3748075486, PARTS, "Defeat {n} Piranha Plants/Jumping Piranha Plants"),
+    DefeatDryBones = (3797704544, PARTS, "Defeat {n} Dry Bones"),
+    DefeatStingby = (3824972405, PARTS, "Defeat {n} Stingbies"),
+    DefeatPiranhaCreeper = (3833342952, PARTS, "Defeat {n} Piranha Creepers"),
+    DefeatFirePiranhaPlant = (3842542008, PARTS, "Defeat {n} Fire Piranha Plants"),
+    BreakCrate = (3874680510, PARTS, "Break {n} Crates"),
+    DefeatLudwig = (3974581191, PARTS, "Defeat {n} Ludwigs"),
+    SuperMario = (3977257962, STATUS, "Reach the goal as Super Mario"),
+    DefeatSkipsqueak = (4042480826, PARTS, "Defeat {n} Skipsqueaks"),
+    GrabCoin = (4116396131, PARTS, "Grab {n} Coins"),
+    DefeatMagikoopa = (4117878280, PARTS, "Defeat {n} Magikoopas"),
+    GrabThirtyCoin = (4122555074, PARTS, "Grab {n} 30-Coins"),
+    BalloonMario = (4153835197, STATUS, "Reach the goal as Balloon Mario"),
+    RedPowBox = (4172105156, STATUS, "Reach the goal while wearing a Red POW Box"),
+    Yoshi = (4209535561, STATUS, "Reach the goal while riding Yoshi"),
+    DefeatSpikeTop = (4269094462, PARTS, "Defeat {n} Spike Tops"),
+    DefeatBanzaiBill = (4293354249, PARTS, "Defeat {n} Banzai Bills"),
+}
+
+impl ClearCondition2 {
+    /// Returns `None`, if the hash is unknown.
+    ///
+    /// The type is only needed to tell apart conditions which share a hash,
+    /// like hitting P Switches and holding a P Switch.
+    pub fn from_hash(
+        hash: u32,
+        condition_type: SMM2CourseHeader_ClearConditionType,
+    ) -> Option<ClearCondition2> {
+        let mut candidates = ClearCondition2::ALL
+            .iter()
+            .filter(|condition| condition.hash() == hash);
+        let first = candidates.next().copied();
+        candidates
+            .find(|condition| condition.condition_type() == condition_type)
+            .copied()
+            .or(first)
+    }
+
+    /// Whether this condition has to be fulfilled a given amount of times.
+    pub fn has_amount(self) -> bool {
+        self.condition_type() == PARTS
+    }
+
+    /// Objects this condition refers to.
+    ///
+    /// Empty for conditions which do not refer to objects,
+    /// and for conditions whose objects are variants, e.g. Red POW Blocks, or not known.
+    pub fn objects(self) -> &'static [ObjectId2] {
+        use ClearCondition2::*;
+
+        match self {
+            DefeatMechakoopa => &[ObjectId2::Mechakoopa],
+            DefeatCheepCheep => &[ObjectId2::CheepCheep],
+            FireMario => &[ObjectId2::FireFlower],
+            DefeatLarry => &[ObjectId2::Larry],
+            DefeatBlooper => &[ObjectId2::Blooper],
+            PropellerBox => &[ObjectId2::PropellerBox],
+            DefeatBoomBoom => &[ObjectId2::BoomBoom],
+            DefeatPorcupuffer => &[ObjectId2::Porkupuffer],
+            DefeatCharvaargh => &[ObjectId2::Charvaargh],
+            DefeatBully => &[ObjectId2::Bully],
+            GoombaMask => &[ObjectId2::GoombaMask],
+            DefeatBobOmb => &[ObjectId2::BobOmb],
+            DefeatSpiny => &[ObjectId2::Spiny],
+            DefeatBowser => &[ObjectId2::Bowser],
+            DefeatAntTrooper => &[ObjectId2::AntTrooper],
+            LakituCloud => &[ObjectId2::LakituCloud],
+            DefeatBoo => &[ObjectId2::Boo],
+            DefeatRoy => &[ObjectId2::Roy],
+            HoldTrampoline => &[ObjectId2::Spring, ObjectId2::MushroomTrampoline],
+            DefeatMorton => &[ObjectId2::Morton],
+            DefeatFishBone => &[ObjectId2::FishBone],
+            DefeatMontyMole => &[ObjectId2::MontyMole],
+            PickUpOneUpMushroom => &[ObjectId2::OneUp],
+            DefeatHammerBro => &[ObjectId2::HammerBro],
+            HitPSwitch | HoldPSwitch => &[ObjectId2::PSwitch],
+            ActivatePowBlock | HoldPowBlock => &[ObjectId2::Pow],
+            DefeatAngrySun => &[ObjectId2::AngrySun],
+            DefeatPokey => &[ObjectId2::Pokey],
+            DefeatLakitu => &[ObjectId2::Lakitu],
+            DefeatLemmy => &[ObjectId2::Lemmy],
+            DefeatLavaBubble => &[ObjectId2::LavaBubble],
+            BulletBillMask => &[ObjectId2::BulletBillMask],
+            DefeatGoomba => &[ObjectId2::Goomba],
+            DefeatThwomp => &[ObjectId2::Thwomp],
+            DefeatIggy => &[ObjectId2::Iggy],
+            DefeatRockyWrench => &[ObjectId2::RockyWrench],
+            BuilderMario => &[ObjectId2::SuperHammer],
+            DefeatWendy => &[ObjectId2::Wendy],
+            CannonBox => &[ObjectId2::CannonBox],
+            SuperStar => &[ObjectId2::SuperStar],
+            DefeatBuzzyBeetle => &[ObjectId2::BuzzyBeetle],
+            DefeatBowserJr => &[ObjectId2::BowserJr],
+            DefeatKoopaTroopa | HoldKoopaShell => &[ObjectId2::Koopa],
+            DefeatChainChomp => &[ObjectId2::ChainChomp],
+            DefeatMuncher => &[ObjectId2::Muncher],
+            DefeatWiggler => &[ObjectId2::Wiggler],
+            Smb2Mario => &[ObjectId2::Smb2Mushroom],
+            ClownCar => &[ObjectId2::ClownCar],
+            Shoe => &[ObjectId2::ShoeGoomba],
+            KoopaTroopaCar => &[ObjectId2::KoopaCar],
+            DefeatDryBones => &[ObjectId2::DryBones],
+            DefeatStingby => &[ObjectId2::Stingby],
+            DefeatPiranhaCreeper => &[ObjectId2::PiranhaCreeper],
+            BreakCrate => &[ObjectId2::Crate],
+            DefeatLudwig => &[ObjectId2::Ludwig],
+            SuperMario => &[ObjectId2::SuperMushroom],
+            DefeatSkipsqueak => &[ObjectId2::Skipsqueak],
+            GrabCoin => &[ObjectId2::Coin],
+            DefeatMagikoopa => &[ObjectId2::Magikoopa],
+            RedPowBox => &[ObjectId2::RedPowBox],
+            DefeatSpikeTop => &[ObjectId2::SpikeTop],
+            DefeatBanzaiBill => &[ObjectId2::BanzaiBill],
+            _ => &[],
+        }
+    }
+
+    /// Fill the template with the given amount.
+    pub fn describe(self, amount: u32) -> String {
+        self.template().replace("{n}", &amount.to_string())
+    }
+}
+
+/// A clear condition together with its amount.
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ClearConditionAmount2 {
+    pub condition: ClearCondition2,
+    /// Always zero, if the condition has no amount.
+    pub amount: u32,
+}
+
+impl ClearConditionAmount2 {
+    pub fn describe(&self) -> String {
+        self.condition.describe(self.amount)
+    }
+}
+
+impl Course2 {
+    /// Get the clear condition of this course.
+    ///
+    /// Returns `None`, if the course has no clear condition or its hash is unknown.
+    pub fn get_clear_condition(&self) -> Option<ClearConditionAmount2> {
+        let header = self.get_course().get_header();
+        if header.get_clear_condition_type() == SMM2CourseHeader_ClearConditionType::NONE {
+            return None;
+        }
+        let condition =
+            ClearCondition2::from_hash(header.clear_condition, header.get_clear_condition_type())?;
+        Some(ClearConditionAmount2 {
+            condition,
+            amount: header.clear_condition_amount,
+        })
+    }
+
+    /// Set or remove the clear condition of this course.
+    ///
+    /// The amount is ignored for conditions without amount.
+    /// This fails, if a condition with amount gets an amount of zero or more than [u16::MAX].
+    pub fn set_clear_condition(
+        &mut self,
+        condition: Option<ClearCondition2>,
+        amount: u32,
+    ) -> Result<()> {
+        let (hash, condition_type, amount) = match condition {
+            Some(condition) if condition.has_amount() => {
+                if amount == 0 || amount > u16::MAX as u32 {
+                    return Err(Smm2Error::ClearConditionAmount(amount).into());
+                }
+                (condition.hash(), condition.condition_type(), amount)
+            }
+            Some(condition) => (condition.hash(), condition.condition_type(), 0),
+            None => (0, SMM2CourseHeader_ClearConditionType::NONE, 0),
+        };
+        let header = self.get_course_mut().mut_header();
+        header.set_clear_condition(hash);
+        header.set_clear_condition_type(condition_type);
+        header.set_clear_condition_amount(amount);
+        if self.has_course_data() {
+            let data = self.get_course_data_mut();
+            data[CLEAR_CONDITION_TYPE_OFFSET] = condition_type as u8;
+            data[CLEAR_CONDITION_OFFSET..CLEAR_CONDITION_OFFSET + 4]
+                .copy_from_slice(&hash.to_le_bytes());
+            data[CLEAR_CONDITION_AMOUNT_OFFSET..CLEAR_CONDITION_AMOUNT_OFFSET + 2]
+                .copy_from_slice(&(amount as u16).to_le_bytes());
+        }
+        Ok(())
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Course2 {
+    #[cfg(target_arch = "wasm32")]
+    #[cfg(feature = "with-serde")]
+    #[wasm_bindgen(js_name = getClearCondition)]
+    pub fn get_clear_condition_js(&self) -> JsResult<JsValue> {
+        Ok(serde_wasm_bindgen::to_value(&self.get_clear_condition())?)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = setClearCondition)]
+    pub fn set_clear_condition_js(
+        &mut self,
+        condition: Option<ClearCondition2>,
+        amount: u32,
+    ) -> JsResult<()> {
+        Ok(self.set_clear_condition(condition, amount)?)
+    }
+}
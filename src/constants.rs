@@ -7,6 +7,7 @@ pub const THUMBNAIL_1_NAME: &str = "thumbnail1.tnl";
 
 pub const COURSE_SIZE: usize = 0x15000;
 pub const CRC_LENGTH: usize = 0x10;
+pub const CRC_OFFSET: usize = 0x8;
 
 pub const YEAR_OFFSET: usize = 0x10;
 pub const MONTH_OFFSET: usize = 0x12;
@@ -36,6 +37,7 @@ pub const WIDTH_OFFSET: usize = 0x76;
 pub const TILE_AMOUNT_OFFSET: usize = 0xEE;
 pub const TILE_SIZE: usize = 0x20;
 pub const TILES_OFFSET: usize = 0xF0;
+pub const TILE_MAX: usize = (SOUND_OFFSET - TILES_OFFSET) / TILE_SIZE;
 
 pub const SOUND_SIZE: usize = 8;
 pub const SOUND_OFFSET: usize = 0x145F0;
@@ -46,6 +48,8 @@ pub const SOUND_TYPE_OFFSET: usize = 0;
 pub const SOUND_VARIATION_OFFSET: usize = 2;
 pub const SOUND_DEFAULT: [u8; 8] = [0xFF, 0xFF, 0, 0xFF, 0xFF, 0, 0, 0];
 
+pub const THUMBNAIL_HEADER_SIZE: usize = 8;
+
 //   DS_HEADER_CRC_OFFSET: 0x4F014,
 //   DS_HEADER_LENGTH: 0x1C,
 //   DS_FILE_LENGTH: 0x4301C
@@ -98,4 +98,68 @@ pub const TRACK_COUNT_OFFSET: [usize; 2] = [LEVEL_AREA_OFFSET + 0x40, LEVEL_AREA
 pub const ICICLE_COUNT_OFFSET: [usize; 2] =
     [LEVEL_AREA_OFFSET + 0x44, LEVEL_AREA_SUB_OFFSET + 0x44];
 
+pub const AREA_SIZE: usize = LEVEL_AREA_SUB_OFFSET - LEVEL_AREA_OFFSET;
+
+// level area entities
+pub const OBJECTS_OFFSET: [usize; 2] = [LEVEL_AREA_OFFSET + 0x48, LEVEL_AREA_SUB_OFFSET + 0x48];
+pub const OBJECT_SIZE: usize = 0x20;
+pub const OBJECT_FLAGS_OFFSET: usize = 0xC;
+pub const OBJECT_ID_OFFSET: usize = 0x18;
+pub const OBJECT_CHILD_ID_OFFSET: usize = 0x1A;
+pub const OBJECT_MAX: usize = 2600;
+
+pub const SOUND_EFFECTS_OFFSET: [usize; 2] =
+    [LEVEL_AREA_OFFSET + 0x14548, LEVEL_AREA_SUB_OFFSET + 0x14548];
+pub const SOUND_EFFECT_SIZE: usize = 0x4;
+pub const SOUND_EFFECT_MAX: usize = 300;
+
+pub const SNAKE_BLOCKS_OFFSET: [usize; 2] =
+    [LEVEL_AREA_OFFSET + 0x149F8, LEVEL_AREA_SUB_OFFSET + 0x149F8];
+pub const SNAKE_BLOCK_SIZE: usize = 0x3C4;
+pub const SNAKE_BLOCK_MAX: usize = 5;
+pub const SNAKE_BLOCK_NODE_SIZE: usize = 0x8;
+pub const SNAKE_BLOCK_NODE_MAX: usize = 120;
+
+pub const CLEAR_PIPES_OFFSET: [usize; 2] =
+    [LEVEL_AREA_OFFSET + 0x15CCC, LEVEL_AREA_SUB_OFFSET + 0x15CCC];
+pub const CLEAR_PIPE_SIZE: usize = 0x124;
+pub const CLEAR_PIPE_MAX: usize = 200;
+pub const CLEAR_PIPE_NODE_SIZE: usize = 0x8;
+pub const CLEAR_PIPE_NODE_MAX: usize = 36;
+
+pub const PIRANHA_CREEPERS_OFFSET: [usize; 2] =
+    [LEVEL_AREA_OFFSET + 0x240EC, LEVEL_AREA_SUB_OFFSET + 0x240EC];
+pub const PIRANHA_CREEPER_SIZE: usize = 0x54;
+pub const PIRANHA_CREEPER_MAX: usize = 10;
+pub const PIRANHA_CREEPER_NODE_MAX: usize = 20;
+
+pub const EXCLAMATION_BLOCKS_OFFSET: [usize; 2] =
+    [LEVEL_AREA_OFFSET + 0x24434, LEVEL_AREA_SUB_OFFSET + 0x24434];
+pub const EXCLAMATION_BLOCK_SIZE: usize = 0x2C;
+pub const EXCLAMATION_BLOCK_MAX: usize = 10;
+
+pub const TRACK_BLOCKS_OFFSET: [usize; 2] =
+    [LEVEL_AREA_OFFSET + 0x245EC, LEVEL_AREA_SUB_OFFSET + 0x245EC];
+pub const TRACK_BLOCK_SIZE: usize = 0x2C;
+pub const TRACK_BLOCK_MAX: usize = 10;
+
+/// Exclamation blocks and track blocks share the same node layout.
+pub const MOVING_BLOCK_NODE_MAX: usize = 10;
+pub const PATH_NODE_SIZE: usize = 0x4;
+
+pub const GROUND_OFFSET: [usize; 2] =
+    [LEVEL_AREA_OFFSET + 0x247A4, LEVEL_AREA_SUB_OFFSET + 0x247A4];
+pub const GROUND_SIZE: usize = 0x4;
+pub const GROUND_MAX: usize = 4000;
+
+pub const TRACKS_OFFSET: [usize; 2] =
+    [LEVEL_AREA_OFFSET + 0x28624, LEVEL_AREA_SUB_OFFSET + 0x28624];
+pub const TRACK_SIZE: usize = 0xC;
+pub const TRACK_MAX: usize = 1500;
+
+pub const ICICLES_OFFSET: [usize; 2] =
+    [LEVEL_AREA_OFFSET + 0x2CC74, LEVEL_AREA_SUB_OFFSET + 0x2CC74];
+pub const ICICLE_SIZE: usize = 0x4;
+pub const ICICLE_MAX: usize = 300;
+
 pub const SAVE_COURSE_OFFSET: u64 = 0xB920;
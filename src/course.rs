@@ -30,7 +30,7 @@ use zip::ZipArchive;
 ///
 /// This struct is a wrapper to hold a [SMMCourse](crate::proto::SMMCourse) struct which can be serialized via Protocol Buffer.
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Course {
     course: SMMCourse,
 }
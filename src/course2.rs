@@ -5,6 +5,7 @@
 #[cfg(target_arch = "wasm32")]
 use crate::JsResult;
 use crate::{
+    area2::{Area2, AreaType, EntityTable2},
     constants2::*,
     decrypt, encrypt,
     errors::{Smm2Error, Smm2Result},
@@ -77,6 +78,23 @@ impl Course2 {
         self.thumb.as_mut()
     }
 
+    /// Decode all entities of the given area.
+    ///
+    /// This fails, if the course has no decrypted course data,
+    /// e.g. because it has been created from a proto.
+    pub fn get_area(&self, area: AreaType) -> Result<Area2> {
+        if !self.has_course_data() {
+            return Err(Smm2Error::CourseDataRequired.into());
+        }
+        Ok(Area2::from_course_data(&self.data, area))
+    }
+
+    /// Returns `false` if the course has no decrypted course data,
+    /// e.g. because it has been created from a proto.
+    pub fn has_course_data(&self) -> bool {
+        self.data.len() >= 0x5bfd0
+    }
+
     pub fn as_zip(&self) -> Result<Vec<u8>> {
         if let Some(thumb) = &self.thumb {
             let buffer = Cursor::new(vec![]);
@@ -156,38 +174,89 @@ impl Course2 {
     /// Reset everything related to clear checks that the game saved.
     /// Afterwards the course can no longer be uploaded.
     pub fn reset_clear_check(&mut self) {
-        let management_flags_lower_byte: u8 = {
-            let header = self.get_course_mut().mut_header();
-
-            let mut management_flags = header.get_management_flags();
-            management_flags -= 2;
-            header.set_management_flags(management_flags);
-
-            header.set_clear_check_tries(0);
-            header.set_clear_check_time(4294967295);
+        self.set_cleared(false);
+
+        let header = self.get_course_mut().mut_header();
+        header.set_clear_check_tries(0);
+        header.set_clear_check_time(4294967295);
+
+        if self.has_course_data() {
+            self.data.splice(
+                CLEAR_CHECK_TRIES_OFFSET..CLEAR_CHECK_TRIES_OFFSET + 4,
+                vec![0, 0, 0, 0],
+            );
+            self.data.splice(
+                CLEAR_CHECK_TIME_OFFSET..CLEAR_CHECK_TIME_OFFSET + 4,
+                vec![0xff, 0xff, 0xff, 0xff],
+            );
+        }
+    }
 
-            (management_flags & 0xff) as u8
-        };
+    /// Recompute the count fields of all entity tables from the used table entries
+    /// and update the area settings accordingly.
+    ///
+    /// Count fields are only recomputed on request, either by this method or by encrypting
+    /// with [`Course2::encrypt_recounted`], so counts which have been set on purpose are kept.
+    pub fn recount(&mut self) -> Result<()> {
+        if !self.has_course_data() {
+            return Err(Smm2Error::CourseDataRequired.into());
+        }
+        Course2::recount_course_data(&mut self.data);
+        for area in AreaType::ALL {
+            let counts: Vec<u32> = EntityTable2::ALL
+                .iter()
+                .map(|table| table.read_count(&self.data, area))
+                .collect();
+            let settings = match area {
+                AreaType::Main => self.course.mut_course_area(),
+                AreaType::Sub => self.course.mut_course_sub_area(),
+            };
+            for (table, count) in EntityTable2::ALL.iter().zip(counts) {
+                match table {
+                    EntityTable2::Objects => settings.set_object_count(count),
+                    EntityTable2::SoundEffects => settings.set_sound_effect_count(count),
+                    EntityTable2::SnakeBlocks => settings.set_snake_block_count(count),
+                    EntityTable2::ClearPipes => settings.set_clear_pipe_count(count),
+                    EntityTable2::PiranhaCreepers => settings.set_piranha_creeper_count(count),
+                    EntityTable2::ExclamationBlocks => settings.set_exclamation_block_count(count),
+                    EntityTable2::TrackBlocks => settings.set_track_block_count(count),
+                    EntityTable2::Ground => settings.set_tile_count(count),
+                    EntityTable2::Tracks => settings.set_track_count(count),
+                    EntityTable2::Icicles => settings.set_icicle_count(count),
+                }
+            }
+        }
+        Ok(())
+    }
 
-        self.data[MANAGEMENT_FLAGS_OFFSET] = management_flags_lower_byte;
-        self.data.splice(
-            CLEAR_CHECK_TRIES_OFFSET..CLEAR_CHECK_TRIES_OFFSET + 4,
-            vec![0, 0, 0, 0],
-        );
-        self.data.splice(
-            CLEAR_CHECK_TIME_OFFSET..CLEAR_CHECK_TIME_OFFSET + 4,
-            vec![0xff, 0xff, 0xff, 0xff],
-        );
+    pub(crate) fn recount_course_data(course: &mut [u8]) {
+        for area in AreaType::ALL {
+            for table in EntityTable2::ALL {
+                let count = table.count_entries(course, area);
+                let offset = table.count_offset(area);
+                course[offset..offset + 4].copy_from_slice(&count.to_le_bytes());
+            }
+        }
     }
 
     #[cfg(not(target_arch = "wasm32"))]
     pub fn encrypt(course: &mut Vec<u8>) {
-        Course2::encrypt_vec(course);
+        Course2::encrypt_vec(course, false);
     }
 
-    fn encrypt_vec(course: &mut Vec<u8>) {
+    /// Recompute the count fields of all entity tables like [`Course2::recount`]
+    /// and encrypt the course data afterwards.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn encrypt_recounted(course: &mut Vec<u8>) {
+        Course2::encrypt_vec(course, true);
+    }
+
+    pub(crate) fn encrypt_vec(course: &mut Vec<u8>, recount: bool) {
         let preserved_aes = course.len() == 0x5c000;
         let len = 0x5bfd0;
+        if recount {
+            Course2::recount_course_data(&mut course[..len]);
+        }
         fix_crc32(&mut course[..len]);
         let aes_info = encrypt(&mut course[0x10..len], &COURSE_KEY_TABLE);
         if preserved_aes {
@@ -328,7 +397,15 @@ impl Course2 {
     #[wasm_bindgen]
     pub fn encrypt(course: &[u8]) -> Box<[u8]> {
         let mut course = course.to_vec();
-        Course2::encrypt_vec(&mut course);
+        Course2::encrypt_vec(&mut course, false);
+        course.into_boxed_slice()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = encryptRecounted)]
+    pub fn encrypt_recounted(course: &[u8]) -> Box<[u8]> {
+        let mut course = course.to_vec();
+        Course2::encrypt_vec(&mut course, true);
         course.into_boxed_slice()
     }
 
@@ -493,7 +570,7 @@ impl Course2 {
         None
     }
 
-    fn _from_switch_files(
+    pub(crate) fn _from_switch_files(
         data: &mut [u8],
         thumb: Option<Vec<u8>>,
         is_encrypted: bool,
@@ -548,7 +625,9 @@ impl Course2 {
         })
     }
 
-    fn get_course_header(course_data: &[u8]) -> Smm2Result<SingularPtrField<SMM2CourseHeader>> {
+    pub(crate) fn get_course_header(
+        course_data: &[u8],
+    ) -> Smm2Result<SingularPtrField<SMM2CourseHeader>> {
         let modified = Course2::get_modified(course_data)?;
         let title =
             Course2::get_utf16_string_from_slice(&course_data[TITLE_OFFSET..TITLE_OFFSET_END]);
@@ -651,7 +730,7 @@ impl Course2 {
         }))
     }
 
-    fn get_course_area(
+    pub(crate) fn get_course_area(
         course_data: &[u8],
         const_index: usize,
     ) -> Smm2Result<SingularPtrField<SMM2CourseArea>> {
@@ -847,6 +926,16 @@ impl Course2 {
             _ => Err(Smm2Error::GameStyleParse),
         }
     }
+
+    pub(crate) fn get_game_style_str(game_style: SMM2CourseHeader_GameStyle) -> &'static str {
+        match game_style {
+            SMM2CourseHeader_GameStyle::M1 => "M1",
+            SMM2CourseHeader_GameStyle::M3 => "M3",
+            SMM2CourseHeader_GameStyle::MW => "MW",
+            SMM2CourseHeader_GameStyle::WU => "WU",
+            SMM2CourseHeader_GameStyle::W3 => "3W",
+        }
+    }
 }
 
 impl TryFrom<Vec<u8>> for Course2 {
@@ -0,0 +1,174 @@
+//! Course and maker codes of Super Mario Maker 2, as they are shared between players.
+//!
+//! A code consists of nine characters like `XXX-XXX-XXX` from a base 30 alphabet.
+//! It encodes the data ID of a course, which is stored as
+//! [upload_id](crate::proto::SMM2Course::SMM2CourseHeader::upload_id),
+//! or of a maker, together with a checksum of the data ID.
+//!
+//! The course IDs of Super Mario Maker are not supported, because their checksum is unknown.
+
+#[cfg(target_arch = "wasm32")]
+use crate::JsResult;
+use crate::{Error, Result};
+
+use std::{fmt, str::FromStr};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+/// Alphabet of codes, which excludes vowels and easily confused letters.
+const CODE_ALPHABET: &[u8; 30] = b"0123456789BCDFGHJKLMNPQRSTVWXY";
+
+const CODE_LENGTH: usize = 9;
+
+/// Fixed value of the four most significant bits of every code.
+const CODE_PREFIX: u64 = 8;
+
+/// Data IDs are scrambled with this mask before they get encoded.
+const DATA_ID_MASK: u32 = 0b0001_0110_1000_0000_1110_0000_0111_1100;
+
+/// Whether a [CourseCode2] refers to a course or to a maker.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CodeType2 {
+    Course,
+    Maker,
+}
+
+/// A Super Mario Maker 2 course or maker code.
+///
+/// The 44 bit value of a code holds from the most significant bit:
+///
+/// | Bits | Content                                   |
+/// |------|-------------------------------------------|
+/// | 4    | always 8                                  |
+/// | 6    | checksum, the data ID minus 31 modulo 64  |
+/// | 1    | 1 for maker codes                         |
+/// | 1    | always 1                                  |
+/// | 32   | data ID xor a mask, rotated left by 14    |
+///
+/// Its base 30 digits are written with the least significant digit first,
+/// which is why every code ends with `F`, `G` or `H`.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CourseCode2 {
+    data_id: u32,
+    code_type: CodeType2,
+}
+
+impl CourseCode2 {
+    pub fn new(data_id: u32, code_type: CodeType2) -> CourseCode2 {
+        CourseCode2 { data_id, code_type }
+    }
+
+    /// Code of an uploaded course.
+    ///
+    /// Fails with [`Error::InvalidCourseId`], if the ID does not fit into 32 bits.
+    pub fn from_upload_id(upload_id: u64) -> Result<CourseCode2> {
+        let data_id =
+            u32::try_from(upload_id).map_err(|_| Error::InvalidCourseId(upload_id.to_string()))?;
+        Ok(CourseCode2::new(data_id, CodeType2::Course))
+    }
+
+    fn checksum(data_id: u32) -> u64 {
+        // 2^32 is a multiple of 64, so wrapping keeps the modulo of the real difference
+        (data_id.wrapping_sub(31) % 64) as u64
+    }
+
+    fn value(&self) -> u64 {
+        let data_id = (self.data_id ^ DATA_ID_MASK).rotate_left(14);
+        CODE_PREFIX << 40
+            | CourseCode2::checksum(self.data_id) << 34
+            | ((self.code_type == CodeType2::Maker) as u64) << 33
+            | 1 << 32
+            | data_id as u64
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl CourseCode2 {
+    /// ID of the course or maker on the Nintendo servers.
+    pub fn get_data_id(&self) -> u32 {
+        self.data_id
+    }
+
+    pub fn get_code_type(&self) -> CodeType2 {
+        self.code_type
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(constructor)]
+    pub fn new_js(data_id: u32, code_type: CodeType2) -> CourseCode2 {
+        CourseCode2::new(data_id, code_type)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = parse)]
+    pub fn parse_js(code: &str) -> JsResult<CourseCode2> {
+        Ok(code.parse()?)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_string_js(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl FromStr for CourseCode2 {
+    type Err = Error;
+
+    /// Parse a code with or without dashes.
+    ///
+    /// Lower case letters are accepted.
+    /// Fails with [`Error::InvalidCourseId`], if the code contains other characters
+    /// or its checksum does not match.
+    /// The checksum only covers six bits of the data ID, so not every typo is detected.
+    fn from_str(code: &str) -> Result<CourseCode2> {
+        let invalid = || Error::InvalidCourseId(code.to_string());
+        let digits: Vec<u8> = code
+            .bytes()
+            .filter(|byte| *byte != b'-')
+            .map(|byte| byte.to_ascii_uppercase())
+            .collect();
+        if digits.len() != CODE_LENGTH {
+            return Err(invalid());
+        }
+        let mut value = 0;
+        for digit in digits.into_iter().rev() {
+            let digit = CODE_ALPHABET
+                .iter()
+                .position(|byte| *byte == digit)
+                .ok_or_else(invalid)?;
+            value = value * CODE_ALPHABET.len() as u64 + digit as u64;
+        }
+
+        let data_id = (value as u32).rotate_right(14) ^ DATA_ID_MASK;
+        if value >> 40 != CODE_PREFIX
+            || (value >> 34) & 0x3F != CourseCode2::checksum(data_id)
+            || (value >> 32) & 1 != 1
+        {
+            return Err(invalid());
+        }
+        let code_type = if (value >> 33) & 1 == 1 {
+            CodeType2::Maker
+        } else {
+            CodeType2::Course
+        };
+        Ok(CourseCode2::new(data_id, code_type))
+    }
+}
+
+impl fmt::Display for CourseCode2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut digits = [0; CODE_LENGTH];
+        let mut value = self.value();
+        for digit in digits.iter_mut() {
+            *digit = CODE_ALPHABET[(value % CODE_ALPHABET.len() as u64) as usize];
+            value /= CODE_ALPHABET.len() as u64;
+        }
+        let digits = String::from_utf8_lossy(&digits);
+        write!(f, "{}-{}-{}", &digits[..3], &digits[3..6], &digits[6..])
+    }
+}
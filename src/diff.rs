@@ -0,0 +1,293 @@
+//! Structural diff between two Super Mario Maker courses.
+//!
+//! Also contains types which are shared with [diff2](crate::diff2).
+
+#[cfg(all(target_arch = "wasm32", feature = "with-serde"))]
+use crate::JsResult;
+use crate::{
+    area::Object,
+    area2::AreaType,
+    proto::{SMMCourse::SMMCourse, Sound::Sound},
+    Course,
+};
+
+use std::{collections::HashMap, fmt::Debug, hash::Hash};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+/// A single field which differs between two courses.
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: String,
+    pub new: String,
+}
+
+impl FieldChange {
+    pub(crate) fn new(field: &str, old: String, new: String) -> FieldChange {
+        FieldChange {
+            field: field.to_string(),
+            old,
+            new,
+        }
+    }
+
+    pub(crate) fn from_debug<T: Debug>(field: &str, old: &T, new: &T) -> FieldChange {
+        FieldChange::new(field, format!("{:?}", old), format!("{:?}", new))
+    }
+}
+
+macro_rules! diff_fields {
+    ($changes:ident, $old:ident, $new:ident, $($field:ident),* $(,)?) => {
+        $(
+            if $old.$field != $new.$field {
+                $changes.push(FieldChange::from_debug(
+                    stringify!($field),
+                    &$old.$field,
+                    &$new.$field,
+                ));
+            }
+        )*
+    };
+}
+
+pub(crate) use diff_fields;
+
+/// An entity which kept all of its properties except its position.
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Moved<T> {
+    pub from: T,
+    pub to: T,
+}
+
+/// Added, removed and moved entities of a single entity table.
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntityDiff<T> {
+    pub added: Vec<T>,
+    pub removed: Vec<T>,
+    pub moved: Vec<Moved<T>>,
+}
+
+impl<T> EntityDiff<T> {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.moved.is_empty()
+    }
+}
+
+/// Entities with a position inside of a course area.
+///
+/// Two entities are considered equal, if both their key and their position are equal.
+pub trait Positioned {
+    /// Everything that identifies this entity except its position.
+    type Key: Eq + Hash;
+
+    fn key(&self) -> Self::Key;
+
+    fn position(&self) -> (i32, i32);
+}
+
+/// Compute added, removed and moved entities between two entity tables.
+///
+/// Entities which exist unchanged in both tables are ignored, regardless of their order.
+/// A removed and an added entity with the same [key](Positioned::key) are reported as moved.
+/// If multiple candidates exist, the nearest one is picked.
+pub(crate) fn diff_entities<T: Positioned + Clone>(old: &[T], new: &[T]) -> EntityDiff<T> {
+    let mut removed: Vec<Option<T>> = unmatched(old, new).into_iter().map(Some).collect();
+    let mut removed_by_key: HashMap<T::Key, Vec<usize>> = HashMap::new();
+    for (index, entity) in removed.iter().enumerate() {
+        if let Some(entity) = entity {
+            removed_by_key.entry(entity.key()).or_default().push(index);
+        }
+    }
+
+    let mut added = vec![];
+    let mut moved = vec![];
+    for entity in unmatched(new, old) {
+        let candidates = removed_by_key.get_mut(&entity.key());
+        let nearest = candidates.and_then(|candidates| {
+            let (position, _) = candidates.iter().enumerate().min_by_key(|(_, index)| {
+                let from = removed[**index].as_ref().unwrap();
+                distance(from.position(), entity.position())
+            })?;
+            Some(candidates.remove(position))
+        });
+        match nearest {
+            Some(index) => moved.push(Moved {
+                from: removed[index].take().unwrap(),
+                to: entity,
+            }),
+            None => added.push(entity),
+        }
+    }
+
+    EntityDiff {
+        added,
+        removed: removed.into_iter().flatten().collect(),
+        moved,
+    }
+}
+
+/// Returns all entities of `entities` which have no equal counterpart in `other`.
+fn unmatched<T: Positioned + Clone>(entities: &[T], other: &[T]) -> Vec<T> {
+    let mut counts: HashMap<(T::Key, (i32, i32)), usize> = HashMap::new();
+    for entity in other {
+        *counts.entry((entity.key(), entity.position())).or_default() += 1;
+    }
+    entities
+        .iter()
+        .filter(
+            |entity| match counts.get_mut(&(entity.key(), entity.position())) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            },
+        )
+        .cloned()
+        .collect()
+}
+
+fn distance(from: (i32, i32), to: (i32, i32)) -> i64 {
+    let dx = (from.0 - to.0) as i64;
+    let dy = (from.1 - to.1) as i64;
+    dx * dx + dy * dy
+}
+
+/// Differences between two Super Mario Maker courses.
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CourseDiff {
+    pub metadata: Vec<FieldChange>,
+    pub areas: Vec<AreaDiff>,
+}
+
+impl CourseDiff {
+    /// Returns `true` if both courses are structurally identical.
+    pub fn is_empty(&self) -> bool {
+        self.metadata.is_empty() && self.areas.iter().all(AreaDiff::is_empty)
+    }
+}
+
+/// Differences of a single course area.
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct AreaDiff {
+    pub area: AreaType,
+    pub objects: EntityDiff<Object>,
+    pub sounds: EntityDiff<Sound>,
+}
+
+impl AreaDiff {
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty() && self.sounds.is_empty()
+    }
+}
+
+impl Positioned for Object {
+    type Key = Object;
+
+    fn key(&self) -> Object {
+        Object {
+            x: 0,
+            y: 0,
+            ..self.clone()
+        }
+    }
+
+    fn position(&self) -> (i32, i32) {
+        (self.x as i32, self.y as i32)
+    }
+}
+
+impl Positioned for Sound {
+    type Key = (u32, bool);
+
+    fn key(&self) -> (u32, bool) {
+        (self.sound_type, self.variation)
+    }
+
+    fn position(&self) -> (i32, i32) {
+        (self.x as i32, self.y as i32)
+    }
+}
+
+impl Course {
+    /// Compute a structural diff from this course to another course.
+    ///
+    /// Metadata is compared field by field.
+    /// Tiles are decoded into [objects](Object) and reported as added, removed or moved,
+    /// just like sounds.
+    pub fn diff(&self, other: &Course) -> CourseDiff {
+        let metadata = Course::diff_metadata(self.get_course(), other.get_course());
+        let areas = AreaType::ALL
+            .iter()
+            .map(|&area| AreaDiff {
+                area,
+                objects: diff_entities(&self.get_objects(area), &other.get_objects(area)),
+                sounds: diff_entities(self.get_area_sounds(area), other.get_area_sounds(area)),
+            })
+            .collect();
+        CourseDiff { metadata, areas }
+    }
+
+    fn diff_metadata(old: &SMMCourse, new: &SMMCourse) -> Vec<FieldChange> {
+        let mut changes = vec![];
+        if old.title != new.title {
+            changes.push(FieldChange::new(
+                "title",
+                old.title.clone(),
+                new.title.clone(),
+            ));
+        }
+        if old.maker != new.maker {
+            changes.push(FieldChange::new(
+                "maker",
+                old.maker.clone(),
+                new.maker.clone(),
+            ));
+        }
+        diff_fields!(
+            changes,
+            old,
+            new,
+            modified,
+            game_style,
+            course_theme,
+            course_theme_sub,
+            time,
+            auto_scroll,
+            auto_scroll_sub,
+            width,
+            width_sub,
+        );
+        if old.thumbnail != new.thumbnail {
+            changes.push(FieldChange::new(
+                "thumbnail",
+                format!("{} bytes", old.thumbnail.len()),
+                format!("{} bytes", new.thumbnail.len()),
+            ));
+        }
+        if old.thumbnail_preview != new.thumbnail_preview {
+            changes.push(FieldChange::new(
+                "thumbnail_preview",
+                format!("{} bytes", old.thumbnail_preview.len()),
+                format!("{} bytes", new.thumbnail_preview.len()),
+            ));
+        }
+        changes
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Course {
+    #[cfg(target_arch = "wasm32")]
+    #[cfg(feature = "with-serde")]
+    #[wasm_bindgen(js_name = diff)]
+    pub fn diff_js(&self, other: &Course) -> JsResult<JsValue> {
+        Ok(serde_wasm_bindgen::to_value(&self.diff(other))?)
+    }
+}
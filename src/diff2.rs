@@ -0,0 +1,182 @@
+//! Structural diff between two Super Mario Maker 2 courses.
+
+#[cfg(all(target_arch = "wasm32", feature = "with-serde"))]
+use crate::JsResult;
+use crate::{
+    area2::{AreaType, Ground2, Object2},
+    diff::{diff_entities, diff_fields, EntityDiff, FieldChange, Positioned},
+    proto::SMM2Course::{SMM2CourseArea, SMM2CourseHeader},
+    Course2, Result,
+};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+/// Differences between two Super Mario Maker 2 courses.
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Course2Diff {
+    pub header: Vec<FieldChange>,
+    pub areas: Vec<AreaDiff2>,
+}
+
+impl Course2Diff {
+    /// Returns `true` if both courses are structurally identical.
+    pub fn is_empty(&self) -> bool {
+        self.header.is_empty() && self.areas.iter().all(AreaDiff2::is_empty)
+    }
+}
+
+/// Differences of a single course area.
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AreaDiff2 {
+    pub area: AreaType,
+    pub settings: Vec<FieldChange>,
+    pub objects: EntityDiff<Object2>,
+    pub ground: EntityDiff<Ground2>,
+}
+
+impl AreaDiff2 {
+    pub fn is_empty(&self) -> bool {
+        self.settings.is_empty() && self.objects.is_empty() && self.ground.is_empty()
+    }
+}
+
+impl Positioned for Object2 {
+    type Key = Object2;
+
+    fn key(&self) -> Object2 {
+        Object2 {
+            x: 0,
+            y: 0,
+            ..self.clone()
+        }
+    }
+
+    fn position(&self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+}
+
+impl Positioned for Ground2 {
+    type Key = (u8, u8);
+
+    fn key(&self) -> (u8, u8) {
+        (self.id, self.background_id)
+    }
+
+    fn position(&self) -> (i32, i32) {
+        (self.x as i32, self.y as i32)
+    }
+}
+
+impl Course2 {
+    /// Compute a structural diff from this course to another course.
+    ///
+    /// Header and area settings are compared field by field.
+    /// Objects and ground tiles are reported as added, removed or moved.
+    ///
+    /// This fails, if one of the courses has no decrypted course data.
+    pub fn diff(&self, other: &Course2) -> Result<Course2Diff> {
+        let header = Course2::diff_header(
+            self.get_course().get_header(),
+            other.get_course().get_header(),
+        );
+        let mut areas = vec![];
+        for area in AreaType::ALL {
+            let old = self.get_area(area)?;
+            let new = other.get_area(area)?;
+            areas.push(AreaDiff2 {
+                area,
+                settings: Course2::diff_area_settings(
+                    self.get_area_settings(area),
+                    other.get_area_settings(area),
+                ),
+                objects: diff_entities(&old.objects, &new.objects),
+                ground: diff_entities(&old.ground, &new.ground),
+            });
+        }
+        Ok(Course2Diff { header, areas })
+    }
+
+    pub(crate) fn get_area_settings(&self, area: AreaType) -> &SMM2CourseArea {
+        match area {
+            AreaType::Main => self.get_course().get_course_area(),
+            AreaType::Sub => self.get_course().get_course_sub_area(),
+        }
+    }
+
+    fn diff_header(old: &SMM2CourseHeader, new: &SMM2CourseHeader) -> Vec<FieldChange> {
+        let mut changes = vec![];
+        if old.title != new.title {
+            changes.push(FieldChange::new(
+                "title",
+                old.title.clone(),
+                new.title.clone(),
+            ));
+        }
+        if old.description != new.description {
+            changes.push(FieldChange::new(
+                "description",
+                old.description.clone(),
+                new.description.clone(),
+            ));
+        }
+        diff_fields!(
+            changes,
+            old,
+            new,
+            modified,
+            start_y,
+            finish_y,
+            finish_x,
+            time,
+            game_style,
+            clear_condition_type,
+            clear_condition,
+            clear_condition_amount,
+            clear_check_tries,
+            clear_check_time,
+            game_version,
+            management_flags,
+            creation_id,
+            upload_id,
+            completion_version,
+        );
+        changes
+    }
+
+    fn diff_area_settings(old: &SMM2CourseArea, new: &SMM2CourseArea) -> Vec<FieldChange> {
+        let mut changes = vec![];
+        diff_fields!(
+            changes,
+            old,
+            new,
+            course_theme,
+            auto_scroll,
+            screen_boundary,
+            orientation,
+            liquid_max,
+            liquid_mode,
+            liquid_speed,
+            liquid_min,
+            right_boundary,
+            top_boundary,
+            left_boundary,
+            bottom_boundary,
+            day_time,
+        );
+        changes
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Course2 {
+    #[cfg(target_arch = "wasm32")]
+    #[cfg(feature = "with-serde")]
+    #[wasm_bindgen(js_name = diff)]
+    pub fn diff_js(&self, other: &Course2) -> JsResult<JsValue> {
+        Ok(serde_wasm_bindgen::to_value(&self.diff(other)?)?)
+    }
+}
@@ -67,9 +67,14 @@ pub fn encrypt(bytes: &mut [u8], key_table: &[u32]) -> Vec<u8> {
     aes_info
 }
 
-pub fn fix_crc32(data: &mut [u8]) {
+/// Checksum of `data`, which is stored at offset 0x8 by [`fix_crc32`].
+pub fn calc_crc32(data: &[u8]) -> u32 {
     let crc = Crc::<u32>::new(&CRC_32_ISO_HDLC);
-    let checksum = crc.checksum(&data[0x10..]);
+    crc.checksum(&data[0x10..])
+}
+
+pub fn fix_crc32(data: &mut [u8]) {
+    let checksum = calc_crc32(data);
     let bytes: [u8; 4] = checksum.to_le().to_ne_bytes();
     data[0x8] = bytes[0];
     data[0x9] = bytes[1];
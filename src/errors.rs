@@ -1,5 +1,9 @@
 //! Module which contains error types.
 
+use crate::area2::EntityTable2;
+#[cfg(feature = "save")]
+use crate::save_slot::{SaveSection, SaveSlot};
+
 use image::ImageError;
 use std::io;
 use thiserror::Error;
@@ -30,6 +34,8 @@ pub enum SmmdbError {
     SaveError(#[from] SaveError),
     #[error(transparent)]
     FromHex(#[from] hex::FromHexError),
+    #[error("Invalid course ID {0}")]
+    InvalidCourseId(String),
 }
 
 impl From<SmmdbError> for String {
@@ -56,6 +62,8 @@ pub enum SmmError {
 pub enum Smm2Error {
     #[error("Thumbnail required")]
     ThumbnailRequired,
+    #[error("Course data required")]
+    CourseDataRequired,
     #[error("CMAC wrong.\nExpected: {expected:?}\nReceived: {received:?}")]
     CmacWrong {
         expected: Vec<u8>,
@@ -95,6 +103,22 @@ pub enum Smm2Error {
     StringTooLong(usize),
     #[error("Header data seems to be empty")]
     HeaderDataEmpty,
+    #[error("Clear condition amount must be between 1 and 65535. Received: {0}")]
+    ClearConditionAmount(u32),
+    #[error("Timestamp {0} can not be stored in a course")]
+    InvalidTimestamp(u64),
+    #[error("Entry {index} of {table:?} would be moved out of the area")]
+    EntityOutOfBounds { table: EntityTable2, index: usize },
+    #[error("Start or goal would be moved out of the main area")]
+    StartGoalOutOfBounds,
+    #[error("The main area can not be vertical")]
+    VerticalMainArea,
+    #[error("Area width of {0} tiles is not allowed")]
+    InvalidAreaWidth(u32),
+    #[error("{0:?} can not be mirrored")]
+    MirrorUnsupported(EntityTable2),
+    #[error("Course files can not be read: {0}")]
+    CourseFilesUnreadable(String),
 }
 
 #[cfg(feature = "save")]
@@ -102,12 +126,20 @@ pub enum Smm2Error {
 pub enum SaveError {
     #[error("index must be between 0 and 180, but received {0}")]
     CourseIndexOutOfBounds(u8),
-    #[error("no course found at index {0}")]
-    CourseNotFound(u8),
+    #[error("index of a slot in {0} must be below 60, but received {1}")]
+    SlotIndexOutOfBounds(SaveSection, u8),
+    #[error("no course found in {0}")]
+    CourseNotFound(SaveSlot),
     #[error("thumbnail is missing for course {0}")]
     ThumbnailRequired(String),
     #[error("cannot add corrupted course {0}")]
     CorruptedCourse(Smm2Error),
+    #[error("no backup found at {0}")]
+    BackupNotFound(String),
+    #[error("only the newest backup can be restored, but received {0}")]
+    BackupNotNewest(String),
+    #[error("an earlier save has been interrupted and has to be recovered first")]
+    InterruptedSave,
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -0,0 +1,110 @@
+//! Canonical content fingerprints for duplicate detection.
+//!
+//! Encrypting a course generates a random IV and seed, so two exports of the same course never match byte for byte.
+//! A fingerprint is instead computed over the decrypted course content.
+
+#[cfg(target_arch = "wasm32")]
+use crate::JsResult;
+use crate::{constants2::*, errors::Smm2Error, Course, Course2, ManagementFlags2, Result};
+
+use protobuf::Message;
+use sha2::{Digest, Sha256};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+/// Controls which parts of a course are excluded from its fingerprint.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FingerprintOptions {
+    /// Ignore the "last modified" timestamp.
+    pub ignore_modified: bool,
+    /// Ignore clear check tries, clear check time and the cleared management flag.
+    ///
+    /// Super Mario Maker 2 only.
+    pub ignore_clear_check: bool,
+    /// Ignore the SMMDB ID embedded in the course header.
+    ///
+    /// Super Mario Maker 2 only.
+    pub ignore_smmdb_id: bool,
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl FingerprintOptions {
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(constructor))]
+    pub fn new(ignore_modified: bool, ignore_clear_check: bool, ignore_smmdb_id: bool) -> Self {
+        FingerprintOptions {
+            ignore_modified,
+            ignore_clear_check,
+            ignore_smmdb_id,
+        }
+    }
+}
+
+impl Course2 {
+    /// Compute a SHA-256 hex digest over the decrypted course data.
+    ///
+    /// The file header including its CRC and the encryption trailer are never part of the fingerprint.
+    ///
+    /// This fails, if the course has no decrypted course data.
+    pub fn fingerprint(&self, options: FingerprintOptions) -> Result<String> {
+        let data = self.get_canonical_data(options)?;
+        Ok(hex::encode(Sha256::digest(data)))
+    }
+
+    fn get_canonical_data(&self, options: FingerprintOptions) -> Result<Vec<u8>> {
+        if !self.has_course_data() {
+            return Err(Smm2Error::CourseDataRequired.into());
+        }
+        let mut data = self.get_course_data().to_vec();
+
+        if options.ignore_modified {
+            data[YEAR_OFFSET..=MINUTE_OFFSET].fill(0);
+        }
+        if options.ignore_clear_check {
+            data[CLEAR_CHECK_TRIES_OFFSET..CLEAR_CHECK_TRIES_OFFSET + 4].fill(0);
+            data[CLEAR_CHECK_TIME_OFFSET..CLEAR_CHECK_TIME_OFFSET + 4].fill(0);
+            data[MANAGEMENT_FLAGS_OFFSET] &= !(ManagementFlags2::CLEARED.bits() as u8);
+        }
+        if options.ignore_smmdb_id {
+            data[SMMDB_OFFSET..SMMDB_OFFSET_END].fill(0);
+        }
+
+        Ok(data.split_off(LEVEL_HEADER_OFFSET))
+    }
+}
+
+impl Course {
+    /// Compute a SHA-256 hex digest over the course content.
+    ///
+    /// Thumbnails are never part of the fingerprint.
+    /// Super Mario Maker courses neither store clear check data nor an SMMDB ID,
+    /// so only [ignore_modified](FingerprintOptions::ignore_modified) has an effect.
+    pub fn fingerprint(&self, options: FingerprintOptions) -> String {
+        let mut course = self.get_course().clone();
+        course.clear_thumbnail();
+        course.clear_thumbnail_preview();
+        if options.ignore_modified {
+            course.clear_modified();
+        }
+        let data = course.write_to_bytes().expect("Writing to Vector failed");
+        hex::encode(Sha256::digest(data))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Course2 {
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = getFingerprint)]
+    pub fn fingerprint_js(&self, options: FingerprintOptions) -> JsResult<String> {
+        Ok(self.fingerprint(options)?)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Course {
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = getFingerprint)]
+    pub fn fingerprint_js(&self, options: FingerprintOptions) -> String {
+        self.fingerprint(options)
+    }
+}
@@ -0,0 +1,114 @@
+//! Typed flags of the Super Mario Maker 2 course header.
+
+use crate::{constants2::*, Course2};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+bitflags! {
+    /// Game versions with which a course has been edited.
+    ///
+    /// Courses which use features of a newer game version can not be played on older versions.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct GameVersion2: u32 {
+        const V1_0_0 = 1 << 0;
+        const V1_0_1 = 1 << 1;
+        const V1_1_0 = 1 << 2;
+        const V2_0_0 = 1 << 3;
+        const V3_0_0 = 1 << 4;
+        const V3_0_1 = 1 << 5;
+
+        const _ = !0;
+    }
+}
+
+bitflags! {
+    /// Management flags of a course.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct ManagementFlags2: u32 {
+        /// Set for every course which has been saved by the game.
+        const SAVED = 1 << 0;
+        /// The maker has cleared the course, which is required to upload it.
+        const CLEARED = 1 << 1;
+
+        const _ = !0;
+    }
+}
+
+impl Course2 {
+    pub fn get_game_version(&self) -> GameVersion2 {
+        GameVersion2::from_bits_retain(self.get_course().get_header().game_version)
+    }
+
+    /// Set the game version of this course.
+    ///
+    /// Unknown bits are kept as is.
+    pub fn set_game_version(&mut self, game_version: GameVersion2) {
+        self.get_course_mut()
+            .mut_header()
+            .set_game_version(game_version.bits());
+        self.write_header_u32(GAME_VERSION_OFFSET, game_version.bits());
+    }
+
+    pub fn get_management_flags(&self) -> ManagementFlags2 {
+        ManagementFlags2::from_bits_retain(self.get_course().get_header().management_flags)
+    }
+
+    /// Set the management flags of this course.
+    ///
+    /// Unknown bits are kept as is.
+    pub fn set_management_flags(&mut self, management_flags: ManagementFlags2) {
+        self.get_course_mut()
+            .mut_header()
+            .set_management_flags(management_flags.bits());
+        self.write_header_u32(MANAGEMENT_FLAGS_OFFSET, management_flags.bits());
+    }
+
+    /// Whether the maker has cleared this course.
+    pub fn is_cleared(&self) -> bool {
+        self.get_management_flags()
+            .contains(ManagementFlags2::CLEARED)
+    }
+
+    /// Whether this course has been uploaded to the Nintendo servers.
+    ///
+    /// The upload state is not part of the management flags,
+    /// but is given by the presence of an upload ID.
+    pub fn is_uploaded(&self) -> bool {
+        self.get_course().get_header().upload_id != 0
+    }
+
+    /// Set or unset the cleared flag without touching any other flag.
+    pub fn set_cleared(&mut self, cleared: bool) {
+        let mut management_flags = self.get_management_flags();
+        management_flags.set(ManagementFlags2::CLEARED, cleared);
+        self.set_management_flags(management_flags);
+    }
+
+    fn write_header_u32(&mut self, offset: usize, value: u32) {
+        if self.has_course_data() {
+            self.get_course_data_mut()[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Course2 {
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = isCleared)]
+    pub fn is_cleared_js(&self) -> bool {
+        self.is_cleared()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = isUploaded)]
+    pub fn is_uploaded_js(&self) -> bool {
+        self.is_uploaded()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = setCleared)]
+    pub fn set_cleared_js(&mut self, cleared: bool) {
+        self.set_cleared(cleared)
+    }
+}
@@ -0,0 +1,211 @@
+//! Conversion of Super Mario Maker 2 courses to another game style.
+//!
+//! Some objects only exist in certain game styles and Super Mario 3D World has a set of its own.
+//! Objects which are not available in the new game style are replaced with their closest counterpart
+//! or removed, if there is none.
+
+#[cfg(all(target_arch = "wasm32", feature = "with-serde"))]
+use crate::JsResult;
+use crate::{
+    area2::{Area2, AreaType, EntityTable2, ObjectId2},
+    clear_condition2::ClearCondition2,
+    constants2::*,
+    errors::Smm2Error,
+    proto::SMM2Course::SMM2CourseHeader_GameStyle,
+    Course2, Result,
+};
+
+#[cfg(all(target_arch = "wasm32", feature = "with-serde"))]
+use protobuf::ProtobufEnum;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+/// An object which is not available in the new game style.
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ObjectSubstitution2 {
+    pub area: AreaType,
+    /// Index of the object before the conversion.
+    pub index: usize,
+    /// Index of the object after the conversion, `None` if the object has been removed.
+    ///
+    /// Removed objects are taken out of the object table, so all following objects move up.
+    pub new_index: Option<usize>,
+    /// The object is carried by the object at `index`, e.g. an item in a block.
+    pub child: bool,
+    pub object: ObjectId2,
+    /// `None`, if the object has been removed.
+    pub replacement: Option<ObjectId2>,
+}
+
+/// Result of a game style conversion.
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GameStyleConversion2 {
+    pub substitutions: Vec<ObjectSubstitution2>,
+    /// Entity tables which have been emptied,
+    /// because their entities are not available in the new game style.
+    pub cleared_tables: Vec<(AreaType, EntityTable2)>,
+    /// Clear condition of the course, if one of its [objects](ClearCondition2::objects)
+    /// has been removed or replaced.
+    ///
+    /// The clear condition itself is left untouched.
+    pub clear_condition: Option<ClearCondition2>,
+}
+
+impl ObjectId2 {
+    /// Closest counterpart of this object, which is used if it is not available in a game style.
+    ///
+    /// The counterpart might not be available in that game style either.
+    pub fn counterpart(self) -> Option<ObjectId2> {
+        use ObjectId2::*;
+
+        match self {
+            Smb2Mushroom => Some(SuperMushroom),
+            ClownCar => Some(KoopaCar),
+            KoopaCar => Some(ClownCar),
+            ShoeGoomba => Some(Goomba),
+            Lemmy | Morton | Larry | Wendy | Iggy | Roy | Ludwig => Some(BoomBoom),
+            RedPowBox => Some(Pow),
+            MushroomTrampoline => Some(Spring),
+            _ => None,
+        }
+    }
+}
+
+impl EntityTable2 {
+    /// Object which is placed to create the entries of this table.
+    fn object(self) -> Option<ObjectId2> {
+        match self {
+            EntityTable2::SoundEffects => Some(ObjectId2::SoundEffect),
+            EntityTable2::SnakeBlocks => Some(ObjectId2::SnakeBlock),
+            EntityTable2::ClearPipes => Some(ObjectId2::ClearPipe),
+            EntityTable2::PiranhaCreepers => Some(ObjectId2::PiranhaCreeper),
+            EntityTable2::ExclamationBlocks => Some(ObjectId2::ExclamationBlock),
+            EntityTable2::TrackBlocks => Some(ObjectId2::TrackBlock),
+            EntityTable2::Tracks => Some(ObjectId2::Track),
+            EntityTable2::Icicles => Some(ObjectId2::Icicle),
+            EntityTable2::Objects | EntityTable2::Ground => None,
+        }
+    }
+}
+
+impl Course2 {
+    /// Convert this course to another game style.
+    ///
+    /// Objects and carried objects which are not available in the new game style
+    /// are replaced with their [counterpart](ObjectId2::counterpart) or removed.
+    /// The remaining objects keep their order, but move up in the object table for every
+    /// removed object before them.
+    /// Objects refer to each other by link IDs instead of their index, so no references
+    /// have to be updated.
+    /// Entity tables of unavailable entities, e.g. the paths of piranha creepers, are emptied.
+    /// Objects of unknown type are kept as is.
+    ///
+    /// The clear condition is left untouched, even if it refers to a removed object,
+    /// but it is reported in the result in that case.
+    pub fn convert_game_style(
+        &mut self,
+        game_style: SMM2CourseHeader_GameStyle,
+    ) -> Result<GameStyleConversion2> {
+        if !self.has_course_data() {
+            return Err(Smm2Error::CourseDataRequired.into());
+        }
+        let mut conversion = GameStyleConversion2::default();
+        let clear_condition = self.get_clear_condition();
+        let data = self.get_course_data_mut();
+        for area in AreaType::ALL {
+            let table = EntityTable2::Objects;
+            let offset = table.offset(area);
+            let size = table.entry_size();
+            let objects = Area2::from_course_data(data, area).objects;
+            let mut entries = vec![];
+            for (index, object) in objects.iter().enumerate() {
+                let mut entry = data[offset + index * size..offset + (index + 1) * size].to_vec();
+                if let Some(id) = object.object_id() {
+                    if !id.is_available(game_style) {
+                        let replacement = id
+                            .counterpart()
+                            .filter(|replacement| replacement.is_available(game_style));
+                        conversion.substitutions.push(ObjectSubstitution2 {
+                            area,
+                            index,
+                            new_index: replacement.map(|_| entries.len() / size),
+                            child: false,
+                            object: id,
+                            replacement,
+                        });
+                        match replacement {
+                            Some(replacement) => entry[OBJECT_ID_OFFSET..OBJECT_ID_OFFSET + 2]
+                                .copy_from_slice(&(replacement as i16).to_le_bytes()),
+                            None => continue,
+                        }
+                    }
+                }
+                if let Some(id) = ObjectId2::from_id(object.child_id) {
+                    if !id.is_available(game_style) {
+                        let replacement = id
+                            .counterpart()
+                            .filter(|replacement| replacement.is_available(game_style));
+                        conversion.substitutions.push(ObjectSubstitution2 {
+                            area,
+                            index,
+                            new_index: Some(entries.len() / size),
+                            child: true,
+                            object: id,
+                            replacement,
+                        });
+                        let child_id = replacement.map_or(-1, |replacement| replacement as i16);
+                        entry[OBJECT_CHILD_ID_OFFSET..OBJECT_CHILD_ID_OFFSET + 2]
+                            .copy_from_slice(&child_id.to_le_bytes());
+                    }
+                }
+                entries.extend(entry);
+            }
+            data[offset..offset + table.max() * size].fill(0);
+            data[offset..offset + entries.len()].copy_from_slice(&entries);
+
+            for table in EntityTable2::ALL {
+                let available = table
+                    .object()
+                    .map_or(true, |object| object.is_available(game_style));
+                if !available && table.count_entries(data, area) != 0 {
+                    let offset = table.offset(area);
+                    data[offset..offset + table.max() * table.entry_size()].fill(0);
+                    conversion.cleared_tables.push((area, table));
+                }
+            }
+        }
+
+        conversion.clear_condition = clear_condition
+            .map(|clear_condition| clear_condition.condition)
+            .filter(|condition| {
+                conversion
+                    .substitutions
+                    .iter()
+                    .any(|substitution| condition.objects().contains(&substitution.object))
+            });
+
+        data[GAME_STYLE_OFFSET..GAME_STYLE_OFFSET_END]
+            .copy_from_slice(Course2::get_game_style_str(game_style).as_bytes());
+        self.get_course_mut()
+            .mut_header()
+            .set_game_style(game_style);
+        self.update_areas()?;
+        Ok(conversion)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Course2 {
+    #[cfg(target_arch = "wasm32")]
+    #[cfg(feature = "with-serde")]
+    #[wasm_bindgen(js_name = convertGameStyle)]
+    pub fn convert_game_style_js(&mut self, game_style: i32) -> JsResult<JsValue> {
+        let game_style =
+            SMM2CourseHeader_GameStyle::from_i32(game_style).ok_or(Smm2Error::GameStyleParse)?;
+        Ok(serde_wasm_bindgen::to_value(
+            &self.convert_game_style(game_style)?,
+        )?)
+    }
+}
@@ -9,6 +9,9 @@
 #[macro_use]
 extern crate arrayref;
 
+#[macro_use]
+extern crate bitflags;
+
 #[macro_use]
 extern crate cfg_if;
 
@@ -18,25 +21,73 @@ extern crate serde_derive;
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
 
+pub mod anonymize;
+pub mod area;
+pub mod area2;
+pub mod area_ops2;
+pub mod clear_condition2;
 pub mod constants;
 pub mod constants2;
 pub mod course;
 pub mod course2;
+pub mod course_id;
+pub mod diff;
+pub mod diff2;
 mod encryption;
 pub mod errors;
+pub mod fingerprint;
+pub mod flags2;
+pub mod game_style2;
 pub(crate) mod key_tables;
 pub mod proto;
-#[cfg(all(feature = "save", not(target_arch = "wasm32")))]
+pub mod reachability2;
+#[cfg(feature = "save")]
 pub mod save;
+#[cfg(feature = "save")]
+pub mod save_slot;
+#[cfg(feature = "save")]
+pub mod save_storage;
+pub mod similarity;
+pub mod statistics;
 pub mod thumbnail2;
+pub mod transform2;
+pub mod validation;
+pub mod validation2;
 
+pub use anonymize::{AnonymizeOptions, AnonymizedField};
+pub use area::{Object, ObjectId};
+pub use area2::{
+    Area2, AreaType, ClearPipe2, ClearPipeNode2, EntityTable2, Ground2, Icicle2, Object2,
+    ObjectId2, Path2, PathNode2, SnakeBlock2, SnakeBlockNode2, SoundEffect2, Track2,
+};
+pub use clear_condition2::{ClearCondition2, ClearConditionAmount2};
 pub use course::*;
 pub use course2::*;
+pub use course_id::{CodeType2, CourseCode2};
+pub use diff::{AreaDiff, CourseDiff, EntityDiff, FieldChange, Moved, Positioned};
+pub use diff2::{AreaDiff2, Course2Diff};
+#[cfg(feature = "save")]
+pub(crate) use encryption::calc_crc32;
 pub(crate) use encryption::{decrypt, encrypt, fix_crc32};
 pub use errors::{SmmdbError as Error, SmmdbResult as Result};
-#[cfg(all(feature = "save", not(target_arch = "wasm32")))]
+pub use fingerprint::FingerprintOptions;
+pub use flags2::{GameVersion2, ManagementFlags2};
+pub use game_style2::{GameStyleConversion2, ObjectSubstitution2};
+pub use reachability2::{Movement2, Reachability2, ReachabilityStep2, ReachabilityVerdict2};
+#[cfg(feature = "save")]
 pub use save::*;
+#[cfg(feature = "save")]
+pub use save_slot::{SaveSection, SaveSlot};
+#[cfg(feature = "save")]
+pub use save_storage::{FsStorage, MemoryStorage, SaveStorage, ZipStorage};
+pub use similarity::{
+    Alignment, CourseLayout, Signature, Similarity, SimilarityIndex, SimilarityMatch,
+    SimilarityOptions,
+};
+pub use statistics::{AreaStatistics, CourseStatistics};
 pub use thumbnail2::*;
+pub use validation::{Diagnostic, Lint, Severity};
+pub use validation2::{Diagnostic2, Lint2};
 
 #[cfg(target_arch = "wasm32")]
 pub type JsResult<T> = core::result::Result<T, JsValue>;
@@ -0,0 +1,581 @@
+//! Start to goal reachability analysis for Super Mario Maker 2 courses.
+//!
+//! Both areas are reduced to a grid of tiles, on which a simplified Mario walks, jumps, wall jumps, falls, climbs and swims.
+//! Switch blocks and hidden blocks are always treated as standable
+//! and jumps are rather generous, so a reachable goal does not guarantee that the course can be cleared.
+//! Enemies, power-ups, moving platforms and everything else which might carry Mario are not part of the model.
+//! If a course contains any of them and the goal has not been reached,
+//! the analysis is [inconclusive](ReachabilityVerdict2::Unknown) instead of reporting the goal as unreachable.
+//! Courses with areas larger than the game allows are not analyzed at all and inconclusive as well.
+
+#[cfg(all(target_arch = "wasm32", feature = "with-serde"))]
+use crate::JsResult;
+use crate::{
+    area2::{Area2, AreaType, Object2, ObjectId2, OBJECT_TILE_SIZE},
+    proto::SMM2Course::{
+        SMM2CourseArea, SMM2CourseArea_CourseTheme, SMM2CourseArea_DayTime, SMM2CourseHeader,
+        SMM2CourseHeader_GameStyle,
+    },
+    validation2::HORIZONTAL_WIDTH,
+    Course2, Result,
+};
+
+use std::collections::VecDeque;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+/// Width of the start ground in tiles.
+const START_GROUND_WIDTH: i32 = 7;
+/// Column on which Mario spawns.
+const START_X: i32 = 2;
+/// Width of the goal ground left of the goal pole in tiles.
+const GOAL_GROUND_MARGIN: i32 = 3;
+/// Jump height in tiles after bouncing off springs and trampolines.
+const SPRING_HEIGHT: u8 = 12;
+/// Additional jump height in tiles after bouncing off note blocks.
+const NOTE_BLOCK_BONUS: u8 = 3;
+
+const SOLID: u8 = 1 << 0;
+const PLATFORM: u8 = 1 << 1;
+const CLIMBABLE: u8 = 1 << 2;
+const SPRING: u8 = 1 << 3;
+const NOTE_BLOCK: u8 = 1 << 4;
+const WARP: u8 = 1 << 5;
+
+/// How Mario got to a position of a [Reachability2] path.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Movement2 {
+    Start,
+    Walk,
+    Jump,
+    Fall,
+    Warp,
+}
+
+/// Verdict of a [reachability analysis](Course2::analyze_reachability).
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReachabilityVerdict2 {
+    /// The goal has been reached from the start.
+    Reachable,
+    /// The goal has not been reached, but the course contains objects,
+    /// which are not part of the movement model, e.g. enemies or power-ups,
+    /// or the boundaries of an area exceed the largest area size.
+    Unknown,
+    /// The goal has not been reached and every object of the course is part of the movement model.
+    Unreachable,
+}
+
+/// A single tile position of a [Reachability2] path.
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ReachabilityStep2 {
+    pub area: AreaType,
+    /// Column in tiles.
+    pub x: u32,
+    /// Row in tiles, counted from the bottom.
+    pub y: u32,
+    pub movement: Movement2,
+}
+
+/// Result of a [reachability analysis](Course2::analyze_reachability).
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reachability2 {
+    pub verdict: ReachabilityVerdict2,
+    /// Shortest path from the start to the goal.
+    ///
+    /// If the goal has not been reached, this is the path to the rightmost position
+    /// which has been reached in the main area.
+    pub path: Vec<ReachabilityStep2>,
+    /// Amount of distinct tile positions which have been explored.
+    pub explored: usize,
+}
+
+impl Course2 {
+    /// Analyze whether the goal can be reached from the start.
+    ///
+    /// The goal is only reported as [unreachable](ReachabilityVerdict2::Unreachable),
+    /// if the whole course is covered by the movement model.
+    /// Pipes, doors and warp boxes are treated as warps to all other pipes, doors and warp boxes
+    /// of either area, because links between them are not decoded.
+    ///
+    /// This fails, if the course has no decrypted course data.
+    pub fn analyze_reachability(&self) -> Result<Reachability2> {
+        let course = self.get_course();
+        let header = course.get_header();
+        let max_size = *HORIZONTAL_WIDTH.end();
+        if [course.get_course_area(), course.get_course_sub_area()]
+            .iter()
+            .any(|settings| settings.right_boundary > max_size || settings.top_boundary > max_size)
+        {
+            return Ok(Reachability2 {
+                verdict: ReachabilityVerdict2::Unknown,
+                path: vec![],
+                explored: 0,
+            });
+        }
+        let main_area = self.get_area(AreaType::Main)?;
+        let sub_area = self.get_area(AreaType::Sub)?;
+        let is_covered = main_area
+            .objects
+            .iter()
+            .chain(sub_area.objects.iter())
+            .all(is_covered);
+        let grids = [
+            Grid::new(course.get_course_area(), &main_area, Some(header)),
+            Grid::new(course.get_course_sub_area(), &sub_area, None),
+        ];
+        let goal_x = (header.finish_x / 10) as i32;
+        let mut reachability =
+            Search::new(grids, jump_height(header.game_style)).run(header.start_y as i32, goal_x);
+        if reachability.verdict == ReachabilityVerdict2::Unreachable && !is_covered {
+            reachability.verdict = ReachabilityVerdict2::Unknown;
+        }
+        Ok(reachability)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Course2 {
+    #[cfg(target_arch = "wasm32")]
+    #[cfg(feature = "with-serde")]
+    #[wasm_bindgen(js_name = analyzeReachability)]
+    pub fn analyze_reachability_js(&self) -> JsResult<JsValue> {
+        Ok(serde_wasm_bindgen::to_value(&self.analyze_reachability()?)?)
+    }
+}
+
+/// Whether an object is fully covered by the movement model,
+/// so that it can not help Mario to get anywhere the model does not expect.
+///
+/// Objects with contents other than coins are not covered, because they might contain power-ups,
+/// enemies or vines.
+fn is_covered(object: &Object2) -> bool {
+    use ObjectId2::*;
+
+    if object.child_id != -1 && object.child_id != Coin as i16 {
+        return false;
+    }
+    matches!(
+        object.object_id(),
+        Some(
+            Block
+                | QuestionBlock
+                | HardBlock
+                | Ground
+                | IceBlock
+                | GoalGround
+                | StartingBrick
+                | NoteBlock
+                | Pipe
+                | Door
+                | WarpBox
+                | MushroomTrampoline
+                | SlightSlope
+                | SteepSlope
+                | Spikes
+                | Muncher
+                | SemisolidPlatform
+                | MushroomPlatform
+                | Bridge
+                | CastleBridge
+                | Cloud
+                | HiddenBlock
+                | OnOffBlock
+                | DottedLineBlock
+                | BlinkingBlock
+                | HalfCollisionPlatform
+                | ExclamationBlock
+                | Tree
+                | Vine
+                | Coin
+                | BigCoin
+                | RedCoin
+                | Goal
+                | StartingArrow
+                | Arrow
+                | OneWay
+                | CheckpointFlag
+                | SoundEffect
+        )
+    )
+}
+
+/// Height of a full running jump in tiles.
+fn jump_height(game_style: SMM2CourseHeader_GameStyle) -> u8 {
+    match game_style {
+        SMM2CourseHeader_GameStyle::M1 | SMM2CourseHeader_GameStyle::W3 => 5,
+        SMM2CourseHeader_GameStyle::M3
+        | SMM2CourseHeader_GameStyle::MW
+        | SMM2CourseHeader_GameStyle::WU => 6,
+    }
+}
+
+/// Tile flags of a single area.
+struct Grid {
+    width: i32,
+    height: i32,
+    /// Rows below this one are filled with water.
+    water: i32,
+    cells: Vec<u8>,
+}
+
+impl Grid {
+    /// The start and goal grounds are not part of the ground table,
+    /// so they are added from the course header for the main area.
+    fn new(settings: &SMM2CourseArea, area: &Area2, header: Option<&SMM2CourseHeader>) -> Grid {
+        let width = (settings.right_boundary / 16) as i32;
+        let height = (settings.top_boundary / 16) as i32;
+        let water = match (settings.course_theme, settings.day_time) {
+            (SMM2CourseArea_CourseTheme::UNDERWATER, _) => height,
+            (SMM2CourseArea_CourseTheme::FOREST, SMM2CourseArea_DayTime::DAY) => {
+                settings.liquid_min.max(settings.liquid_max) as i32
+            }
+            _ => 0,
+        };
+        let mut grid = Grid {
+            width,
+            height,
+            water,
+            cells: vec![0; (width * height) as usize],
+        };
+
+        if let Some(header) = header {
+            grid.fill(0, 0, START_GROUND_WIDTH, header.start_y as i32, SOLID);
+            let goal_x = (header.finish_x / 10) as i32 - GOAL_GROUND_MARGIN;
+            grid.fill(goal_x, 0, width - goal_x, header.finish_y as i32, SOLID);
+        }
+        for ground in area.ground.iter() {
+            grid.fill(ground.x as i32, ground.y as i32, 1, 1, SOLID);
+        }
+        for object in area.objects.iter() {
+            grid.add_object(object);
+        }
+        grid
+    }
+
+    /// Objects are positioned by the center of their bottom left tile and extend to the top right.
+    fn add_object(&mut self, object: &Object2) {
+        use ObjectId2::*;
+
+        let x = (object.x - OBJECT_TILE_SIZE / 2).div_euclid(OBJECT_TILE_SIZE);
+        let y = (object.y - OBJECT_TILE_SIZE / 2).div_euclid(OBJECT_TILE_SIZE);
+        let width = (object.width as i32).max(1);
+        let height = (object.height as i32).max(1);
+        let top = y + height - 1;
+        match object.object_id() {
+            Some(
+                Block | QuestionBlock | HardBlock | Ground | IceBlock | BulletBillBlaster | Cannon
+                | ConveyorBelt | FastConveyorBelt | GoalGround | StartingBrick | Crate,
+            ) => self.fill(x, y, width, height, SOLID),
+            Some(NoteBlock) => self.fill(x, y, width, height, SOLID | NOTE_BLOCK),
+            Some(Pipe) => {
+                // pipes are two tiles thick, centered on their position and extend from their opening
+                let center = (object.x - OBJECT_TILE_SIZE).div_euclid(OBJECT_TILE_SIZE);
+                let length = height;
+                let (x, y, width, height) = match (object.flags >> 5) & 0b11 {
+                    0 => (x, y, length, 2),
+                    1 => (x - length + 1, y, length, 2),
+                    2 => (center, y, 2, length),
+                    _ => (center, y - length + 1, 2, length),
+                };
+                self.fill(x, y, width, height, SOLID);
+                self.fill(x - 1, y - 1, width + 2, height + 2, WARP);
+            }
+            Some(Door) => self.fill(x, y, width, height, WARP),
+            Some(WarpBox) => self.fill(x, y, width, height, PLATFORM | WARP),
+            Some(Spring | MushroomTrampoline | OnOffTrampoline | JumpingMachine) => {
+                self.fill(x, y, width, height, PLATFORM | SPRING)
+            }
+            Some(SlightSlope | SteepSlope | Spikes | Muncher) => {
+                self.fill(x, y, width, height, PLATFORM)
+            }
+            Some(
+                SemisolidPlatform
+                | MushroomPlatform
+                | Bridge
+                | CastleBridge
+                | Cloud
+                | Lift
+                | LavaLift
+                | DonutBlock
+                | Donut
+                | HiddenBlock
+                | OnOffBlock
+                | DottedLineBlock
+                | BlinkingBlock
+                | PBlock
+                | HalfCollisionPlatform
+                | Seesaw
+                | SnakeBlock
+                | TrackBlock
+                | ExclamationBlock
+                | Tree
+                | SprintPlatform,
+            ) => self.fill(x, top, width, 1, PLATFORM),
+            Some(Vine | ClearPipe | ClownCar | LakituCloud | Twister) => {
+                self.fill(x, y, width, height, CLIMBABLE)
+            }
+            _ => {}
+        }
+    }
+
+    fn fill(&mut self, x: i32, y: i32, width: i32, height: i32, flags: u8) {
+        for y in y.max(0)..(y + height).min(self.height) {
+            for x in x.max(0)..(x + width).min(self.width) {
+                self.cells[(y * self.width + x) as usize] |= flags;
+            }
+        }
+    }
+
+    fn contains(&self, x: i32, y: i32) -> bool {
+        (0..self.width).contains(&x) && (0..self.height).contains(&y)
+    }
+
+    fn get(&self, x: i32, y: i32) -> u8 {
+        if self.contains(x, y) {
+            self.cells[(y * self.width + x) as usize]
+        } else {
+            0
+        }
+    }
+
+    fn is_free(&self, x: i32, y: i32) -> bool {
+        self.contains(x, y) && self.get(x, y) & SOLID == 0
+    }
+
+    /// Whether Mario can jump off from the given position.
+    ///
+    /// Walls can be used for wall jumps.
+    fn is_supported(&self, x: i32, y: i32) -> bool {
+        self.get(x, y - 1) & (SOLID | PLATFORM) != 0
+            || self.get(x - 1, y) & SOLID != 0
+            || self.get(x + 1, y) & SOLID != 0
+            || self.get(x, y) & CLIMBABLE != 0
+            || y < self.water
+    }
+}
+
+/// Amount of air budget levels, see [State::air].
+const AIR_LEVELS: usize = 2 * SPRING_HEIGHT as usize + 1;
+/// Amount of states per tile.
+const TILE_STATES: usize = AIR_LEVELS * 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct State {
+    area: AreaType,
+    x: i32,
+    y: i32,
+    /// Remaining air budget.
+    ///
+    /// Moving a tile upwards costs two and moving a tile sideways costs one,
+    /// so a running jump covers about twice its height in width.
+    air: u8,
+    /// Mario can only move upwards until he starts falling.
+    rising: bool,
+}
+
+struct Search {
+    grids: [Grid; 2],
+    jump_height: u8,
+    parents: Vec<Option<(usize, Movement2)>>,
+    visited: Vec<bool>,
+    queue: VecDeque<State>,
+}
+
+impl Search {
+    fn new(grids: [Grid; 2], jump_height: u8) -> Search {
+        let states = grids.iter().map(|grid| grid.cells.len()).sum::<usize>() * TILE_STATES;
+        Search {
+            grids,
+            jump_height,
+            parents: vec![None; states],
+            visited: vec![false; states],
+            queue: VecDeque::new(),
+        }
+    }
+
+    fn run(mut self, start_y: i32, goal_x: i32) -> Reachability2 {
+        if !self.grids[AreaType::Main.index()].is_free(START_X, start_y) {
+            return Reachability2 {
+                verdict: ReachabilityVerdict2::Unreachable,
+                path: vec![],
+                explored: 0,
+            };
+        }
+        let start = self.land(AreaType::Main, START_X, start_y, 0, false);
+        self.visit(start, None);
+        let mut goal = None;
+        let mut rightmost = start;
+        let mut warped = false;
+
+        while let Some(state) = self.queue.pop_front() {
+            if state.area == AreaType::Main {
+                if state.x >= goal_x {
+                    goal = Some(state);
+                    break;
+                }
+                if state.x > rightmost.x {
+                    rightmost = state;
+                }
+            }
+            let index = self.index(state);
+            let grid = &self.grids[state.area.index()];
+            let (x, y) = (state.x, state.y);
+
+            if !warped && grid.get(x, y) & WARP != 0 {
+                warped = true;
+                for area in AreaType::ALL {
+                    for (x, y) in self.warp_exits(area) {
+                        let next = self.land(area, x, y, 0, false);
+                        self.visit(next, Some((index, Movement2::Warp)));
+                    }
+                }
+            }
+
+            let grid = &self.grids[state.area.index()];
+            let supported = grid.is_supported(x, y);
+            let air_movement = if state.rising {
+                Movement2::Jump
+            } else {
+                Movement2::Fall
+            };
+            let mut moves = vec![];
+            for dx in [-1, 1] {
+                if supported {
+                    moves.push((x + dx, y, 0, false, Movement2::Walk));
+                    moves.push((x + dx, y - 1, 0, false, Movement2::Walk));
+                } else if state.air >= 1 {
+                    moves.push((x + dx, y, state.air - 1, state.rising, air_movement));
+                }
+            }
+            for dx in [-1, 0, 1] {
+                if state.rising && state.air >= 2 {
+                    moves.push((x + dx, y + 1, state.air - 2, true, Movement2::Jump));
+                }
+                if !supported {
+                    moves.push((x + dx, y - 1, state.air, false, Movement2::Fall));
+                }
+            }
+
+            for (next_x, next_y, air, rising, movement) in moves {
+                if self.grids[state.area.index()].is_free(next_x, next_y) {
+                    let next = self.land(state.area, next_x, next_y, air, rising);
+                    self.visit(next, Some((index, movement)));
+                }
+            }
+        }
+
+        let end = goal.unwrap_or(rightmost);
+        Reachability2 {
+            verdict: if goal.is_some() {
+                ReachabilityVerdict2::Reachable
+            } else {
+                ReachabilityVerdict2::Unreachable
+            },
+            path: self.path(end),
+            explored: self.explored(),
+        }
+    }
+
+    /// Free tiles next to or inside of all warps of the given area.
+    fn warp_exits(&self, area: AreaType) -> Vec<(i32, i32)> {
+        let grid = &self.grids[area.index()];
+        let mut exits = vec![];
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                if grid.get(x, y) & WARP != 0 && grid.is_free(x, y) {
+                    exits.push((x, y));
+                }
+            }
+        }
+        exits
+    }
+
+    /// Mario can jump off again, whenever he is supported.
+    fn land(&self, area: AreaType, x: i32, y: i32, air: u8, rising: bool) -> State {
+        let grid = &self.grids[area.index()];
+        let (air, rising) = if grid.is_supported(x, y) {
+            let below = grid.get(x, y - 1);
+            let height = if below & SPRING != 0 {
+                SPRING_HEIGHT
+            } else if below & NOTE_BLOCK != 0 {
+                self.jump_height + NOTE_BLOCK_BONUS
+            } else {
+                self.jump_height
+            };
+            (air.max(2 * height), true)
+        } else {
+            (air, rising)
+        };
+        State {
+            area,
+            x,
+            y,
+            air,
+            rising,
+        }
+    }
+
+    fn visit(&mut self, state: State, parent: Option<(usize, Movement2)>) {
+        let index = self.index(state);
+        if !self.visited[index] {
+            self.visited[index] = true;
+            self.parents[index] = parent;
+            self.queue.push_back(state);
+        }
+    }
+
+    fn index(&self, state: State) -> usize {
+        let offset = match state.area {
+            AreaType::Main => 0,
+            AreaType::Sub => self.grids[0].cells.len(),
+        };
+        let grid = &self.grids[state.area.index()];
+        let tile = offset + (state.y * grid.width + state.x) as usize;
+        tile * TILE_STATES + state.air as usize * 2 + state.rising as usize
+    }
+
+    fn position(&self, index: usize) -> (AreaType, u32, u32) {
+        let tile = index / TILE_STATES;
+        let (area, tile) = if tile < self.grids[0].cells.len() {
+            (AreaType::Main, tile)
+        } else {
+            (AreaType::Sub, tile - self.grids[0].cells.len())
+        };
+        let width = self.grids[area.index()].width as usize;
+        (area, (tile % width) as u32, (tile / width) as u32)
+    }
+
+    fn path(&self, end: State) -> Vec<ReachabilityStep2> {
+        let mut path = vec![];
+        let mut index = self.index(end);
+        loop {
+            let (area, x, y) = self.position(index);
+            let parent = self.parents[index];
+            path.push(ReachabilityStep2 {
+                area,
+                x,
+                y,
+                movement: parent.map_or(Movement2::Start, |(_, movement)| movement),
+            });
+            match parent {
+                Some((parent, _)) => index = parent,
+                None => break,
+            }
+        }
+        path.reverse();
+        path
+    }
+
+    fn explored(&self) -> usize {
+        self.visited
+            .chunks_exact(TILE_STATES)
+            .filter(|states| states.iter().any(|visited| *visited))
+            .count()
+    }
+}
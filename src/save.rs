@@ -1,222 +1,1101 @@
 use crate::{
+    calc_crc32,
     constants2::*,
     encryption::{decrypt, encrypt},
     errors::{SaveError, Smm2Error},
     fix_crc32,
     key_tables::*,
+    save_slot::{SaveSection, SaveSlot},
+    save_storage::{FsStorage, SaveStorage},
     Course2, Error, Result,
 };
 
+#[cfg(target_arch = "wasm32")]
+use crate::{save_storage::MemoryStorage, JsResult};
 use arr_macro::arr;
-use async_std::{
-    fs::{remove_file, rename, File},
-    prelude::*,
-};
-use std::{cell::Cell, path::PathBuf};
+#[cfg(any(target_arch = "wasm32", test))]
+use std::collections::BTreeMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{path::PathBuf, sync::OnceLock};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
 
 type Courses = [Option<Box<CourseEntry>>; 60];
+/// Course of a single slot, which is read from the storage on first access.
+type LazyCourse = OnceLock<Option<Box<CourseEntry>>>;
+/// Encrypted course data and thumbnail.
+type CourseFiles = (Vec<u8>, Vec<u8>);
 
+/// Super Mario Maker 2 save folder, whose files are accessed through a [`SaveStorage`].
+///
+/// Occupied slots are read from `save.dat` up front,
+/// but course files are only read and decrypted, when a course is accessed for the first time.
 #[derive(Clone, Debug)]
-pub struct Save {
-    path: PathBuf,
+pub struct Save<S: SaveStorage = FsStorage> {
+    storage: S,
     save_file: Vec<u8>,
-    own_courses: Courses,
-    unknown_courses: Courses,
-    downloaded_courses: Courses,
-    pending_fs_operations: [Option<PendingFsOperation>; 180],
+    /// Courses of every [`SaveSection`], once all courses of that section have been accessed.
+    sections: [OnceLock<Courses>; 3],
+    /// Courses of single slots, which have been accessed before their whole section.
+    courses: [LazyCourse; 180],
+    /// Content of every slot once pending changes have been saved.
+    pending_slots: [SlotContent; 180],
+    /// Slots whose course files are currently stored.
+    saved_slots: [bool; 180],
+    /// The journal or staged files of an interrupted [`Save::save`] have been found.
+    interrupted: bool,
+    /// Maximum amount of backups, which are kept after saving.
+    backup_limit: Option<usize>,
 }
 
 impl Save {
-    pub async fn new<T: Into<PathBuf>>(path: T) -> Result<Save> {
-        let path: PathBuf = path.into();
-
-        let mut save_path = path.clone();
-        save_path.push("save.dat");
-        let mut file = File::open(save_path).await?;
-        let mut save_file = vec![];
-        file.read_to_end(&mut save_file).await?;
-        decrypt(&mut save_file[0x10..], &SAVE_KEY_TABLE)?;
-        save_file = save_file[..save_file.len() - 0x30].to_vec();
+    /// See [`Save::from_storage`].
+    pub fn new<T: Into<PathBuf>>(path: T) -> Result<Save> {
+        Save::from_storage(FsStorage::new(path))
+    }
 
-        let mut own_courses = arr![None; 60];
-        let mut unknown_courses = arr![None; 60];
-        let mut downloaded_courses = arr![None; 60];
-        let mut index = 0;
-        let offset = SAVE_COURSE_OFFSET as usize + 0x10;
-        while index < 180 {
-            let offset: usize = offset + index * 8;
-            if save_file[offset + 1] == 0 {
-                index += 1;
-                continue;
+    /// Async version of [`Save::new`].
+    pub async fn new_async<T: Into<PathBuf>>(path: T) -> Result<Save> {
+        Save::new(path)
+    }
+}
+
+impl<S: SaveStorage> Save<S> {
+    /// Load a save from a storage.
+    ///
+    /// Only `save.dat` is read. Course files are read on first access or by [`Save::preload`].
+    ///
+    /// All functions of `Save` access the storage synchronously.
+    /// Their `_async` versions do not depend on any async runtime, but only wrap the same code,
+    /// so they still block the current thread while the storage is accessed.
+    /// Async code should call the synchronous functions from the `spawn_blocking` function
+    /// of its runtime instead, if a [`FsStorage`] is used.
+    pub fn from_storage(storage: S) -> Result<Save<S>> {
+        let mut save = Save {
+            storage,
+            save_file: vec![],
+            sections: Default::default(),
+            courses: std::array::from_fn(|_| OnceLock::new()),
+            pending_slots: [SlotContent::Empty; 180],
+            saved_slots: [false; 180],
+            interrupted: false,
+            backup_limit: None,
+        };
+        save.reload()?;
+        Ok(save)
+    }
+
+    /// Read `save.dat` from the storage and discard all courses and pending changes.
+    fn reload(&mut self) -> Result<()> {
+        let save_file = Save::read_save_file(&self.storage)?;
+        let interrupted = self
+            .storage
+            .list("")?
+            .iter()
+            .any(|name| name == SAVE_JOURNAL || is_staged_file(name));
+
+        let mut pending_slots = [SlotContent::Empty; 180];
+        let mut saved_slots = [false; 180];
+        for index in 0..180 {
+            if save_file[get_entry_offset(index) + 1] != 0 {
+                pending_slots[index] = SlotContent::Saved(index as u8);
+                saved_slots[index] = true;
             }
+        }
 
-            let mut course_data_path = path.clone();
-            course_data_path.push(format!("course_data_{:0>3}.bcd", index));
-            let mut course_file = File::open(course_data_path).await?;
-            let mut course_data = vec![];
-            course_file.read_to_end(&mut course_data).await?;
-
-            let mut thumb_path = path.clone();
-            thumb_path.push(format!("course_thumb_{:0>3}.btl", index));
-            let mut thumb_file = File::open(thumb_path).await?;
-            let mut thumb_data = vec![];
-            thumb_file.read_to_end(&mut thumb_data).await?;
-
-            let courses = match index {
-                i if i < 60 => &mut own_courses,
-                i if (60..120).contains(&i) => &mut unknown_courses,
-                i if (120..180).contains(&i) => &mut downloaded_courses,
-                _ => panic!(),
-            };
-            let course = Course2::from_switch_files(&mut course_data, Some(thumb_data), true);
-            match course {
-                Ok(course) => {
-                    courses[index % 60] = Some(Box::new(CourseEntry::SavedCourse(
-                        SavedCourse::new(*array_ref!(&save_file[..], offset, 8), course),
-                    )));
+        self.save_file = save_file;
+        self.sections = Default::default();
+        self.courses = std::array::from_fn(|_| OnceLock::new());
+        self.pending_slots = pending_slots;
+        self.saved_slots = saved_slots;
+        self.interrupted = interrupted;
+        Ok(())
+    }
+
+    /// Read and decrypt the course of the slot at `index` from its course files.
+    fn load_course(
+        storage: &S,
+        save_file: &[u8],
+        index: usize,
+        slot: SlotContent,
+    ) -> Result<Option<Box<CourseEntry>>> {
+        let saved_index = match slot {
+            SlotContent::Empty => return Ok(None),
+            SlotContent::Saved(saved_index) => saved_index,
+            SlotContent::Added => unreachable!("added courses are never unloaded"),
+        };
+        let (course_name, thumb_name) = get_course_names(saved_index);
+        let mut course_data = storage.read(&course_name)?;
+        let thumb_data = storage.read(&thumb_name)?;
+
+        let entry = SlotEntry::read(save_file, index);
+        match Course2::_from_switch_files(&mut course_data, Some(thumb_data), true) {
+            Ok(course) => Ok(Some(Box::new(CourseEntry::SavedCourse(SavedCourse {
+                entry,
+                course,
+            })))),
+            Err(Error::Smm2Error(err)) => Ok(Some(Box::new(CourseEntry::CorruptedCourse(err)))),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Like [`Save::load_course`], but course files which can not be read are treated as corrupted.
+    fn load_course_or_corrupted(
+        storage: &S,
+        save_file: &[u8],
+        index: usize,
+        slot: SlotContent,
+    ) -> Option<Box<CourseEntry>> {
+        Save::load_course(storage, save_file, index, slot).unwrap_or_else(|err| {
+            Some(Box::new(CourseEntry::CorruptedCourse(
+                Smm2Error::CourseFilesUnreadable(err.to_string()),
+            )))
+        })
+    }
+
+    /// Load the course of a slot with its pending content.
+    fn read_course(&self, index: usize) -> Option<Box<CourseEntry>> {
+        Save::load_course_or_corrupted(
+            &self.storage,
+            &self.save_file,
+            index,
+            self.pending_slots[index],
+        )
+    }
+
+    fn is_loaded(&self, index: usize) -> bool {
+        self.sections[index / 60].get().is_some() || self.courses[index].get().is_some()
+    }
+
+    /// Read and decrypt all courses, which have not been accessed yet, on multiple threads.
+    ///
+    /// Just like on first access by [`Save::get_course`], courses which can not be read or parsed
+    /// are stored as [`CourseEntry::CorruptedCourse`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn preload(&self)
+    where
+        S: Sync,
+    {
+        let indices: Vec<usize> = (0..180).filter(|index| !self.is_loaded(*index)).collect();
+        if indices.is_empty() {
+            return;
+        }
+        let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+        let chunk_size = (indices.len() + threads - 1) / threads;
+        let (storage, save_file, pending_slots) =
+            (&self.storage, &self.save_file[..], &self.pending_slots);
+        let loaded: Vec<(usize, Option<Box<CourseEntry>>)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = indices
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|index| {
+                                let course = Save::load_course_or_corrupted(
+                                    storage,
+                                    save_file,
+                                    *index,
+                                    pending_slots[*index],
+                                );
+                                (*index, course)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("course loading thread panicked"))
+                .collect()
+        });
+
+        let mut loaded_courses: [Option<Option<Box<CourseEntry>>>; 180] =
+            std::array::from_fn(|_| None);
+        for (index, course) in loaded {
+            loaded_courses[index] = Some(course);
+        }
+        for (section, cell) in self.sections.iter().enumerate() {
+            let indices = section * 60..(section + 1) * 60;
+            if cell.get().is_some() {
+                continue;
+            }
+            if indices
+                .clone()
+                .all(|index| loaded_courses[index].is_some() || self.courses[index].get().is_some())
+            {
+                let mut courses = arr![None; 60];
+                for index in indices {
+                    courses[index % 60] = match loaded_courses[index].take() {
+                        Some(course) => course,
+                        None => self.courses[index].get().cloned().flatten(),
+                    };
                 }
-                Err(Error::Smm2Error(err)) => {
-                    courses[index % 60] = Some(Box::new(CourseEntry::CorruptedCourse(err)));
+                let _ = cell.set(courses);
+            } else {
+                for index in indices {
+                    if let Some(course) = loaded_courses[index].take() {
+                        let _ = self.courses[index].set(course);
+                    }
                 }
-                Err(err) => return Err(err),
             }
+        }
+    }
 
-            index += 1;
+    /// Course of a slot, which is read from the storage on first access.
+    ///
+    /// Course files which can not be read are returned as [`CourseEntry::CorruptedCourse`].
+    pub fn get_course(&self, slot: SaveSlot) -> Option<&CourseEntry> {
+        let index = slot.get_save_index() as usize;
+        match self.sections[index / 60].get() {
+            Some(courses) => courses[index % 60].as_deref(),
+            None => self.courses[index]
+                .get_or_init(|| self.read_course(index))
+                .as_deref(),
         }
+    }
 
-        Ok(Save {
-            path,
-            save_file,
-            own_courses,
-            unknown_courses,
-            downloaded_courses,
-            pending_fs_operations: arr![None; 180],
+    /// Whether a course is stored in a slot, including pending changes.
+    ///
+    /// Unlike [`Save::get_course`], this does not read any course files.
+    pub fn is_occupied(&self, slot: SaveSlot) -> bool {
+        self.save_file[get_entry_offset(slot.get_save_index() as usize) + 1] != 0
+    }
+
+    /// Slots of a section, which currently hold a course.
+    pub fn occupied_slots(&self, section: SaveSection) -> impl Iterator<Item = SaveSlot> + '_ {
+        section.slots().filter(|slot| self.is_occupied(*slot))
+    }
+
+    /// Slots of a section, to which a course can be added.
+    pub fn free_slots(&self, section: SaveSection) -> impl Iterator<Item = SaveSlot> + '_ {
+        section.slots().filter(|slot| !self.is_occupied(*slot))
+    }
+
+    fn get_section(&self, section: usize) -> &Courses {
+        self.sections[section].get_or_init(|| {
+            let mut courses = arr![None; 60];
+            for (index, course) in courses.iter_mut().enumerate() {
+                let index = section * 60 + index;
+                *course = match self.courses[index].get() {
+                    Some(course) => course.clone(),
+                    None => self.read_course(index),
+                };
+            }
+            courses
         })
     }
 
-    pub fn add_course(&mut self, mut index: u8, course: Course2) -> Result<()> {
-        let courses = match index {
-            i if i < 60 => &mut self.own_courses,
-            i if (60..120).contains(&i) => &mut self.unknown_courses,
-            i if (120..180).contains(&i) => &mut self.downloaded_courses,
-            _ => return Err(SaveError::CourseIndexOutOfBounds(index).into()),
+    /// Store the course of a slot, whose entry in `save.dat` has already been updated.
+    fn set_course(&mut self, index: usize, mut course: Option<Box<CourseEntry>>) {
+        if let Some(CourseEntry::SavedCourse(course)) = course.as_deref_mut() {
+            course.entry = SlotEntry::read(&self.save_file, index);
+        }
+        self.courses[index] = OnceLock::new();
+        match self.sections[index / 60].get_mut() {
+            Some(courses) => courses[index % 60] = course,
+            None => self.courses[index] = OnceLock::from(course),
+        }
+    }
+
+    fn take_course(&mut self, index: usize) -> Option<Box<CourseEntry>> {
+        match self.sections[index / 60].get_mut() {
+            Some(courses) => courses[index % 60].take(),
+            None => match self.courses[index].take() {
+                Some(course) => course,
+                None => self.read_course(index),
+            },
+        }
+    }
+
+    /// Add a course to a slot, replacing the course in that slot.
+    ///
+    /// The entry of the slot in `save.dat` is marked as occupied
+    /// and its [reserved bytes](SlotEntry::get_reserved) are kept.
+    pub fn add_course(&mut self, slot: SaveSlot, course: Course2) -> Result<()> {
+        let index = slot.get_save_index() as usize;
+        let entry = SlotEntry::read(&self.save_file, index)
+            .with_index(index as u8)
+            .with_exists(true);
+        entry.write(&mut self.save_file, index);
+        let course = SavedCourse { entry, course };
+
+        self.pending_slots[index] = SlotContent::Added;
+        self.set_course(index, Some(Box::new(CourseEntry::SavedCourse(course))));
+
+        Ok(())
+    }
+
+    /// Swap the courses of two slots, of which at least one has to be occupied.
+    ///
+    /// Entries in `save.dat` move with their courses, except for the slot number.
+    pub fn swap_course(&mut self, first: SaveSlot, second: SaveSlot) -> Result<()> {
+        if !self.is_occupied(first) && !self.is_occupied(second) {
+            return Err(SaveError::CourseNotFound(first).into());
+        }
+        if first == second {
+            return Ok(());
+        }
+
+        let first = first.get_save_index() as usize;
+        let second = second.get_save_index() as usize;
+        let first_entry = SlotEntry::read(&self.save_file, first);
+        let second_entry = SlotEntry::read(&self.save_file, second);
+        first_entry
+            .with_index(second as u8)
+            .write(&mut self.save_file, second);
+        second_entry
+            .with_index(first as u8)
+            .write(&mut self.save_file, first);
+
+        if !self.is_loaded(first) && !self.is_loaded(second) {
+            // unloaded courses are read from the slot they have been saved to
+            self.courses.swap(first, second);
+        } else {
+            let first_course = self.take_course(first);
+            let second_course = self.take_course(second);
+            self.set_course(first, second_course);
+            self.set_course(second, first_course);
+        }
+        self.pending_slots.swap(first, second);
+
+        Ok(())
+    }
+
+    /// Remove the course of a slot and mark its entry in `save.dat` as empty.
+    ///
+    /// The [reserved bytes](SlotEntry::get_reserved) of the entry are kept.
+    pub fn remove_course(&mut self, slot: SaveSlot) -> Result<()> {
+        let index = slot.get_save_index() as usize;
+        SlotEntry::read(&self.save_file, index)
+            .with_index(index as u8)
+            .with_exists(false)
+            .write(&mut self.save_file, index);
+
+        self.pending_slots[index] = SlotContent::Empty;
+        self.set_course(index, None);
+
+        Ok(())
+    }
+
+    /// Write all pending changes to the storage.
+    ///
+    /// Every file which is about to change is copied to a new timestamped directory
+    /// inside the `backup` directory of the save folder, whose name is returned.
+    /// The `backup` directory has to be left out when copying the save folder back to a console.
+    ///
+    /// Before the first file of the save folder changes, the name of the backup is written
+    /// to the journal file `save.journal`, which is removed once `save.dat` has been replaced.
+    /// New course files are written to a staging name first
+    /// and course files which only changed their slot are moved to a staging name,
+    /// so that any permutation of slots can be applied.
+    /// `save.dat` is replaced last.
+    /// If any step fails, the save folder is restored from the backup
+    /// and the pending changes are kept, so that saving can be retried.
+    /// If the save folder can not be restored either, e.g. because the process has been killed,
+    /// the journal is left behind, so the save is [interrupted](Save::is_interrupted)
+    /// and has to be [recovered](Save::recover).
+    ///
+    /// Backups beyond the [backup limit](Save::set_backup_limit) are removed afterwards, oldest first.
+    pub fn save(&mut self) -> Result<String> {
+        if self.interrupted {
+            return Err(SaveError::InterruptedSave.into());
+        }
+        let mut added = vec![];
+        for (index, slot) in self.pending_slots.iter().enumerate() {
+            if *slot == SlotContent::Added {
+                let slot = SaveSlot::from_save_index(index as u8)?;
+                added.push((index as u8, self.get_course_files(slot)?));
+            }
+        }
+        let save_data = self.encode_save_file();
+
+        let backup = self.create_backup()?;
+        let written = self
+            .storage
+            .write(SAVE_JOURNAL, backup.as_bytes())
+            .map_err(Error::from)
+            .and_then(|_| self.write_pending(added, save_data))
+            .and_then(|_| Ok(self.storage.remove(SAVE_JOURNAL)?));
+        if let Err(err) = written {
+            let rolled_back = self
+                .remove_staged_files()
+                .and_then(|_| Save::restore_files(&mut self.storage, &backup))
+                .and_then(|_| self.remove_journal());
+            self.interrupted = rolled_back.is_err();
+            return Err(err);
+        }
+
+        for index in 0..180 {
+            let exists = self.pending_slots[index] != SlotContent::Empty;
+            self.saved_slots[index] = exists;
+            self.pending_slots[index] = if exists {
+                SlotContent::Saved(index as u8)
+            } else {
+                SlotContent::Empty
+            };
+        }
+        self.remove_old_backups()?;
+        Ok(backup)
+    }
+
+    /// Returns `true`, if the journal or staged files of an interrupted [`Save::save`]
+    /// have been found or a failed save could not be rolled back.
+    ///
+    /// The save folder might be in an inconsistent state,
+    /// so saving fails until the save has been [recovered](Save::recover).
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted
+    }
+
+    /// Roll back an [interrupted](Save::is_interrupted) save by restoring the backup
+    /// named in its journal, which has been created right before the interrupted save
+    /// started to change any files, and reload the save.
+    /// Without a journal, the newest backup is restored.
+    ///
+    /// The journal is removed last, so recovering can be retried, if it fails as well.
+    /// All pending changes are discarded.
+    /// Returns the restored backup or `None`, if the save has not been interrupted.
+    pub fn recover(&mut self) -> Result<Option<String>> {
+        if !self.interrupted {
+            return Ok(None);
+        }
+        let backup = if self.storage.exists(SAVE_JOURNAL)? {
+            String::from_utf8_lossy(&self.storage.read(SAVE_JOURNAL)?).into_owned()
+        } else {
+            self.list_backups()?
+                .pop()
+                .ok_or_else(|| SaveError::BackupNotFound("backup".to_string()))?
         };
-        let offset = SAVE_COURSE_OFFSET as usize + 0x10 + index as usize * 8;
-        self.save_file[offset + 1] = 1;
-        let course = SavedCourse::new(*array_ref!(&self.save_file[..], offset, 8), course);
+        self.remove_staged_files()?;
+        Save::restore_files(&mut self.storage, &backup)?;
+        self.remove_journal()?;
+        self.reload()?;
+        Ok(Some(backup))
+    }
+
+    /// All complete backups, which have been created by [`Save::save`] and [`Save::repair`],
+    /// from oldest to newest.
+    pub fn list_backups(&self) -> Result<Vec<String>> {
+        let mut backups = vec![];
+        for dir in self.storage.list_dirs("backup")? {
+            let backup = format!("backup/{}", dir);
+            // `save.dat` is copied last, so a backup without it is incomplete
+            if self.storage.exists(&format!("{}/save.dat", backup))? {
+                let (timestamp, suffix) = dir.split_once('_').unwrap_or((&dir, "0"));
+                let order = (timestamp.parse().unwrap_or(0), suffix.parse().unwrap_or(0));
+                backups.push((order, backup));
+            }
+        }
+        backups.sort();
+        Ok(backups
+            .into_iter()
+            .map(|(_, backup): ((u128, u32), _)| backup)
+            .collect())
+    }
 
-        self.pending_fs_operations[index as usize] = Some(PendingFsOperation::AddOrReplace(index));
+    /// Limit the amount of backups, which are kept after saving or repairing.
+    ///
+    /// With a limit of zero, backups are only kept during a save, so that it can be rolled back,
+    /// which avoids growing in-memory storages.
+    /// `None` keeps all backups, which is the default.
+    pub fn set_backup_limit(&mut self, backup_limit: Option<usize>) {
+        self.backup_limit = backup_limit;
+    }
 
-        index %= 60;
-        courses[index as usize] = Some(Box::new(CourseEntry::SavedCourse(course)));
+    pub fn get_backup_limit(&self) -> Option<usize> {
+        self.backup_limit
+    }
 
+    /// Remove the oldest backups beyond the backup limit.
+    fn remove_old_backups(&mut self) -> Result<()> {
+        let Some(backup_limit) = self.backup_limit else {
+            return Ok(());
+        };
+        let backups = self.list_backups()?;
+        let count = backups.len().saturating_sub(backup_limit);
+        for backup in backups.into_iter().take(count) {
+            self.remove_backup(&backup)?;
+        }
         Ok(())
     }
 
-    pub fn swap_course(&mut self, first: u8, second: u8) -> Result<()> {
-        let courses_as_cell = [
-            Cell::from_mut(&mut self.own_courses[..]).as_slice_of_cells(),
-            Cell::from_mut(&mut self.unknown_courses[..]).as_slice_of_cells(),
-            Cell::from_mut(&mut self.downloaded_courses[..]).as_slice_of_cells(),
-        ];
+    fn remove_backup(&mut self, backup: &str) -> Result<()> {
+        for name in self.storage.list(backup)? {
+            self.storage.remove(&format!("{}/{}", backup, name))?;
+        }
+        self.storage.remove_dir(backup)?;
+        Ok(())
+    }
 
-        if first >= 180 {
-            return Err(SaveError::CourseIndexOutOfBounds(first).into());
+    /// Roll the save folder back to the newest backup, which has been created by [`Save::save`],
+    /// remove that backup and reload the save.
+    ///
+    /// A backup only holds the files, which have been changed by the save that created it,
+    /// so restoring an older backup on its own would mix files of different saves.
+    /// Fails with [`SaveError::BackupNotNewest`] for any other backup.
+    /// Older backups can be restored one after another, newest first,
+    /// because every restored backup is removed.
+    ///
+    /// All pending changes are discarded.
+    pub fn restore_backup(&mut self, backup: &str) -> Result<()> {
+        match self.list_backups()?.last() {
+            Some(newest) if newest == backup => {}
+            Some(_) => return Err(SaveError::BackupNotNewest(backup.to_string()).into()),
+            None => return Err(SaveError::BackupNotFound(backup.to_string()).into()),
         }
-        let first_course = &courses_as_cell[first as usize / 60][first as usize % 60];
+        self.remove_staged_files()?;
+        Save::restore_files(&mut self.storage, backup)?;
+        self.remove_journal()?;
+        self.remove_backup(backup)?;
+        self.reload()
+    }
 
-        if second >= 180 {
-            return Err(SaveError::CourseIndexOutOfBounds(second).into());
+    /// Check the files of the save in the storage for problems, which can be fixed by [`Save::repair`].
+    ///
+    /// Every course file of an occupied slot is decrypted.
+    /// Pending changes are not taken into account.
+    ///
+    /// If the save is [interrupted](Save::is_interrupted), only [`SaveIssue::InterruptedSave`] is reported,
+    /// because the course files do not match `save.dat` until the save has been recovered.
+    pub fn check(&self) -> Result<Vec<SaveIssue>> {
+        let files: Vec<String> = self.storage.list("")?;
+        let staged: Vec<String> = files
+            .iter()
+            .filter(|name| *name == SAVE_JOURNAL || is_staged_file(name))
+            .cloned()
+            .collect();
+        if self.interrupted || !staged.is_empty() {
+            return Ok(vec![SaveIssue::InterruptedSave(staged)]);
         }
-        let second_course = &courses_as_cell[second as usize / 60][second as usize % 60];
 
-        let first_offset = SAVE_COURSE_OFFSET as usize + 0x10 + first as usize * 8 + 1;
-        let second_offset = SAVE_COURSE_OFFSET as usize + 0x10 + second as usize * 8 + 1;
-        match (self.save_file[first_offset], self.save_file[second_offset]) {
-            (1, 0) => {
-                self.pending_fs_operations[first as usize] =
-                    Some(PendingFsOperation::Move(first, second));
-                self.save_file[first_offset] = 0;
-                self.save_file[second_offset] = 1;
+        let save_file = Save::read_save_file(&self.storage)?;
+        let mut issues = vec![];
+
+        let offset = SAVE_COURSE_OFFSET as usize;
+        let expected = calc_crc32(&save_file[offset..]);
+        let found = u32::from_le_bytes(*array_ref!(save_file, offset + 0x8, 4));
+        if expected != found {
+            issues.push(SaveIssue::ChecksumMismatch { expected, found });
+        }
+
+        let mut referenced = vec![];
+        for slot in SaveSection::ALL.iter().flat_map(|section| section.slots()) {
+            let index = slot.get_save_index();
+            let entry = SlotEntry::read(&save_file, index as usize);
+            if entry.get_index() != index {
+                issues.push(SaveIssue::EntryIndexMismatch {
+                    slot,
+                    index: entry.get_index(),
+                });
+            }
+            if !entry.exists() {
+                continue;
+            }
+
+            let (course_name, thumb_name) = get_course_names(index);
+            let missing: Vec<String> = [&course_name, &thumb_name]
+                .into_iter()
+                .filter(|name| !files.contains(name))
+                .cloned()
+                .collect();
+            if !missing.is_empty() {
+                issues.push(SaveIssue::MissingFiles { slot, missing });
+            } else {
+                let mut course_data = self.storage.read(&course_name)?;
+                let thumb_data = self.storage.read(&thumb_name)?;
+                if let Err(err) =
+                    Course2::_from_switch_files(&mut course_data, Some(thumb_data), true)
+                {
+                    issues.push(SaveIssue::CorruptedCourse {
+                        slot,
+                        error: err.to_string(),
+                    });
+                }
             }
-            (0, 1) => {
-                self.pending_fs_operations[first as usize] =
-                    Some(PendingFsOperation::Move(second, first));
-                self.save_file[first_offset] = 1;
-                self.save_file[second_offset] = 0;
+            referenced.push(course_name);
+            referenced.push(thumb_name);
+        }
+
+        for name in files {
+            if is_course_file(&name) && !referenced.contains(&name) {
+                issues.push(SaveIssue::OrphanFile(name));
             }
-            (1, 1) => {
-                self.pending_fs_operations[first as usize] =
-                    Some(PendingFsOperation::Swap(first, second))
+        }
+        Ok(issues)
+    }
+
+    /// Fix all issues, which are found by [`Save::check`], and reload the save.
+    ///
+    /// An interrupted save is [recovered](Save::recover) first
+    /// and the save is checked again afterwards.
+    /// Slots with missing or corrupted course files are marked as empty.
+    /// Their remaining course files and all orphan files are moved
+    /// to a new timestamped directory inside the `quarantine` directory of the save folder.
+    /// Wrong slot numbers and the checksum of `save.dat` are corrected.
+    /// `save.dat` is copied to a new backup directory first, just like by [`Save::save`].
+    ///
+    /// All pending changes are discarded. Returns the issues, which have been fixed.
+    pub fn repair(&mut self) -> Result<Vec<SaveIssue>> {
+        if !self.interrupted {
+            self.reload()?;
+        }
+        let mut issues = self.check()?;
+        if let [SaveIssue::InterruptedSave(_)] = issues[..] {
+            self.recover()?;
+            issues.extend(self.check()?);
+            if issues.len() == 1 {
+                return Ok(issues);
             }
-            (_, _) => return Err(SaveError::CourseNotFound(first).into()),
         }
+        if issues.is_empty() {
+            return Ok(issues);
+        }
+        self.create_backup()?;
 
-        first_course.swap(second_course);
+        let quarantine = self.get_timestamped_dir("quarantine")?;
+        let files = self.storage.list("")?;
+        let mut quarantined = vec![];
+        for issue in issues.iter() {
+            match issue {
+                SaveIssue::EntryIndexMismatch { slot, .. } => {
+                    let index = slot.get_save_index() as usize;
+                    SlotEntry::read(&self.save_file, index)
+                        .with_index(index as u8)
+                        .write(&mut self.save_file, index);
+                }
+                SaveIssue::MissingFiles { slot, .. } | SaveIssue::CorruptedCourse { slot, .. } => {
+                    let index = slot.get_save_index();
+                    SlotEntry::read(&self.save_file, index as usize)
+                        .with_index(index)
+                        .with_exists(false)
+                        .write(&mut self.save_file, index as usize);
+                    let (course_name, thumb_name) = get_course_names(index);
+                    quarantined.extend(
+                        [course_name, thumb_name]
+                            .into_iter()
+                            .filter(|name| files.contains(name)),
+                    );
+                }
+                SaveIssue::OrphanFile(name) => quarantined.push(name.clone()),
+                SaveIssue::ChecksumMismatch { .. } | SaveIssue::InterruptedSave(_) => {}
+            }
+        }
+        for name in quarantined {
+            self.storage
+                .rename(&name, &format!("{}/{}", quarantine, name))?;
+        }
 
-        Ok(())
+        let save_data = self.encode_save_file();
+        self.storage.write("save.dat.new", &save_data)?;
+        self.storage.rename("save.dat.new", "save.dat")?;
+
+        self.reload()?;
+        self.remove_old_backups()?;
+        Ok(issues)
     }
 
-    pub fn remove_course(&mut self, mut index: u8) -> Result<()> {
-        let courses = match index {
-            i if i < 60 => &mut self.own_courses,
-            i if (60..120).contains(&i) => &mut self.unknown_courses,
-            i if (120..180).contains(&i) => &mut self.downloaded_courses,
-            _ => return Err(SaveError::CourseIndexOutOfBounds(index).into()),
-        };
-        let offset = SAVE_COURSE_OFFSET as usize + 0x10 + index as usize * 8;
-        self.save_file[offset + 1] = 0;
+    /// Async version of [`Save::save`].
+    pub async fn save_async(&mut self) -> Result<String> {
+        self.save()
+    }
+
+    /// Async version of [`Save::recover`].
+    pub async fn recover_async(&mut self) -> Result<Option<String>> {
+        self.recover()
+    }
+
+    /// Async version of [`Save::restore_backup`].
+    pub async fn restore_backup_async(&mut self, backup: &str) -> Result<()> {
+        self.restore_backup(backup)
+    }
+
+    /// Async version of [`Save::check`].
+    pub async fn check_async(&self) -> Result<Vec<SaveIssue>> {
+        self.check()
+    }
+
+    /// Async version of [`Save::repair`].
+    pub async fn repair_async(&mut self) -> Result<Vec<SaveIssue>> {
+        self.repair()
+    }
+
+    pub fn get_storage(&self) -> &S {
+        &self.storage
+    }
+
+    pub fn into_storage(self) -> S {
+        self.storage
+    }
 
-        self.pending_fs_operations[index as usize] = Some(PendingFsOperation::Remove(index));
+    /// Name of a new timestamped directory inside of `parent`, which does not contain any files yet.
+    fn get_timestamped_dir(&self, parent: &str) -> Result<String> {
+        #[cfg(target_arch = "wasm32")]
+        let timestamp = js_sys::Date::now() as u128;
+        #[cfg(not(target_arch = "wasm32"))]
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let mut dir = format!("{}/{}", parent, timestamp);
+        let mut suffix = 0;
+        while !self.storage.list(&dir)?.is_empty() {
+            suffix += 1;
+            dir = format!("{}/{}_{}", parent, timestamp, suffix);
+        }
+        Ok(dir)
+    }
+
+    /// Copy `save.dat` and the course files of all slots with pending changes to a new backup directory.
+    ///
+    /// `save.dat` is copied last, so that incomplete backups can be told apart.
+    fn create_backup(&mut self) -> Result<String> {
+        let backup = self.get_timestamped_dir("backup")?;
+
+        for index in 0..180 {
+            if self.saved_slots[index]
+                && self.pending_slots[index] != SlotContent::Saved(index as u8)
+            {
+                let (course_name, thumb_name) = get_course_names(index as u8);
+                for name in [course_name, thumb_name] {
+                    let data = self.storage.read(&name)?;
+                    self.storage.write(&format!("{}/{}", backup, name), &data)?;
+                }
+            }
+        }
+        let save_data = self.storage.read("save.dat")?;
+        self.storage
+            .write(&format!("{}/save.dat", backup), &save_data)?;
+        Ok(backup)
+    }
 
-        index %= 60;
-        courses[index as usize] = None;
+    fn write_pending(&mut self, added: Vec<(u8, CourseFiles)>, save_data: Vec<u8>) -> Result<()> {
+        for (index, (course_data, thumb_data)) in added.iter() {
+            let (course_name, thumb_name) = get_staged_names(*index, "new");
+            self.storage.write(&course_name, course_data)?;
+            self.storage.write(&thumb_name, thumb_data)?;
+        }
+        let moved: Vec<(u8, u8)> = self
+            .pending_slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| match slot {
+                SlotContent::Saved(from) if *from as usize != index => Some((*from, index as u8)),
+                _ => None,
+            })
+            .collect();
+
+        for (from, _) in moved.iter() {
+            let (course_name, thumb_name) = get_course_names(*from);
+            let (staged_course_name, staged_thumb_name) = get_staged_names(*from, "swap");
+            self.storage.rename(&course_name, &staged_course_name)?;
+            self.storage.rename(&thumb_name, &staged_thumb_name)?;
+        }
+        for index in 0..180 {
+            let is_used = self
+                .pending_slots
+                .contains(&SlotContent::Saved(index as u8));
+            if self.saved_slots[index]
+                && !is_used
+                && self.pending_slots[index] == SlotContent::Empty
+            {
+                let (course_name, thumb_name) = get_course_names(index as u8);
+                self.storage.remove(&course_name)?;
+                self.storage.remove(&thumb_name)?;
+            }
+        }
+        for (from, to) in moved {
+            let (staged_course_name, staged_thumb_name) = get_staged_names(from, "swap");
+            let (course_name, thumb_name) = get_course_names(to);
+            self.storage.rename(&staged_course_name, &course_name)?;
+            self.storage.rename(&staged_thumb_name, &thumb_name)?;
+        }
+        for (index, _) in added {
+            let (staged_course_name, staged_thumb_name) = get_staged_names(index, "new");
+            let (course_name, thumb_name) = get_course_names(index);
+            self.storage.rename(&staged_course_name, &course_name)?;
+            self.storage.rename(&staged_thumb_name, &thumb_name)?;
+        }
 
+        self.storage.write("save.dat.new", &save_data)?;
+        self.storage.rename("save.dat.new", "save.dat")?;
         Ok(())
     }
 
-    pub async fn save(&mut self) -> Result<()> {
-        let mut update_save = true;
-        for i in 0..180 {
-            if let Some(op) = self.pending_fs_operations[i].take() {
-                op.run(
-                    self.path.clone(),
-                    &self.own_courses,
-                    &self.unknown_courses,
-                    &self.downloaded_courses,
-                )
-                .await?;
-                update_save = true;
+    /// Remove files, which have been left behind by an incomplete call to [`Save::save`].
+    fn remove_staged_files(&mut self) -> Result<()> {
+        for name in self.storage.list("")? {
+            if is_staged_file(&name) {
+                self.storage.remove(&name)?;
             }
         }
-        if update_save {
-            let offset = SAVE_COURSE_OFFSET as usize;
-            fix_crc32(&mut self.save_file[offset..]);
-            let mut save_data = self.save_file.clone();
-            let aes_info = encrypt(&mut save_data[0x10..], &SAVE_KEY_TABLE);
-            save_data.extend_from_slice(&aes_info);
+        Ok(())
+    }
 
-            let mut save_path = self.path.clone();
-            save_path.push("save.dat");
-            let mut save_file = File::create(save_path).await?;
-            save_file.write_all(&save_data).await?;
+    fn remove_journal(&mut self) -> Result<()> {
+        if self.storage.exists(SAVE_JOURNAL)? {
+            self.storage.remove(SAVE_JOURNAL)?;
         }
         Ok(())
     }
 
+    /// Copy all files of a backup into the save folder
+    /// and remove the course files of all slots, which are empty in the restored `save.dat`.
+    fn restore_files(storage: &mut S, backup: &str) -> Result<()> {
+        let names = storage.list(backup)?;
+        if !names.iter().any(|name| name == "save.dat") {
+            return Err(SaveError::BackupNotFound(backup.to_string()).into());
+        }
+        for name in names {
+            let data = storage.read(&format!("{}/{}", backup, name))?;
+            storage.write(&name, &data)?;
+        }
+
+        let save_file = Save::read_save_file(storage)?;
+        let offset = SAVE_COURSE_OFFSET as usize + 0x10;
+        for index in 0..180 {
+            if save_file[offset + index * 8 + 1] == 0 {
+                let (course_name, thumb_name) = get_course_names(index as u8);
+                for name in [course_name, thumb_name] {
+                    if storage.exists(&name)? {
+                        storage.remove(&name)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Fix the checksum of `save.dat` and encrypt it.
+    fn encode_save_file(&mut self) -> Vec<u8> {
+        let offset = SAVE_COURSE_OFFSET as usize;
+        fix_crc32(&mut self.save_file[offset..]);
+        let mut save_data = self.save_file.clone();
+        let aes_info = encrypt(&mut save_data[0x10..], &SAVE_KEY_TABLE);
+        save_data.extend_from_slice(&aes_info);
+        save_data
+    }
+
+    fn read_save_file(storage: &S) -> Result<Vec<u8>> {
+        let mut save_file = storage.read("save.dat")?;
+        decrypt(&mut save_file[0x10..], &SAVE_KEY_TABLE)?;
+        Ok(save_file[..save_file.len() - 0x30].to_vec())
+    }
+
+    /// Encrypted course data and thumbnail of a course, which will be written to the storage.
+    fn get_course_files(&self, slot: SaveSlot) -> Result<CourseFiles> {
+        match self.get_course(slot) {
+            Some(CourseEntry::SavedCourse(course)) => {
+                let course = &course.course;
+                let mut course_data = course.get_course_data().to_vec();
+                Course2::encrypt_vec(&mut course_data, false);
+                let thumb_data = course.get_course_thumb().ok_or_else(|| -> Error {
+                    SaveError::ThumbnailRequired(
+                        course.get_course().get_header().get_title().to_string(),
+                    )
+                    .into()
+                })?;
+                #[cfg(target_arch = "wasm32")]
+                let thumb_data = thumb_data.clone().get_encrypted();
+                #[cfg(not(target_arch = "wasm32"))]
+                let thumb_data = thumb_data.get_encrypted().to_vec();
+                Ok((course_data, thumb_data))
+            }
+            Some(CourseEntry::CorruptedCourse(err)) => {
+                Err(SaveError::CorruptedCourse(err.clone()).into())
+            }
+            None => Err(SaveError::CourseNotFound(slot).into()),
+        }
+    }
+
+    /// Courses of all slots of a section.
+    pub fn get_courses(&self, section: SaveSection) -> &Courses {
+        self.get_section(match section {
+            SaveSection::Own => 0,
+            SaveSection::OwnExtended => 1,
+            SaveSection::Downloaded => 2,
+        })
+    }
+
     pub fn get_own_courses(&self) -> &Courses {
-        &self.own_courses
+        self.get_courses(SaveSection::Own)
     }
 
+    pub fn get_own_extended_courses(&self) -> &Courses {
+        self.get_courses(SaveSection::OwnExtended)
+    }
+
+    #[deprecated(note = "use `get_own_extended_courses` instead")]
     pub fn get_unknown_courses(&self) -> &Courses {
-        &self.unknown_courses
+        self.get_own_extended_courses()
     }
 
     pub fn get_downloaded_courses(&self) -> &Courses {
-        &self.downloaded_courses
+        self.get_courses(SaveSection::Downloaded)
     }
 }
 
+/// Files of a [`MemoryStorage`], which have been written or removed by [`Save::save`].
+///
+/// Backups are not included.
+#[cfg(any(target_arch = "wasm32", test))]
+#[derive(Clone, Debug, Default, PartialEq)]
+struct ChangedFiles {
+    written: BTreeMap<String, Vec<u8>>,
+    removed: Vec<String>,
+}
+
+#[cfg(any(target_arch = "wasm32", test))]
+impl ChangedFiles {
+    fn new(before: &BTreeMap<String, Vec<u8>>, after: &BTreeMap<String, Vec<u8>>) -> ChangedFiles {
+        let is_backup = |name: &String| name.starts_with("backup/");
+        ChangedFiles {
+            written: after
+                .iter()
+                .filter(|(name, data)| !is_backup(name) && before.get(*name) != Some(data))
+                .map(|(name, data)| (name.clone(), data.clone()))
+                .collect(),
+            removed: before
+                .keys()
+                .filter(|name| !is_backup(name) && !after.contains_key(*name))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Super Mario Maker 2 save folder for WebAssembly, whose files are kept in memory.
+///
+/// Slots are addressed by their number in `save.dat` from 0 to 179.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(js_name = Save)]
+pub struct JsSave {
+    save: Save<MemoryStorage>,
+}
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(js_class = Save)]
+impl JsSave {
+    /// Load a save from an object, which maps file names to their content,
+    /// e.g. `{ "save.dat": Uint8Array, "course_data_000.bcd": Uint8Array, ... }`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(files: &js_sys::Object) -> JsResult<JsSave> {
+        let mut storage = MemoryStorage::new();
+        for entry in js_sys::Object::entries(files).iter() {
+            let entry: js_sys::Array = entry.into();
+            let name = entry
+                .get(0)
+                .as_string()
+                .ok_or_else(|| JsValue::from_str("file name must be a string"))?;
+            let data = js_sys::Uint8Array::new(&entry.get(1)).to_vec();
+            storage.write(&name, &data).map_err(Error::from)?;
+        }
+        let mut save = Save::from_storage(storage)?;
+        // the files are returned to the caller, who has to keep backups on their own
+        save.set_backup_limit(Some(0));
+        Ok(JsSave { save })
+    }
+
+    #[wasm_bindgen(js_name = addCourse)]
+    pub fn add_course(&mut self, index: u8, course: &Course2) -> JsResult<()> {
+        let slot = SaveSlot::from_save_index(index)?;
+        Ok(self.save.add_course(slot, course.clone())?)
+    }
+
+    #[wasm_bindgen(js_name = swapCourse)]
+    pub fn swap_course(&mut self, first: u8, second: u8) -> JsResult<()> {
+        let first = SaveSlot::from_save_index(first)?;
+        let second = SaveSlot::from_save_index(second)?;
+        Ok(self.save.swap_course(first, second)?)
+    }
+
+    #[wasm_bindgen(js_name = removeCourse)]
+    pub fn remove_course(&mut self, index: u8) -> JsResult<()> {
+        let slot = SaveSlot::from_save_index(index)?;
+        Ok(self.save.remove_course(slot)?)
+    }
+
+    /// Course at the given slot or `undefined`, if the slot is empty.
+    ///
+    /// Throws, if the course is corrupted.
+    #[wasm_bindgen(js_name = getCourse)]
+    pub fn get_course(&self, index: u8) -> JsResult<Option<Course2>> {
+        let slot = SaveSlot::from_save_index(index)?;
+        match self.save.get_course(slot) {
+            Some(CourseEntry::SavedCourse(course)) => Ok(Some(course.get_course().clone())),
+            Some(CourseEntry::CorruptedCourse(err)) => {
+                Err(Error::from(SaveError::CorruptedCourse(err.clone())).into())
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Apply all pending changes and return the files, which have to be written back.
+    ///
+    /// Returns an object with the written files as `written`, which maps file names to their content,
+    /// and the names of the removed files as `removed`.
+    pub fn save(&mut self) -> JsResult<js_sys::Object> {
+        let before = self.save.get_storage().get_files().clone();
+        self.save.save()?;
+        let changed_files = ChangedFiles::new(&before, self.save.get_storage().get_files());
+
+        let written = js_sys::Object::new();
+        for (name, data) in changed_files.written {
+            js_sys::Reflect::set(
+                &written,
+                &JsValue::from_str(&name),
+                &js_sys::Uint8Array::from(&data[..]),
+            )?;
+        }
+        let removed: js_sys::Array = changed_files
+            .removed
+            .into_iter()
+            .map(|name| JsValue::from_str(&name))
+            .collect();
+        let res = js_sys::Object::new();
+        js_sys::Reflect::set(&res, &JsValue::from_str("written"), &written)?;
+        js_sys::Reflect::set(&res, &JsValue::from_str("removed"), &removed)?;
+        Ok(res)
+    }
+}
+
+/// Offset of the entry of a slot in `save.dat`.
+fn get_entry_offset(index: usize) -> usize {
+    SAVE_COURSE_OFFSET as usize + 0x10 + index * 8
+}
+
+fn get_course_names(index: u8) -> (String, String) {
+    (
+        format!("course_data_{:0>3}.bcd", index),
+        format!("course_thumb_{:0>3}.btl", index),
+    )
+}
+
+/// Whether a file in the save folder is a course file.
+fn is_course_file(name: &str) -> bool {
+    (name.starts_with("course_data_") || name.starts_with("course_thumb_"))
+        && (name.ends_with(".bcd") || name.ends_with(".btl"))
+}
+
+/// Journal of a running [`Save::save`], which holds the name of its backup.
+const SAVE_JOURNAL: &str = "save.journal";
+
+/// Returns `true` for files, which only exist while [`Save::save`] is running.
+///
+/// The [journal](SAVE_JOURNAL) is not included, because it has to outlive all other staged files.
+fn is_staged_file(name: &str) -> bool {
+    name.starts_with("new_") || name.starts_with("swap_") || name == "save.dat.new"
+}
+
+/// Temporary names of course files, which are written to or moved to another slot.
+fn get_staged_names(index: u8, prefix: &str) -> (String, String) {
+    (
+        format!("{}_{:0>3}.bcd", prefix, index),
+        format!("{}_{:0>3}.btl", prefix, index),
+    )
+}
+
 #[derive(Clone, Debug)]
 pub enum CourseEntry {
     SavedCourse(SavedCourse),
@@ -225,148 +1104,319 @@ pub enum CourseEntry {
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct SavedCourse {
-    index: u8,
-    exists: u8,
-    buf: [u8; 8],
+    entry: SlotEntry,
     course: Course2,
 }
 
 impl SavedCourse {
-    fn new(buf: [u8; 8], course: Course2) -> SavedCourse {
-        SavedCourse {
+    pub fn get_index(&self) -> u8 {
+        self.entry.index
+    }
+
+    pub fn get_entry(&self) -> &SlotEntry {
+        &self.entry
+    }
+
+    pub fn get_course(&self) -> &Course2 {
+        &self.course
+    }
+}
+
+/// Problem of a save, which is reported by [`Save::check`] and fixed by [`Save::repair`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SaveIssue {
+    /// The checksum of the course slots in `save.dat` does not match.
+    ChecksumMismatch { expected: u32, found: u32 },
+    /// The entry of a slot in `save.dat` contains the number of another slot.
+    EntryIndexMismatch { slot: SaveSlot, index: u8 },
+    /// A slot is marked as occupied, but some of its course files do not exist.
+    MissingFiles {
+        slot: SaveSlot,
+        missing: Vec<String>,
+    },
+    /// The course files of an occupied slot can not be decrypted or parsed.
+    CorruptedCourse { slot: SaveSlot, error: String },
+    /// A course file, which does not belong to an occupied slot.
+    OrphanFile(String),
+    /// A [`Save::save`] has been interrupted and left these files behind, e.g. its journal.
+    InterruptedSave(Vec<String>),
+}
+
+/// Entry of a course slot in `save.dat`, which consists of 8 bytes.
+///
+/// | Offset | Size | Content                          |
+/// |--------|------|----------------------------------|
+/// | 0x00   | 1    | number of the slot from 0 to 179 |
+/// | 0x01   | 1    | 1 if the slot holds a course     |
+/// | 0x02   | 6    | reserved                         |
+///
+/// The reserved bytes are not decoded.
+/// They have only been checked in a single save,
+/// which holds downloaded courses and no own courses, and are zero there.
+/// Their meaning for own courses is unknown, so they are never changed:
+/// they move with their course, when courses are swapped,
+/// and are kept, when a course is added to or removed from a slot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SlotEntry {
+    index: u8,
+    exists: bool,
+    reserved: [u8; 6],
+}
+
+impl SlotEntry {
+    /// Entry of an occupied slot, whose reserved bytes are zero.
+    pub fn new(slot: SaveSlot) -> SlotEntry {
+        SlotEntry {
+            exists: true,
+            ..SlotEntry::empty(slot)
+        }
+    }
+
+    /// Entry of an empty slot, whose reserved bytes are zero.
+    pub fn empty(slot: SaveSlot) -> SlotEntry {
+        SlotEntry {
+            index: slot.get_save_index(),
+            exists: false,
+            reserved: [0; 6],
+        }
+    }
+
+    pub fn from_bytes(buf: [u8; 8]) -> SlotEntry {
+        SlotEntry {
             index: buf[0],
-            exists: buf[1],
-            buf,
-            course,
+            exists: buf[1] != 0,
+            reserved: *array_ref!(buf, 2, 6),
         }
     }
 
+    pub fn to_bytes(&self) -> [u8; 8] {
+        let mut buf = [0; 8];
+        buf[0] = self.index;
+        buf[1] = self.exists as u8;
+        buf[2..].copy_from_slice(&self.reserved);
+        buf
+    }
+
+    /// Number of the slot in `save.dat` and in the names of course files.
     pub fn get_index(&self) -> u8 {
         self.index
     }
 
-    pub fn get_course(&self) -> &Course2 {
-        &self.course
+    pub fn get_slot(&self) -> Result<SaveSlot> {
+        SaveSlot::from_save_index(self.index)
+    }
+
+    pub fn exists(&self) -> bool {
+        self.exists
+    }
+
+    pub fn get_reserved(&self) -> &[u8; 6] {
+        &self.reserved
+    }
+
+    fn with_index(self, index: u8) -> SlotEntry {
+        SlotEntry { index, ..self }
+    }
+
+    fn with_exists(self, exists: bool) -> SlotEntry {
+        SlotEntry { exists, ..self }
+    }
+
+    fn read(save_file: &[u8], index: usize) -> SlotEntry {
+        SlotEntry::from_bytes(*array_ref!(save_file, get_entry_offset(index), 8))
+    }
+
+    fn write(&self, save_file: &mut [u8], index: usize) {
+        let offset = get_entry_offset(index);
+        save_file[offset..offset + 8].copy_from_slice(&self.to_bytes());
     }
 }
 
-#[derive(Clone, Debug)]
-enum PendingFsOperation {
-    AddOrReplace(u8),
-    Swap(u8, u8),
-    Move(u8, u8),
-    Remove(u8),
+/// Content of a course slot after the next call to [`Save::save`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SlotContent {
+    Empty,
+    /// Course files which are currently stored on disk in the given slot.
+    Saved(u8),
+    /// Course which has been added and still has to be written to disk.
+    Added,
 }
 
-impl PendingFsOperation {
-    async fn run(
-        self,
-        path: PathBuf,
-        own_courses: &Courses,
-        unknown_courses: &Courses,
-        downloaded_courses: &Courses,
-    ) -> Result<()> {
-        match self {
-            Self::AddOrReplace(index) => {
-                let course = match index {
-                    i if i < 60 => own_courses[index as usize].as_ref().unwrap(),
-                    i if (60..120).contains(&i) => {
-                        unknown_courses[index as usize].as_ref().unwrap()
-                    }
-                    i if (120..180).contains(&i) => {
-                        downloaded_courses[index as usize].as_ref().unwrap()
-                    }
-                    _ => return Err(SaveError::CourseIndexOutOfBounds(index).into()),
-                };
+#[cfg(test)]
+mod test {
+    use super::*;
 
-                let mut course_path = path.clone();
-                course_path.push(format!("course_data_{:0>3}.bcd", index));
-                let mut course_file = File::create(course_path).await?;
-                match &**course {
-                    CourseEntry::SavedCourse(course) => {
-                        let course = &course.course;
-                        let mut course_data = course.get_course_data().to_vec();
-                        Course2::encrypt(&mut course_data);
-                        course_file.write_all(&course_data).await?;
-
-                        let thumb_data = course.get_course_thumb().ok_or_else(|| -> Error {
-                            SaveError::ThumbnailRequired(
-                                course.get_course().get_header().get_title().to_string(),
-                            )
-                            .into()
-                        })?;
-                        let mut thumb_path = path.clone();
-                        thumb_path.push(format!("course_thumb_{:0>3}.btl", index));
-                        let mut thumb_file = File::create(thumb_path).await?;
-                        thumb_file.write_all(thumb_data.get_encrypted()).await?;
-                    }
-                    CourseEntry::CorruptedCourse(err) => {
-                        return Err(SaveError::CorruptedCourse(err.clone()).into())
+    use crate::save_storage::{MemoryStorage, ZipStorage};
+    use crc::{Crc, CRC_32_ISO_HDLC};
+    use fs_extra::dir::{copy, remove, CopyOptions};
+    use std::{
+        collections::BTreeMap,
+        env::temp_dir,
+        fs::{create_dir, read, read_dir},
+        future::Future,
+        io::{Cursor, Read, Write},
+        path::Path,
+        pin::pin,
+        sync::Arc,
+        task::{Context, Poll, Wake, Waker},
+    };
+    use zip::{write::FileOptions, ZipWriter};
+
+    fn copy_save(name: &str) -> anyhow::Result<PathBuf> {
+        let mut path = temp_dir();
+        path.push(format!("smmdb_{}", name));
+        remove(&path)?;
+        let mut options = CopyOptions::new();
+        options.copy_inside = true;
+        copy("./tests/assets/saves/smm2/save1", &path, &options)?;
+        Ok(path)
+    }
+
+    fn slot(index: u8) -> SaveSlot {
+        SaveSlot::from_save_index(index).unwrap()
+    }
+
+    fn get_course<S: SaveStorage>(save: &Save<S>, index: u8) -> Option<&Course2> {
+        match save.get_course(slot(index)) {
+            Some(CourseEntry::SavedCourse(course)) => Some(course.get_course()),
+            Some(CourseEntry::CorruptedCourse(_)) => panic!("corrupted course at {}", index),
+            None => None,
+        }
+    }
+
+    /// Contents of all files of a save folder.
+    fn read_files(path: &Path) -> anyhow::Result<BTreeMap<String, Vec<u8>>> {
+        let mut files = BTreeMap::new();
+        for entry in read_dir(path)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                files.insert(
+                    entry.file_name().to_string_lossy().to_string(),
+                    read(entry.path())?,
+                );
+            }
+        }
+        Ok(files)
+    }
+
+    /// Simple linear congruential generator, so that the operations are reproducible.
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self, max: u64) -> u64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (self.0 >> 33) % max
+        }
+    }
+
+    /// Apply random operations to a save and to a simulated model of its slots,
+    /// which stores the title of the course in each slot.
+    fn run_operations(name: &str, seed: u64, slots: std::ops::Range<u8>) -> anyhow::Result<()> {
+        let path = copy_save(name)?;
+        let mut save = Save::new(&path)?;
+        let file = include_bytes!("../tests/assets/saves/smm2/save1.zip");
+        let courses = Course2::from_packed(file)?;
+        let title = |course: &Course2| course.get_course().get_header().get_title().to_string();
+        let mut model: Vec<Option<String>> = (0..180)
+            .map(|index| get_course(&save, index).map(title))
+            .collect();
+
+        let mut rng = Lcg(seed);
+        let len = slots.end as u64 - slots.start as u64;
+        for step in 0..200 {
+            let first = slots.start + rng.next(len) as u8;
+            let second = slots.start + rng.next(len) as u8;
+            match rng.next(4) {
+                0 => {
+                    let course = &courses[rng.next(courses.len() as u64) as usize];
+                    save.add_course(slot(first), course.clone())?;
+                    model[first as usize] = Some(title(course));
+                }
+                1 => {
+                    save.remove_course(slot(first))?;
+                    model[first as usize] = None;
+                }
+                _ => {
+                    let res = save.swap_course(slot(first), slot(second));
+                    if model[first as usize].is_none() && model[second as usize].is_none() {
+                        assert!(res.is_err());
+                    } else {
+                        res?;
+                        model.swap(first as usize, second as usize);
                     }
                 }
             }
-            Self::Swap(first, second) => {
-                // TODO only works for immediate swap
-                let mut first_course_path = path.clone();
-                let mut swap_course_path = first_course_path.clone();
-                first_course_path.push(format!("course_data_{:0>3}.bcd", first));
-                swap_course_path.push("swap.bcd");
-                rename(first_course_path.clone(), swap_course_path.clone()).await?;
-
-                let mut first_thumb_path = path.clone();
-                let mut swap_thumb_path = first_thumb_path.clone();
-                first_thumb_path.push(format!("course_thumb_{:0>3}.btl", first));
-                swap_thumb_path.push("swap.btl");
-                rename(first_thumb_path.clone(), swap_thumb_path.clone()).await?;
-
-                let mut second_course_path = path.clone();
-                second_course_path.push(format!("course_data_{:0>3}.bcd", second));
-                rename(second_course_path.clone(), first_course_path).await?;
-
-                let mut second_thumb_path = path.clone();
-                second_thumb_path.push(format!("course_thumb_{:0>3}.btl", second));
-                rename(second_thumb_path.clone(), first_thumb_path).await?;
-
-                rename(swap_course_path, second_course_path).await?;
-                rename(swap_thumb_path, second_thumb_path).await?;
-            }
-            Self::Move(start, end) => {
-                let mut start_course_path = path.clone();
-                let mut end_course_path = start_course_path.clone();
-                start_course_path.push(format!("course_data_{:0>3}.bcd", start));
-                end_course_path.push(format!("course_data_{:0>3}.bcd", end));
-                rename(start_course_path, end_course_path).await?;
-
-                let mut start_thumb_path = path.clone();
-                let mut end_thumb_path = start_thumb_path.clone();
-                start_thumb_path.push(format!("course_thumb_{:0>3}.btl", start));
-                end_thumb_path.push(format!("course_thumb_{:0>3}.btl", end));
-                rename(start_thumb_path, end_thumb_path).await?;
-            }
-            Self::Remove(index) => {
-                let mut course_path = path.clone();
-                course_path.push(format!("course_data_{:0>3}.bcd", index));
-                remove_file(course_path).await?;
-
-                let mut thumb_path = path.clone();
-                thumb_path.push(format!("course_thumb_{:0>3}.btl", index));
-                remove_file(thumb_path).await?;
+            if step % 50 == 49 {
+                save.save()?;
             }
         }
+        save.save()?;
 
+        let save = Save::new(&path)?;
+        for index in 0..180 {
+            assert_eq!(
+                get_course(&save, index).map(title),
+                model[index as usize],
+                "slot {}",
+                index
+            );
+        }
+        let file_count = read_dir(&path)?
+            .filter(|entry| {
+                let name = entry.as_ref().unwrap().file_name();
+                let name = name.to_string_lossy();
+                name.ends_with(".bcd") || name.ends_with(".btl")
+            })
+            .count();
+        assert_eq!(
+            file_count,
+            model.iter().filter(|course| course.is_some()).count() * 2
+        );
+        remove(&path)?;
         Ok(())
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    #[test]
+    pub fn test_save_swap_chains() -> anyhow::Result<()> {
+        let path = copy_save("swap_chains")?;
+        let mut save = Save::new(&path)?;
+        let title = |save: &Save, index| {
+            get_course(save, index)
+                .map(|course| course.get_course().get_header().get_title().to_string())
+        };
+        let titles: Vec<_> = (120..124).map(|index| title(&save, index)).collect();
 
-    use crc::{Crc, CRC_32_ISO_HDLC};
-    use fs_extra::dir::{copy, remove, CopyOptions};
+        save.swap_course(slot(120), slot(121))?;
+        save.swap_course(slot(121), slot(122))?;
+        save.swap_course(slot(119), slot(120))?;
+        save.swap_course(slot(123), slot(120))?;
+        save.save()?;
+
+        let save = Save::new(&path)?;
+        assert_eq!(title(&save, 119), titles[1]);
+        assert_eq!(title(&save, 120), titles[3]);
+        assert_eq!(title(&save, 121), titles[2]);
+        assert_eq!(title(&save, 122), titles[0]);
+        assert_eq!(title(&save, 123), None);
+        remove(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_save_operations_own_courses() -> anyhow::Result<()> {
+        run_operations("operations_own", 1, 0..8)
+    }
+
+    #[test]
+    pub fn test_save_operations_downloaded_courses() -> anyhow::Result<()> {
+        run_operations("operations_downloaded", 2, 116..128)
+    }
 
-    #[async_std::test]
-    pub async fn test_save() -> anyhow::Result<()> {
+    #[test]
+    pub fn test_save() -> anyhow::Result<()> {
         remove("./tests/assets/saves/smm2/tmp")?;
         let mut options = CopyOptions::new();
         options.copy_inside = true;
@@ -376,20 +1426,20 @@ mod test {
             "tests/assets/saves/smm2/tmp",
             &options,
         )?;
-        let mut save = Save::new("./tests/assets/saves/smm2/tmp").await?;
+        let mut save = Save::new("./tests/assets/saves/smm2/tmp")?;
 
         let file = include_bytes!("../tests/assets/saves/smm2/save1.zip");
         let courses = Course2::from_packed(file)?;
-        save.add_course(0, courses[0].clone())?;
-        save.add_course(1, courses[1].clone())?;
-        save.add_course(2, courses[2].clone())?;
-        save.add_course(5, courses[3].clone())?;
-        save.add_course(6, courses[4].clone())?;
-        save.add_course(9, courses[5].clone())?;
-        save.add_course(12, courses[6].clone())?;
-        save.remove_course(122)?;
-        save.remove_course(126)?;
-        save.save().await?;
+        save.add_course(slot(0), courses[0].clone())?;
+        save.add_course(slot(1), courses[1].clone())?;
+        save.add_course(slot(2), courses[2].clone())?;
+        save.add_course(slot(5), courses[3].clone())?;
+        save.add_course(slot(6), courses[4].clone())?;
+        save.add_course(slot(9), courses[5].clone())?;
+        save.add_course(slot(12), courses[6].clone())?;
+        save.remove_course(slot(122))?;
+        save.remove_course(slot(126))?;
+        save.save()?;
 
         let offset = SAVE_COURSE_OFFSET as usize;
         let crc = Crc::<u32>::new(&CRC_32_ISO_HDLC);
@@ -401,4 +1451,836 @@ mod test {
         assert_eq!(save.save_file[offset + 0xB], bytes[3]);
         Ok(())
     }
+
+    #[test]
+    pub fn test_save_restore_backup() -> anyhow::Result<()> {
+        let path = copy_save("restore_backup")?;
+        let files = read_files(&path)?;
+        let mut save = Save::new(&path)?;
+        let file = include_bytes!("../tests/assets/saves/smm2/save1.zip");
+        let courses = Course2::from_packed(file)?;
+
+        save.swap_course(slot(120), slot(121))?;
+        save.remove_course(slot(122))?;
+        save.add_course(slot(5), courses[0].clone())?;
+        let backup = save.save()?;
+        assert_ne!(read_files(&path)?, files);
+        let backup_files = read_files(&path.join(&backup))?
+            .into_keys()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            backup_files,
+            vec![
+                "course_data_120.bcd",
+                "course_data_121.bcd",
+                "course_data_122.bcd",
+                "course_thumb_120.btl",
+                "course_thumb_121.btl",
+                "course_thumb_122.btl",
+                "save.dat",
+            ]
+        );
+
+        save.restore_backup(&backup)?;
+
+        assert_eq!(read_files(&path)?, files);
+        assert!(get_course(&save, 5).is_none());
+        assert!(get_course(&save, 122).is_some());
+        remove(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_save_rollback() -> anyhow::Result<()> {
+        let path = copy_save("rollback")?;
+        let files = read_files(&path)?;
+        let mut save = Save::new(&path)?;
+        let file = include_bytes!("../tests/assets/saves/smm2/save1.zip");
+        let courses = Course2::from_packed(file)?;
+        let title = get_course(&save, 120)
+            .unwrap()
+            .get_course()
+            .get_header()
+            .get_title()
+            .to_string();
+
+        // a directory in place of the thumbnail makes the last rename fail
+        let obstacle = path.join("course_thumb_005.btl");
+        create_dir(&obstacle)?;
+        save.swap_course(slot(120), slot(121))?;
+        save.remove_course(slot(122))?;
+        save.add_course(slot(5), courses[0].clone())?;
+        assert!(save.save().is_err());
+
+        assert_eq!(read_files(&path)?, files);
+
+        remove(&obstacle)?;
+        save.save()?;
+        let save = Save::new(&path)?;
+        assert!(get_course(&save, 5).is_some());
+        assert!(get_course(&save, 122).is_none());
+        assert_eq!(
+            get_course(&save, 121)
+                .unwrap()
+                .get_course()
+                .get_header()
+                .get_title(),
+            title
+        );
+        remove(&path)?;
+        Ok(())
+    }
+
+    fn get_title<S: SaveStorage>(save: &Save<S>, index: u8) -> Option<String> {
+        get_course(save, index)
+            .map(|course| course.get_course().get_header().get_title().to_string())
+    }
+
+    /// Swap, remove and add courses and check the result after reloading it from the storage.
+    fn modify_and_reload<S: SaveStorage>(
+        mut save: Save<S>,
+        reload: impl FnOnce(S) -> anyhow::Result<S>,
+    ) -> anyhow::Result<S> {
+        let file = include_bytes!("../tests/assets/saves/smm2/save1.zip");
+        let courses = Course2::from_packed(file)?;
+        let titles: Vec<_> = (120..123).map(|index| get_title(&save, index)).collect();
+
+        save.swap_course(slot(120), slot(121))?;
+        save.remove_course(slot(122))?;
+        save.add_course(slot(0), courses[0].clone())?;
+        save.save()?;
+
+        let save = Save::from_storage(reload(save.into_storage())?)?;
+        assert_eq!(get_title(&save, 120), titles[1]);
+        assert_eq!(get_title(&save, 121), titles[0]);
+        assert_eq!(get_title(&save, 122), None);
+        assert_eq!(
+            get_title(&save, 0).as_deref(),
+            Some(courses[0].get_course().get_header().get_title())
+        );
+        Ok(save.into_storage())
+    }
+
+    #[test]
+    pub fn test_save_memory_storage() -> anyhow::Result<()> {
+        let path = Path::new("./tests/assets/saves/smm2/save1");
+        let storage = MemoryStorage::from_files(read_files(path)?);
+        let save = Save::from_storage(storage)?;
+
+        let storage = modify_and_reload(save, Ok)?;
+
+        let files = storage.get_files();
+        assert!(files.contains_key("course_data_000.bcd"));
+        assert!(files.contains_key("course_thumb_000.btl"));
+        assert!(!files.contains_key("course_data_122.bcd"));
+        assert!(!files.keys().any(|name| name.starts_with("swap_")));
+        // backup of save.dat and the three changed courses
+        assert_eq!(storage.list("")?.len() + 7, files.len());
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_save_zip_storage() -> anyhow::Result<()> {
+        let path = Path::new("./tests/assets/saves/smm2/save1");
+        let mut zip = ZipWriter::new(Cursor::new(vec![]));
+        for (name, data) in read_files(path)? {
+            zip.start_file(format!("save1/{}", name), FileOptions::default())?;
+            zip.write_all(&data)?;
+        }
+        zip.start_file("readme.txt", FileOptions::default())?;
+        zip.write_all(b"readme")?;
+        zip.start_file("older/save1/save.dat", FileOptions::default())?;
+        zip.write_all(b"other save")?;
+        let buffer = zip.finish()?.into_inner();
+        let save = Save::from_storage(ZipStorage::from_zip(&buffer)?)?;
+
+        let storage = modify_and_reload(save, |storage| {
+            Ok(ZipStorage::from_zip(&storage.to_zip()?)?)
+        })?;
+
+        assert!(storage.exists("save.dat")?);
+        let buffer = storage.to_zip()?;
+        let mut archive = zip::ZipArchive::new(Cursor::new(&buffer[..]))?;
+        assert!(archive.by_name("save1/course_data_000.bcd").is_ok());
+        assert!(archive.by_name("save1/course_data_122.bcd").is_err());
+        for (name, expected) in [
+            ("readme.txt", &b"readme"[..]),
+            ("older/save1/save.dat", b"other save"),
+        ] {
+            let mut data = vec![];
+            archive.by_name(name)?.read_to_end(&mut data)?;
+            assert_eq!(data, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_save_changed_files() -> anyhow::Result<()> {
+        let path = Path::new("./tests/assets/saves/smm2/save1");
+        let files = read_files(path)?;
+        let mut save = Save::from_storage(MemoryStorage::from_files(files.clone()))?;
+        let file = include_bytes!("../tests/assets/saves/smm2/save1.zip");
+        let courses = Course2::from_packed(file)?;
+
+        save.swap_course(slot(120), slot(121))?;
+        save.remove_course(slot(122))?;
+        save.add_course(slot(0), courses[0].clone())?;
+        save.save()?;
+        let changed_files = ChangedFiles::new(&files, save.get_storage().get_files());
+
+        assert_eq!(
+            changed_files.written.keys().collect::<Vec<_>>(),
+            vec![
+                "course_data_000.bcd",
+                "course_data_120.bcd",
+                "course_data_121.bcd",
+                "course_thumb_000.btl",
+                "course_thumb_120.btl",
+                "course_thumb_121.btl",
+                "save.dat",
+            ]
+        );
+        assert_eq!(
+            changed_files.removed,
+            vec!["course_data_122.bcd", "course_thumb_122.btl"]
+        );
+        assert_eq!(
+            changed_files.written["course_data_120.bcd"],
+            files["course_data_121.bcd"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_save_fs_storage() -> anyhow::Result<()> {
+        let path = copy_save("fs_storage")?;
+        let files = read_files(&path)?;
+        let mut save = Save::new(&path)?;
+        let title = get_title(&save, 120);
+
+        save.swap_course(slot(120), slot(121))?;
+        save.remove_course(slot(122))?;
+        let backup = save.save()?;
+
+        let mut save = Save::new(&path)?;
+        assert_eq!(get_title(&save, 121), title);
+        assert_eq!(get_title(&save, 122), None);
+
+        save.restore_backup(&backup)?;
+        assert_eq!(get_title(&save, 120), title);
+        assert_eq!(read_files(&path)?, files);
+        remove(&path)?;
+        Ok(())
+    }
+
+    /// Run a future on the current thread without any async runtime.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        struct NoopWaker;
+
+        impl Wake for NoopWaker {
+            fn wake(self: Arc<Self>) {}
+        }
+
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut context = Context::from_waker(&waker);
+        let mut future = pin!(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_save_async() -> anyhow::Result<()> {
+        let path = copy_save("async")?;
+        let files = read_files(&path)?;
+        block_on(async {
+            let mut save = Save::new_async(&path).await?;
+            let title = get_title(&save, 120);
+
+            save.swap_course(slot(120), slot(121))?;
+            let backup = save.save_async().await?;
+            assert_eq!(get_title(&Save::new_async(&path).await?, 121), title);
+            assert_eq!(save.check_async().await?, vec![]);
+            assert_eq!(save.repair_async().await?, vec![]);
+            assert_eq!(save.recover_async().await?, None);
+
+            save.restore_backup_async(&backup).await?;
+            assert_eq!(get_title(&save, 120), title);
+            anyhow::Ok(())
+        })?;
+        assert_eq!(read_files(&path)?, files);
+        remove(&path)?;
+        Ok(())
+    }
+
+    /// Storage, which fails after a number of successful writes, renames and removals.
+    ///
+    /// With `crash`, all later changes fail as well, as if the process had been killed.
+    struct FailingStorage {
+        inner: MemoryStorage,
+        changes: usize,
+        crash: bool,
+    }
+
+    impl FailingStorage {
+        fn check(&mut self) -> std::io::Result<()> {
+            if self.changes == 0 {
+                if !self.crash {
+                    self.changes = usize::MAX;
+                }
+                return Err(std::io::Error::new(std::io::ErrorKind::Other, "failed"));
+            }
+            self.changes -= 1;
+            Ok(())
+        }
+    }
+
+    impl SaveStorage for FailingStorage {
+        fn read(&self, name: &str) -> std::io::Result<Vec<u8>> {
+            self.inner.read(name)
+        }
+
+        fn write(&mut self, name: &str, data: &[u8]) -> std::io::Result<()> {
+            self.check()?;
+            self.inner.write(name, data)
+        }
+
+        fn rename(&mut self, from: &str, to: &str) -> std::io::Result<()> {
+            self.check()?;
+            self.inner.rename(from, to)
+        }
+
+        fn remove(&mut self, name: &str) -> std::io::Result<()> {
+            self.check()?;
+            self.inner.remove(name)
+        }
+
+        fn exists(&self, name: &str) -> std::io::Result<bool> {
+            self.inner.exists(name)
+        }
+
+        fn list(&self, dir: &str) -> std::io::Result<Vec<String>> {
+            self.inner.list(dir)
+        }
+
+        fn list_dirs(&self, dir: &str) -> std::io::Result<Vec<String>> {
+            self.inner.list_dirs(dir)
+        }
+
+        fn remove_dir(&mut self, dir: &str) -> std::io::Result<()> {
+            self.inner.remove_dir(dir)
+        }
+    }
+
+    /// Changes of [`save_failing`] before the first course file is removed:
+    /// seven files of the backup, the journal, two new and four swapped course files.
+    const STAGED_CHANGES: usize = 14;
+
+    /// Swap, remove and add courses with a storage, which fails after a number of changes.
+    fn save_failing(
+        changes: usize,
+        crash: bool,
+    ) -> anyhow::Result<(Save<FailingStorage>, Result<String>)> {
+        let path = Path::new("./tests/assets/saves/smm2/save1");
+        let storage = FailingStorage {
+            inner: MemoryStorage::from_files(read_files(path)?),
+            changes,
+            crash,
+        };
+        let mut save = Save::from_storage(storage)?;
+        let file = include_bytes!("../tests/assets/saves/smm2/save1.zip");
+        let courses = Course2::from_packed(file)?;
+        save.swap_course(slot(120), slot(121))?;
+        save.remove_course(slot(122))?;
+        save.add_course(slot(0), courses[0].clone())?;
+        let result = save.save();
+        Ok((save, result))
+    }
+
+    /// Files of the save folder without backups.
+    fn without_backups(storage: &MemoryStorage) -> BTreeMap<String, Vec<u8>> {
+        let mut files = storage.get_files().clone();
+        files.retain(|name, _| !name.starts_with("backup/"));
+        files
+    }
+
+    #[test]
+    pub fn test_save_rollback_error() -> anyhow::Result<()> {
+        let path = Path::new("./tests/assets/saves/smm2/save1");
+        let files = read_files(path)?;
+        let (mut save, result) = save_failing(STAGED_CHANGES, false)?;
+
+        assert!(matches!(result, Err(Error::IoError(_))));
+        assert!(!save.is_interrupted());
+        let backups = save.list_backups()?;
+        assert_eq!(backups.len(), 1);
+        assert_eq!(without_backups(&save.get_storage().inner), files);
+
+        // the pending changes are kept, so that saving can be retried
+        save.save()?;
+        assert_eq!(get_title(&save, 122), None);
+        assert_eq!(save.list_backups()?.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_save_recover_interrupted() -> anyhow::Result<()> {
+        let path = Path::new("./tests/assets/saves/smm2/save1");
+        let files = read_files(path)?;
+        let (save, _) = save_failing(STAGED_CHANGES, true)?;
+        assert!(save.is_interrupted());
+
+        let storage = save.into_storage().inner;
+        assert!(storage.exists(SAVE_JOURNAL)?);
+        let mut save = Save::from_storage(storage)?;
+        assert!(save.is_interrupted());
+        assert!(matches!(
+            save.save(),
+            Err(Error::SaveError(SaveError::InterruptedSave))
+        ));
+
+        let backup = save.recover()?;
+        assert_eq!(backup, save.list_backups()?.pop());
+        assert!(!save.is_interrupted());
+        assert_eq!(without_backups(save.get_storage()), files);
+        assert!(get_title(&save, 122).is_some());
+        assert_eq!(save.recover()?, None);
+        Ok(())
+    }
+
+    /// Fail a save at every single change, once temporarily and once for good.
+    #[test]
+    pub fn test_save_fail_at_every_change() -> anyhow::Result<()> {
+        let path = Path::new("./tests/assets/saves/smm2/save1");
+        let files = read_files(path)?;
+
+        let mut changes = 0;
+        loop {
+            let (save, result) = save_failing(changes, false)?;
+            if result.is_ok() {
+                break;
+            }
+            assert!(!save.is_interrupted(), "{}", changes);
+            assert_eq!(
+                without_backups(&save.get_storage().inner),
+                files,
+                "{}",
+                changes
+            );
+
+            let (save, result) = save_failing(changes, true)?;
+            assert!(result.is_err(), "{}", changes);
+            let mut save = Save::from_storage(save.into_storage().inner)?;
+            save.recover()?;
+            assert!(!save.is_interrupted(), "{}", changes);
+            assert_eq!(without_backups(save.get_storage()), files, "{}", changes);
+            changes += 1;
+        }
+        // backup, journal, new files, swapped files, removed files, renamed swapped and new files,
+        // save.dat, its rename and the removal of the journal
+        assert_eq!(changes, 7 + 1 + 2 + 4 + 2 + 4 + 2 + 2 + 1);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_save_repair_interrupted() -> anyhow::Result<()> {
+        let path = Path::new("./tests/assets/saves/smm2/save1");
+        let files = read_files(path)?;
+        let (save, _) = save_failing(STAGED_CHANGES, true)?;
+        let mut save = Save::from_storage(save.into_storage().inner)?;
+
+        let issues = save.check()?;
+        let staged = match &issues[..] {
+            [SaveIssue::InterruptedSave(staged)] => staged.clone(),
+            _ => panic!("unexpected issues {:?}", issues),
+        };
+        assert!(staged.contains(&SAVE_JOURNAL.to_string()));
+        assert!(staged.contains(&"swap_120.bcd".to_string()));
+        assert!(staged.contains(&"new_000.bcd".to_string()));
+
+        assert_eq!(save.repair()?, issues);
+        assert_eq!(save.check()?, vec![]);
+        assert!(!save.is_interrupted());
+        let storage = save.get_storage();
+        assert!(!storage.list_dirs("")?.contains(&"quarantine".to_string()));
+        assert_eq!(without_backups(storage), files);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_save_restore_older_backup() -> anyhow::Result<()> {
+        let path = Path::new("./tests/assets/saves/smm2/save1");
+        let files = read_files(path)?;
+        let storage = MemoryStorage::from_files(files.clone());
+        let mut save = Save::from_storage(storage)?;
+        let title = get_title(&save, 120);
+
+        save.swap_course(slot(120), slot(121))?;
+        let first = save.save()?;
+        save.remove_course(slot(120))?;
+        save.swap_course(slot(122), slot(123))?;
+        let second = save.save()?;
+        let saved = without_backups(save.get_storage());
+
+        assert!(matches!(
+            save.restore_backup(&first),
+            Err(Error::SaveError(SaveError::BackupNotNewest(_)))
+        ));
+        assert_eq!(without_backups(save.get_storage()), saved);
+
+        save.restore_backup(&second)?;
+        assert_eq!(save.list_backups()?, vec![first.clone()]);
+        assert_eq!(get_title(&save, 121), title);
+        save.restore_backup(&first)?;
+        assert!(save.list_backups()?.is_empty());
+        assert_eq!(get_title(&save, 120), title);
+        assert_eq!(without_backups(save.get_storage()), files);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_save_backup_limit() -> anyhow::Result<()> {
+        let path = Path::new("./tests/assets/saves/smm2/save1");
+        let storage = MemoryStorage::from_files(read_files(path)?);
+        let mut save = Save::from_storage(storage)?;
+        save.set_backup_limit(Some(2));
+
+        let mut backups = vec![];
+        for _ in 0..3 {
+            save.swap_course(slot(120), slot(121))?;
+            backups.push(save.save()?);
+        }
+        assert_eq!(save.list_backups()?, backups[1..]);
+        assert!(save.get_storage().list(&backups[0])?.is_empty());
+
+        save.set_backup_limit(Some(0));
+        save.swap_course(slot(120), slot(121))?;
+        save.save()?;
+        assert!(save.list_backups()?.is_empty());
+        assert!(!save
+            .get_storage()
+            .get_files()
+            .keys()
+            .any(|name| name.starts_with("backup/")));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_save_lazy_unreadable_course() -> anyhow::Result<()> {
+        let path = Path::new("./tests/assets/saves/smm2/save1");
+        let mut files = read_files(path)?;
+        files.remove("course_data_121.bcd");
+        let save = Save::from_storage(MemoryStorage::from_files(files))?;
+
+        assert!(get_title(&save, 120).is_some());
+        assert!(matches!(
+            save.get_course(slot(121)),
+            Some(CourseEntry::CorruptedCourse(
+                Smm2Error::CourseFilesUnreadable(_)
+            ))
+        ));
+        assert!(matches!(
+            save.get_downloaded_courses()[1].as_deref(),
+            Some(CourseEntry::CorruptedCourse(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_save_lazy_swap() -> anyhow::Result<()> {
+        let path = Path::new("./tests/assets/saves/smm2/save1");
+        let storage = MemoryStorage::from_files(read_files(path)?);
+        let loaded = Save::from_storage(storage.clone())?;
+        let titles: Vec<_> = (120..125).map(|index| get_title(&loaded, index)).collect();
+
+        let mut save = Save::from_storage(storage)?;
+        // neither course has been accessed
+        save.swap_course(slot(120), slot(121))?;
+        // only one course has been accessed
+        get_title(&save, 122);
+        save.swap_course(slot(122), slot(123))?;
+        save.swap_course(slot(123), slot(124))?;
+        assert_eq!(get_title(&save, 120), titles[1]);
+        assert_eq!(get_title(&save, 121), titles[0]);
+        assert_eq!(get_title(&save, 122), titles[3]);
+        assert_eq!(get_title(&save, 123), titles[4]);
+        assert_eq!(get_title(&save, 124), titles[2]);
+
+        save.save()?;
+        let save = Save::from_storage(save.into_storage())?;
+        assert_eq!(get_title(&save, 121), titles[0]);
+        assert_eq!(get_title(&save, 124), titles[2]);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_save_preload() -> anyhow::Result<()> {
+        let path = Path::new("./tests/assets/saves/smm2/save1");
+        let storage = MemoryStorage::from_files(read_files(path)?);
+        let lazy = Save::from_storage(storage.clone())?;
+        let mut preloaded = Save::from_storage(storage)?;
+        preloaded.swap_course(slot(120), slot(0))?;
+        preloaded.swap_course(slot(120), slot(0))?;
+        preloaded.preload();
+
+        let titles = |courses: &Courses| -> Vec<Option<String>> {
+            courses
+                .iter()
+                .map(|course| match course.as_deref() {
+                    Some(CourseEntry::SavedCourse(course)) => Some(
+                        course
+                            .get_course()
+                            .get_course()
+                            .get_header()
+                            .get_title()
+                            .to_string(),
+                    ),
+                    Some(CourseEntry::CorruptedCourse(err)) => Some(err.to_string()),
+                    None => None,
+                })
+                .collect()
+        };
+        assert_eq!(
+            titles(preloaded.get_own_courses()),
+            titles(lazy.get_own_courses())
+        );
+        assert_eq!(
+            titles(preloaded.get_downloaded_courses()),
+            titles(lazy.get_downloaded_courses())
+        );
+        assert!(titles(preloaded.get_downloaded_courses())
+            .iter()
+            .any(Option::is_some));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_save_preload_unreadable_course() -> anyhow::Result<()> {
+        fn assert_sync<T: Sync>(_: &T) {}
+
+        let path = Path::new("./tests/assets/saves/smm2/save1");
+        let mut files = read_files(path)?;
+        files.remove("course_thumb_121.btl");
+        let save = Save::from_storage(MemoryStorage::from_files(files))?;
+        assert_sync(&save);
+        save.preload();
+
+        assert!(get_title(&save, 120).is_some());
+        assert!(matches!(
+            save.get_course(slot(121)),
+            Some(CourseEntry::CorruptedCourse(
+                Smm2Error::CourseFilesUnreadable(_)
+            ))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_save_slot() -> anyhow::Result<()> {
+        let slot = SaveSlot::new(SaveSection::OwnExtended, 5)?;
+        assert_eq!(slot.get_save_index(), 65);
+        assert_eq!(SaveSlot::from_save_index(65)?, slot);
+        assert_eq!(
+            SaveSlot::try_from(179)?.get_section(),
+            SaveSection::Downloaded
+        );
+        assert!(matches!(
+            SaveSlot::new(SaveSection::Own, 60),
+            Err(Error::SaveError(SaveError::SlotIndexOutOfBounds(
+                SaveSection::Own,
+                60
+            )))
+        ));
+        assert!(matches!(
+            SaveSlot::from_save_index(180),
+            Err(Error::SaveError(SaveError::CourseIndexOutOfBounds(180)))
+        ));
+        let indices: Vec<u8> = SaveSection::ALL
+            .iter()
+            .flat_map(|section| section.slots())
+            .map(|slot| slot.get_save_index())
+            .collect();
+        assert_eq!(indices, (0..180).collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_save_occupied_slots() -> anyhow::Result<()> {
+        let path = Path::new("./tests/assets/saves/smm2/save1");
+        let storage = MemoryStorage::from_files(read_files(path)?);
+        let mut save = Save::from_storage(storage)?;
+        assert_eq!(save.occupied_slots(SaveSection::Own).count(), 0);
+        assert_eq!(save.free_slots(SaveSection::Own).count(), 60);
+        assert_eq!(save.occupied_slots(SaveSection::Downloaded).count(), 60);
+
+        let own = SaveSlot::new(SaveSection::Own, 3)?;
+        let downloaded = SaveSlot::new(SaveSection::Downloaded, 1)?;
+        save.swap_course(own, downloaded)?;
+        save.remove_course(SaveSlot::new(SaveSection::Downloaded, 2)?)?;
+        assert_eq!(
+            save.occupied_slots(SaveSection::Own).collect::<Vec<_>>(),
+            vec![own]
+        );
+        assert_eq!(
+            save.free_slots(SaveSection::Downloaded)
+                .map(|slot| slot.get_index())
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert!(matches!(
+            save.swap_course(downloaded, SaveSlot::new(SaveSection::OwnExtended, 0)?),
+            Err(Error::SaveError(SaveError::CourseNotFound(slot))) if slot == downloaded
+        ));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_save_slot_entries() -> anyhow::Result<()> {
+        let path = Path::new("./tests/assets/saves/smm2/save1");
+        let storage = MemoryStorage::from_files(read_files(path)?);
+        let mut save = Save::from_storage(storage)?;
+        let entry = |save: &Save<MemoryStorage>, index: u8| match save.get_course(slot(index)) {
+            Some(CourseEntry::SavedCourse(course)) => Some(*course.get_entry()),
+            _ => None,
+        };
+        assert_eq!(
+            SlotEntry::read(&save.save_file, 120).to_bytes(),
+            [120, 1, 0, 0, 0, 0, 0, 0]
+        );
+        SlotEntry::from_bytes([121, 1, 1, 2, 3, 4, 5, 6]).write(&mut save.save_file, 121);
+
+        // one loaded and one unloaded course
+        assert_eq!(entry(&save, 120).map(|entry| entry.get_index()), Some(120));
+        save.swap_course(slot(120), slot(121))?;
+        save.swap_course(slot(121), slot(3))?;
+        let moved = entry(&save, 120).unwrap();
+        assert_eq!(moved.get_slot()?, slot(120));
+        assert_eq!(moved.get_reserved(), &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(
+            entry(&save, 3).unwrap().to_bytes(),
+            [3, 1, 0, 0, 0, 0, 0, 0]
+        );
+        assert!(!SlotEntry::read(&save.save_file, 121).exists());
+
+        // the reserved bytes of the game are kept, when a slot is overwritten or cleared
+        let file = include_bytes!("../tests/assets/saves/smm2/save1.zip");
+        let course = Course2::from_packed(file)?.remove(0);
+        SlotEntry::from_bytes([122, 1, 6, 5, 4, 3, 2, 1]).write(&mut save.save_file, 122);
+        save.add_course(slot(120), course.clone())?;
+        save.add_course(slot(0), course)?;
+        save.remove_course(slot(122))?;
+        assert_eq!(
+            entry(&save, 120).unwrap().to_bytes(),
+            [120, 1, 1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(entry(&save, 0), Some(SlotEntry::new(slot(0))));
+        assert_eq!(
+            SlotEntry::read(&save.save_file, 122).to_bytes(),
+            [122, 0, 6, 5, 4, 3, 2, 1]
+        );
+
+        save.save()?;
+        let save = Save::from_storage(save.into_storage())?;
+        assert_eq!(entry(&save, 3).map(|entry| entry.get_index()), Some(3));
+        assert_eq!(
+            entry(&save, 120).unwrap().to_bytes(),
+            [120, 1, 1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(entry(&save, 0), Some(SlotEntry::new(slot(0))));
+        assert_eq!(entry(&save, 122), None);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_save_check() -> anyhow::Result<()> {
+        let path = Path::new("./tests/assets/saves/smm2/save1");
+        let storage = MemoryStorage::from_files(read_files(path)?);
+        let save = Save::from_storage(storage)?;
+        assert_eq!(save.check()?, vec![]);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_save_repair() -> anyhow::Result<()> {
+        let path = Path::new("./tests/assets/saves/smm2/save1");
+        let mut files = read_files(path)?;
+        files.remove("course_thumb_121.btl");
+        files.get_mut("course_data_122.bcd").unwrap()[0x1000] ^= 0xFF;
+        let orphan = files["course_data_123.bcd"].clone();
+        files.insert("course_data_005.bcd".to_string(), orphan);
+        files.insert("course_thumb_007.btl".to_string(), vec![0; 16]);
+        let mut save = Save::from_storage(MemoryStorage::from_files(files))?;
+
+        let offset = SAVE_COURSE_OFFSET as usize;
+        let entry = get_entry_offset(124);
+        save.save_file[entry] = 7;
+        let mut save_data = save.save_file.clone();
+        save_data[offset + 0x8] ^= 0xFF;
+        let aes_info = encrypt(&mut save_data[0x10..], &SAVE_KEY_TABLE);
+        save_data.extend_from_slice(&aes_info);
+        save.storage.write("save.dat", &save_data)?;
+
+        let issues = save.check()?;
+        assert!(matches!(issues[0], SaveIssue::ChecksumMismatch { .. }));
+        assert_eq!(
+            issues[1],
+            SaveIssue::MissingFiles {
+                slot: slot(121),
+                missing: vec!["course_thumb_121.btl".to_string()]
+            }
+        );
+        assert!(matches!(
+            &issues[2],
+            SaveIssue::CorruptedCourse { slot: corrupted, .. } if *corrupted == slot(122)
+        ));
+        assert_eq!(
+            issues[3..],
+            [
+                SaveIssue::EntryIndexMismatch {
+                    slot: slot(124),
+                    index: 7
+                },
+                SaveIssue::OrphanFile("course_data_005.bcd".to_string()),
+                SaveIssue::OrphanFile("course_thumb_007.btl".to_string()),
+            ]
+        );
+
+        save.add_course(slot(0), get_course(&save, 120).unwrap().clone())?;
+        assert_eq!(save.repair()?, issues);
+        assert_eq!(save.check()?, vec![]);
+        assert!(get_course(&save, 0).is_none());
+        assert!(get_course(&save, 121).is_none());
+        assert!(get_course(&save, 122).is_none());
+        assert!(get_course(&save, 124).is_some());
+
+        let storage = save.get_storage();
+        let quarantine = storage
+            .get_files()
+            .keys()
+            .filter_map(|name| name.strip_prefix("quarantine/"))
+            .filter_map(|name| name.split_once('/'))
+            .map(|(_, name)| name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            quarantine,
+            [
+                "course_data_005.bcd",
+                "course_data_121.bcd",
+                "course_data_122.bcd",
+                "course_thumb_007.btl",
+                "course_thumb_122.btl"
+            ]
+        );
+        assert_eq!(
+            storage
+                .get_files()
+                .keys()
+                .filter(|name| name.starts_with("backup/"))
+                .count(),
+            1
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,120 @@
+//! Addressing of the course slots of a Super Mario Maker 2 save.
+//!
+//! `save.dat` and the course files number the 180 slots from 0 to 179,
+//! which are split into three sections of 60 slots each.
+
+use crate::{errors::SaveError, Result};
+
+use std::fmt;
+
+/// Number of course slots in every [`SaveSection`].
+pub const SAVE_SECTION_SLOT_COUNT: u8 = 60;
+
+/// Section of the course slots of a save.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SaveSection {
+    /// Slots 0 to 59, the first 60 course slots of Coursebot for own courses.
+    Own,
+    /// Slots 60 to 119.
+    ///
+    /// Coursebot stores up to 120 own courses,
+    /// so this section holds the own courses after the first 60.
+    OwnExtended,
+    /// Slots 120 to 179, which hold courses downloaded from Course World.
+    Downloaded,
+}
+
+impl SaveSection {
+    pub const ALL: [SaveSection; 3] = [
+        SaveSection::Own,
+        SaveSection::OwnExtended,
+        SaveSection::Downloaded,
+    ];
+
+    /// All slots of this section in ascending order.
+    pub fn slots(self) -> impl Iterator<Item = SaveSlot> {
+        (0..SAVE_SECTION_SLOT_COUNT).map(move |index| SaveSlot {
+            section: self,
+            index,
+        })
+    }
+
+    fn get_offset(self) -> u8 {
+        match self {
+            SaveSection::Own => 0,
+            SaveSection::OwnExtended => SAVE_SECTION_SLOT_COUNT,
+            SaveSection::Downloaded => 2 * SAVE_SECTION_SLOT_COUNT,
+        }
+    }
+}
+
+impl fmt::Display for SaveSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveSection::Own => write!(f, "own courses"),
+            SaveSection::OwnExtended => write!(f, "extended own courses"),
+            SaveSection::Downloaded => write!(f, "downloaded courses"),
+        }
+    }
+}
+
+/// Course slot of a save, which is addressed by its section and its index inside of that section.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SaveSlot {
+    section: SaveSection,
+    index: u8,
+}
+
+impl SaveSlot {
+    /// Fails with [`SaveError::SlotIndexOutOfBounds`], if `index` is not below [`SAVE_SECTION_SLOT_COUNT`].
+    pub fn new(section: SaveSection, index: u8) -> Result<SaveSlot> {
+        if index >= SAVE_SECTION_SLOT_COUNT {
+            return Err(SaveError::SlotIndexOutOfBounds(section, index).into());
+        }
+        Ok(SaveSlot { section, index })
+    }
+
+    /// Slot from its number in `save.dat` and in the names of course files.
+    ///
+    /// Fails with [`SaveError::CourseIndexOutOfBounds`], if `index` is not below 180.
+    pub fn from_save_index(index: u8) -> Result<SaveSlot> {
+        let section = match index / SAVE_SECTION_SLOT_COUNT {
+            0 => SaveSection::Own,
+            1 => SaveSection::OwnExtended,
+            2 => SaveSection::Downloaded,
+            _ => return Err(SaveError::CourseIndexOutOfBounds(index).into()),
+        };
+        Ok(SaveSlot {
+            section,
+            index: index % SAVE_SECTION_SLOT_COUNT,
+        })
+    }
+
+    pub fn get_section(&self) -> SaveSection {
+        self.section
+    }
+
+    /// Index inside of the section.
+    pub fn get_index(&self) -> u8 {
+        self.index
+    }
+
+    /// Number of the slot in `save.dat` and in the names of course files.
+    pub fn get_save_index(&self) -> u8 {
+        self.section.get_offset() + self.index
+    }
+}
+
+impl TryFrom<u8> for SaveSlot {
+    type Error = crate::Error;
+
+    fn try_from(index: u8) -> Result<SaveSlot> {
+        SaveSlot::from_save_index(index)
+    }
+}
+
+impl fmt::Display for SaveSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "slot {} of {}", self.index, self.section)
+    }
+}
@@ -0,0 +1,325 @@
+//! Storage backends for the files of a Super Mario Maker 2 save folder.
+//!
+//! Files are addressed by their path relative to the save folder, e.g. `course_data_000.bcd`.
+//! Subdirectories are separated by `/`.
+
+use crate::Result;
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Cursor, Read, Write},
+    path::PathBuf,
+};
+use zip::{write::FileOptions, ZipArchive, ZipWriter};
+
+/// Storage of the files of a save folder, which is used by [`Save`](crate::save::Save).
+pub trait SaveStorage {
+    /// Read a file.
+    ///
+    /// Fails with [`io::ErrorKind::NotFound`], if the file does not exist.
+    fn read(&self, name: &str) -> io::Result<Vec<u8>>;
+
+    /// Create or overwrite a file.
+    fn write(&mut self, name: &str, data: &[u8]) -> io::Result<()>;
+
+    /// Move a file, replacing the destination if it already exists.
+    fn rename(&mut self, from: &str, to: &str) -> io::Result<()>;
+
+    /// Remove a file.
+    fn remove(&mut self, name: &str) -> io::Result<()>;
+
+    /// Whether a file exists.
+    fn exists(&self, name: &str) -> io::Result<bool>;
+
+    /// Names of all files inside a directory, relative to that directory.
+    ///
+    /// An empty `dir` lists the files of the save folder itself.
+    /// Subdirectories are not included and a missing directory is empty.
+    fn list(&self, dir: &str) -> io::Result<Vec<String>>;
+
+    /// Names of all subdirectories inside a directory, relative to that directory.
+    ///
+    /// A missing directory is empty.
+    fn list_dirs(&self, dir: &str) -> io::Result<Vec<String>>;
+
+    /// Remove an empty directory.
+    ///
+    /// Storages without real directories do nothing.
+    fn remove_dir(&mut self, dir: &str) -> io::Result<()>;
+}
+
+fn not_found(name: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, format!("file {} not found", name))
+}
+
+/// Save folder on the local file system.
+#[derive(Clone, Debug)]
+pub struct FsStorage {
+    path: PathBuf,
+}
+
+impl FsStorage {
+    pub fn new<T: Into<PathBuf>>(path: T) -> FsStorage {
+        FsStorage { path: path.into() }
+    }
+
+    pub fn get_path(&self) -> &PathBuf {
+        &self.path
+    }
+}
+
+impl SaveStorage for FsStorage {
+    fn read(&self, name: &str) -> io::Result<Vec<u8>> {
+        fs::read(self.path.join(name))
+    }
+
+    fn write(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        let path = self.path.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::File::create(path)?;
+        file.write_all(data)?;
+        file.sync_all()
+    }
+
+    fn rename(&mut self, from: &str, to: &str) -> io::Result<()> {
+        fs::rename(self.path.join(from), self.path.join(to))
+    }
+
+    fn remove(&mut self, name: &str) -> io::Result<()> {
+        fs::remove_file(self.path.join(name))
+    }
+
+    fn exists(&self, name: &str) -> io::Result<bool> {
+        match fs::metadata(self.path.join(name)) {
+            Ok(metadata) => Ok(metadata.is_file()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn list(&self, dir: &str) -> io::Result<Vec<String>> {
+        let entries = match fs::read_dir(self.path.join(dir)) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err),
+        };
+        let mut names = vec![];
+        for entry in entries {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                names.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+        Ok(names)
+    }
+
+    fn list_dirs(&self, dir: &str) -> io::Result<Vec<String>> {
+        let entries = match fs::read_dir(self.path.join(dir)) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err),
+        };
+        let mut names = vec![];
+        for entry in entries {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                names.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+        Ok(names)
+    }
+
+    fn remove_dir(&mut self, dir: &str) -> io::Result<()> {
+        fs::remove_dir(self.path.join(dir))
+    }
+}
+
+/// Save folder which is kept in memory.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MemoryStorage {
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> MemoryStorage {
+        MemoryStorage::default()
+    }
+
+    pub fn from_files<I: IntoIterator<Item = (String, Vec<u8>)>>(files: I) -> MemoryStorage {
+        MemoryStorage {
+            files: files.into_iter().collect(),
+        }
+    }
+
+    pub fn get_files(&self) -> &BTreeMap<String, Vec<u8>> {
+        &self.files
+    }
+
+    pub fn into_files(self) -> BTreeMap<String, Vec<u8>> {
+        self.files
+    }
+}
+
+impl SaveStorage for MemoryStorage {
+    fn read(&self, name: &str) -> io::Result<Vec<u8>> {
+        self.files.get(name).cloned().ok_or_else(|| not_found(name))
+    }
+
+    fn write(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        self.files.insert(name.to_string(), data.to_vec());
+        Ok(())
+    }
+
+    fn rename(&mut self, from: &str, to: &str) -> io::Result<()> {
+        let data = self.files.remove(from).ok_or_else(|| not_found(from))?;
+        self.files.insert(to.to_string(), data);
+        Ok(())
+    }
+
+    fn remove(&mut self, name: &str) -> io::Result<()> {
+        self.files
+            .remove(name)
+            .map(|_| ())
+            .ok_or_else(|| not_found(name))
+    }
+
+    fn exists(&self, name: &str) -> io::Result<bool> {
+        Ok(self.files.contains_key(name))
+    }
+
+    fn list(&self, dir: &str) -> io::Result<Vec<String>> {
+        let prefix = if dir.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", dir.trim_end_matches('/'))
+        };
+        Ok(self
+            .files
+            .keys()
+            .filter_map(|name| name.strip_prefix(&prefix))
+            .filter(|name| !name.contains('/'))
+            .map(|name| name.to_string())
+            .collect())
+    }
+
+    fn list_dirs(&self, dir: &str) -> io::Result<Vec<String>> {
+        let prefix = if dir.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", dir.trim_end_matches('/'))
+        };
+        let mut names: Vec<String> = self
+            .files
+            .keys()
+            .filter_map(|name| name.strip_prefix(&prefix))
+            .filter_map(|name| name.split_once('/'))
+            .map(|(dir, _)| dir.to_string())
+            .collect();
+        names.dedup();
+        Ok(names)
+    }
+
+    fn remove_dir(&mut self, _dir: &str) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Save folder inside of a zip archive.
+///
+/// The archive is read into memory and has to be written back with [`ZipStorage::to_zip`].
+/// If `save.dat` is located in a subdirectory of the archive, that subdirectory is used as the save folder.
+/// All other files of the archive can not be accessed, but are written back unchanged.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ZipStorage {
+    prefix: String,
+    files: MemoryStorage,
+    /// Files outside of the save folder.
+    other_files: BTreeMap<String, Vec<u8>>,
+}
+
+impl ZipStorage {
+    pub fn from_zip(buffer: &[u8]) -> Result<ZipStorage> {
+        let mut archive = ZipArchive::new(Cursor::new(buffer))?;
+        let mut files = BTreeMap::new();
+        for index in 0..archive.len() {
+            let mut file = archive.by_index(index)?;
+            if !file.is_file() {
+                continue;
+            }
+            let mut data = vec![];
+            file.read_to_end(&mut data)?;
+            files.insert(file.name().to_string(), data);
+        }
+        let prefix = files
+            .keys()
+            .filter_map(|name| name.strip_suffix("save.dat"))
+            .filter(|prefix| prefix.is_empty() || prefix.ends_with('/'))
+            .min_by_key(|prefix| prefix.len())
+            .unwrap_or_default()
+            .to_string();
+        let (files, other_files): (BTreeMap<_, _>, BTreeMap<_, _>) = files
+            .into_iter()
+            .partition(|(name, _)| name.starts_with(&prefix));
+        let files = files
+            .into_iter()
+            .map(|(name, data)| (name[prefix.len()..].to_string(), data))
+            .collect::<Vec<_>>();
+
+        Ok(ZipStorage {
+            prefix,
+            files: MemoryStorage::from_files(files),
+            other_files,
+        })
+    }
+
+    /// Write all files into a new zip archive, including the files outside of the save folder.
+    pub fn to_zip(&self) -> Result<Vec<u8>> {
+        let mut zip = ZipWriter::new(Cursor::new(vec![]));
+        for (name, data) in self.files.get_files() {
+            zip.start_file(format!("{}{}", self.prefix, name), FileOptions::default())?;
+            zip.write_all(data)?;
+        }
+        for (name, data) in self.other_files.iter() {
+            zip.start_file(name, FileOptions::default())?;
+            zip.write_all(data)?;
+        }
+        Ok(zip.finish()?.into_inner())
+    }
+}
+
+impl SaveStorage for ZipStorage {
+    fn read(&self, name: &str) -> io::Result<Vec<u8>> {
+        self.files.read(name)
+    }
+
+    fn write(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        self.files.write(name, data)
+    }
+
+    fn rename(&mut self, from: &str, to: &str) -> io::Result<()> {
+        self.files.rename(from, to)
+    }
+
+    fn remove(&mut self, name: &str) -> io::Result<()> {
+        self.files.remove(name)
+    }
+
+    fn exists(&self, name: &str) -> io::Result<bool> {
+        self.files.exists(name)
+    }
+
+    fn list(&self, dir: &str) -> io::Result<Vec<String>> {
+        self.files.list(dir)
+    }
+
+    fn list_dirs(&self, dir: &str) -> io::Result<Vec<String>> {
+        self.files.list_dirs(dir)
+    }
+
+    fn remove_dir(&mut self, dir: &str) -> io::Result<()> {
+        self.files.remove_dir(dir)
+    }
+}
@@ -0,0 +1,546 @@
+//! Course similarity scoring to detect stolen courses with small edits.
+//!
+//! Courses are reduced to a [CourseLayout], which is the set of occupied tiles together with the type of entity.
+//! Two layouts can be compared directly via [CourseLayout::similarity]
+//! or inserted into a [SimilarityIndex] to find the nearest matches among many stored courses.
+
+#[cfg(target_arch = "wasm32")]
+use crate::JsResult;
+use crate::{
+    area2::{AreaType, OBJECT_TILE_SIZE},
+    Course, Course2, Result,
+};
+
+use std::collections::{HashMap, HashSet};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+/// Object type of ground tiles.
+///
+/// Super Mario Maker 2 stores ground tiles in a separate table.
+/// They are mapped to the object type of ground in Super Mario Maker,
+/// so that ported courses can be compared.
+const GROUND_KIND: i32 = 7;
+
+/// Tiles in each direction which are part of a pattern used for signatures.
+const PATTERN_RADIUS: i32 = 2;
+
+const SIGNATURE_SIZE: usize = 128;
+const BAND_ROWS: usize = 4;
+const BANDS: usize = SIGNATURE_SIZE / BAND_ROWS;
+
+type Cell = (i32, i32, i32);
+
+/// Occupied tiles of both areas of a course.
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CourseLayout {
+    areas: [HashSet<Cell>; 2],
+}
+
+impl CourseLayout {
+    fn new(areas: [Vec<Cell>; 2]) -> CourseLayout {
+        let [main, sub] = areas;
+        CourseLayout {
+            areas: [CourseLayout::normalize(main), CourseLayout::normalize(sub)],
+        }
+    }
+
+    /// Moves all cells so that the smallest coordinates are zero.
+    fn normalize(cells: Vec<Cell>) -> HashSet<Cell> {
+        let min_x = cells.iter().map(|cell| cell.0).min().unwrap_or_default();
+        let min_y = cells.iter().map(|cell| cell.1).min().unwrap_or_default();
+        cells
+            .into_iter()
+            .map(|(x, y, kind)| (x - min_x, y - min_y, kind))
+            .collect()
+    }
+
+    /// Returns the amount of occupied tiles of the given area.
+    pub fn len(&self, area: AreaType) -> usize {
+        self.areas[area.index()].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.areas.iter().all(HashSet::is_empty)
+    }
+
+    /// Compute the similarity to another layout.
+    ///
+    /// For each area the best alignment within the allowed shifts and mirroring is searched.
+    /// The score is the Jaccard index of all aligned tiles, so `1.0` means identical layouts.
+    pub fn similarity(&self, other: &CourseLayout, options: SimilarityOptions) -> Similarity {
+        let mut intersection = 0;
+        let mut union = 0;
+        let mut alignments = vec![];
+        for area in AreaType::ALL {
+            let cells = &self.areas[area.index()];
+            let other_cells = &other.areas[area.index()];
+            let (alignment, matching) = CourseLayout::align(area, cells, other_cells, options);
+            intersection += matching;
+            union += cells.len() + other_cells.len() - matching;
+            alignments.push(alignment);
+        }
+        let score = if union == 0 {
+            1.0
+        } else {
+            intersection as f64 / union as f64
+        };
+        Similarity { score, alignments }
+    }
+
+    /// Returns the best shift and mirroring together with the amount of matching cells.
+    fn align(
+        area: AreaType,
+        cells: &HashSet<Cell>,
+        other: &HashSet<Cell>,
+        options: SimilarityOptions,
+    ) -> (Alignment, usize) {
+        let max_x = other.iter().map(|cell| cell.0).max().unwrap_or_default();
+        let mut best = (
+            Alignment {
+                area,
+                shift_x: 0,
+                shift_y: 0,
+                mirrored: false,
+            },
+            0,
+        );
+        let mirrors: &[bool] = if options.allow_mirror {
+            &[false, true]
+        } else {
+            &[false]
+        };
+        let max_shift_x = options.max_shift_x as i32;
+        let max_shift_y = options.max_shift_y as i32;
+        for &mirrored in mirrors {
+            for shift_x in -max_shift_x..=max_shift_x {
+                for shift_y in -max_shift_y..=max_shift_y {
+                    let matching = other
+                        .iter()
+                        .filter(|(x, y, kind)| {
+                            let x = if mirrored { max_x - x } else { *x };
+                            cells.contains(&(x + shift_x, y + shift_y, *kind))
+                        })
+                        .count();
+                    if matching > best.1 {
+                        best = (
+                            Alignment {
+                                area,
+                                shift_x,
+                                shift_y,
+                                mirrored,
+                            },
+                            matching,
+                        );
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// Compute a MinHash signature of this layout.
+    ///
+    /// The signature is built from local patterns around every occupied tile,
+    /// which makes it independent of shifts and mirroring.
+    pub fn signature(&self) -> Signature {
+        let mut signature = vec![u64::MAX; SIGNATURE_SIZE];
+        for shingle in self.shingles() {
+            for (seed, min) in signature.iter_mut().enumerate() {
+                *min = (*min).min(mix(shingle ^ mix(seed as u64 + 1)));
+            }
+        }
+        Signature(signature)
+    }
+
+    fn shingles(&self) -> HashSet<u64> {
+        let mut shingles = HashSet::new();
+        for area in AreaType::ALL {
+            let cells = &self.areas[area.index()];
+            let mut by_position: HashMap<(i32, i32), Vec<i32>> = HashMap::new();
+            for (x, y, kind) in cells {
+                by_position.entry((*x, *y)).or_default().push(*kind);
+            }
+            for (x, y, kind) in cells {
+                let mut pattern = vec![];
+                let mut mirrored = vec![];
+                for dx in -PATTERN_RADIUS..=PATTERN_RADIUS {
+                    for dy in -PATTERN_RADIUS..=PATTERN_RADIUS {
+                        if let Some(kinds) = by_position.get(&(x + dx, y + dy)) {
+                            for neighbor in kinds {
+                                pattern.push((dx, dy, *neighbor));
+                                mirrored.push((-dx, dy, *neighbor));
+                            }
+                        }
+                    }
+                }
+                pattern.sort_unstable();
+                mirrored.sort_unstable();
+                let seed = hash_values(&[area.index() as i32, *kind]);
+                shingles.insert(hash_pattern(seed, &pattern).min(hash_pattern(seed, &mirrored)));
+            }
+        }
+        shingles
+    }
+}
+
+impl Course2 {
+    /// Get the layout of this course for similarity scoring.
+    ///
+    /// This fails, if the course has no decrypted course data.
+    pub fn get_layout(&self) -> Result<CourseLayout> {
+        let mut areas = [vec![], vec![]];
+        for area in AreaType::ALL {
+            let entities = self.get_area(area)?;
+            let cells = &mut areas[area.index()];
+            cells.extend(entities.objects.iter().map(|object| {
+                (
+                    object.x.div_euclid(OBJECT_TILE_SIZE),
+                    object.y.div_euclid(OBJECT_TILE_SIZE),
+                    object.id as i32,
+                )
+            }));
+            cells.extend(
+                entities
+                    .ground
+                    .iter()
+                    .map(|ground| (ground.x as i32, ground.y as i32, GROUND_KIND)),
+            );
+        }
+        Ok(CourseLayout::new(areas))
+    }
+}
+
+impl Course {
+    /// Get the layout of this course for similarity scoring.
+    pub fn get_layout(&self) -> CourseLayout {
+        let mut areas = [vec![], vec![]];
+        for area in AreaType::ALL {
+            areas[area.index()] = self
+                .get_objects(area)
+                .iter()
+                .map(|object| {
+                    (
+                        (object.x as i32).div_euclid(OBJECT_TILE_SIZE),
+                        (object.y as i32).div_euclid(OBJECT_TILE_SIZE),
+                        object.object_type as i32,
+                    )
+                })
+                .collect();
+        }
+        CourseLayout::new(areas)
+    }
+}
+
+/// Controls how tolerant similarity scoring is.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SimilarityOptions {
+    /// Maximum horizontal shift in tiles.
+    pub max_shift_x: u32,
+    /// Maximum vertical shift in tiles.
+    pub max_shift_y: u32,
+    /// Also compare against the horizontally mirrored layout.
+    pub allow_mirror: bool,
+}
+
+impl Default for SimilarityOptions {
+    fn default() -> Self {
+        SimilarityOptions {
+            max_shift_x: 4,
+            max_shift_y: 2,
+            allow_mirror: true,
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl SimilarityOptions {
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(constructor))]
+    pub fn new(max_shift_x: u32, max_shift_y: u32, allow_mirror: bool) -> Self {
+        SimilarityOptions {
+            max_shift_x,
+            max_shift_y,
+            allow_mirror,
+        }
+    }
+}
+
+/// Result of comparing two layouts.
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Similarity {
+    /// Jaccard index between `0.0` and `1.0`.
+    pub score: f64,
+    /// Best alignment of each area.
+    pub alignments: Vec<Alignment>,
+}
+
+/// Transformation which has been applied to the other layout to match this layout.
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alignment {
+    pub area: AreaType,
+    pub shift_x: i32,
+    pub shift_y: i32,
+    pub mirrored: bool,
+}
+
+/// MinHash signature of a [CourseLayout].
+///
+/// Signatures are stable across versions of this library, so they can be persisted.
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signature(Vec<u64>);
+
+impl Signature {
+    /// Estimate the Jaccard index of the local patterns of both layouts.
+    pub fn estimate_similarity(&self, other: &Signature) -> f64 {
+        let equal = self
+            .0
+            .iter()
+            .zip(other.0.iter())
+            .filter(|(a, b)| a == b)
+            .count();
+        equal as f64 / SIGNATURE_SIZE as f64
+    }
+
+    fn band_keys(&self) -> impl Iterator<Item = u64> + '_ {
+        self.0
+            .chunks(BAND_ROWS)
+            .map(|band| band.iter().fold(0, |acc, value| mix(acc ^ value)))
+    }
+}
+
+/// A match returned by [SimilarityIndex::query].
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimilarityMatch {
+    pub id: String,
+    /// Estimated similarity between `0.0` and `1.0`.
+    pub score: f64,
+}
+
+/// Index to find the nearest matches of a course among many stored courses.
+///
+/// Signatures are bucketed via locality-sensitive hashing,
+/// so only courses with a reasonable similarity are considered for a query.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default)]
+pub struct SimilarityIndex {
+    entries: Vec<(String, Signature)>,
+    ids: HashMap<String, usize>,
+    bands: Vec<HashMap<u64, Vec<usize>>>,
+}
+
+impl SimilarityIndex {
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Insert a layout.
+    ///
+    /// An existing entry with the same ID gets replaced.
+    pub fn insert(&mut self, id: String, layout: &CourseLayout) {
+        self.insert_signature(id, layout.signature());
+    }
+
+    /// Insert a previously computed signature.
+    pub fn insert_signature(&mut self, id: String, signature: Signature) {
+        self.remove(&id);
+        if self.bands.is_empty() {
+            self.bands = vec![HashMap::new(); BANDS];
+        }
+        let index = self.entries.len();
+        for (band, key) in self.bands.iter_mut().zip(signature.band_keys()) {
+            band.entry(key).or_default().push(index);
+        }
+        self.ids.insert(id.clone(), index);
+        self.entries.push((id, signature));
+    }
+
+    /// Remove the entry with the given ID.
+    ///
+    /// Returns `false`, if no such entry exists.
+    ///
+    /// The last entry takes the place of the removed one, so the index does not grow
+    /// when entries are replaced.
+    pub fn remove(&mut self, id: &str) -> bool {
+        let Some(index) = self.ids.remove(id) else {
+            return false;
+        };
+        let (_, signature) = self.entries.swap_remove(index);
+        for (band, key) in self.bands.iter_mut().zip(signature.band_keys()) {
+            if let Some(indices) = band.get_mut(&key) {
+                indices.retain(|other| *other != index);
+                if indices.is_empty() {
+                    band.remove(&key);
+                }
+            }
+        }
+        if let Some((moved_id, moved)) = self.entries.get(index) {
+            let last = self.entries.len();
+            for (band, key) in self.bands.iter_mut().zip(moved.band_keys()) {
+                for other in band.get_mut(&key).into_iter().flatten() {
+                    if *other == last {
+                        *other = index;
+                    }
+                }
+            }
+            self.ids.insert(moved_id.clone(), index);
+        }
+        true
+    }
+
+    /// Find the nearest matches of the given layout, sorted by descending similarity.
+    pub fn query(&self, layout: &CourseLayout, limit: usize) -> Vec<SimilarityMatch> {
+        self.query_signature(&layout.signature(), limit)
+    }
+
+    pub fn query_signature(&self, signature: &Signature, limit: usize) -> Vec<SimilarityMatch> {
+        let mut candidates = HashSet::new();
+        for (band, key) in self.bands.iter().zip(signature.band_keys()) {
+            if let Some(indices) = band.get(&key) {
+                candidates.extend(indices.iter().copied());
+            }
+        }
+        let mut matches: Vec<SimilarityMatch> = candidates
+            .into_iter()
+            .map(|index| &self.entries[index])
+            .map(|(id, other)| SimilarityMatch {
+                id: id.clone(),
+                score: signature.estimate_similarity(other),
+            })
+            .collect();
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.id.cmp(&b.id)));
+        matches.truncate(limit);
+        matches
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl SimilarityIndex {
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(constructor)]
+    pub fn new_js() -> SimilarityIndex {
+        SimilarityIndex::default()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = insertCourse2)]
+    pub fn insert_course2_js(&mut self, id: String, course: &Course2) -> JsResult<()> {
+        self.insert(id, &course.get_layout()?);
+        Ok(())
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = insertCourse)]
+    pub fn insert_course_js(&mut self, id: String, course: &Course) {
+        self.insert(id, &course.get_layout());
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = remove)]
+    pub fn remove_js(&mut self, id: &str) -> bool {
+        self.remove(id)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[cfg(feature = "with-serde")]
+    #[wasm_bindgen(js_name = queryCourse2)]
+    pub fn query_course2_js(&self, course: &Course2, limit: usize) -> JsResult<JsValue> {
+        Ok(serde_wasm_bindgen::to_value(
+            &self.query(&course.get_layout()?, limit),
+        )?)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[cfg(feature = "with-serde")]
+    #[wasm_bindgen(js_name = queryCourse)]
+    pub fn query_course_js(&self, course: &Course, limit: usize) -> JsResult<JsValue> {
+        Ok(serde_wasm_bindgen::to_value(
+            &self.query(&course.get_layout(), limit),
+        )?)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Course2 {
+    #[cfg(target_arch = "wasm32")]
+    #[cfg(feature = "with-serde")]
+    #[wasm_bindgen(js_name = getSimilarity)]
+    pub fn similarity_js(&self, other: &Course2, options: SimilarityOptions) -> JsResult<JsValue> {
+        let similarity = self.get_layout()?.similarity(&other.get_layout()?, options);
+        Ok(serde_wasm_bindgen::to_value(&similarity)?)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Course {
+    #[cfg(target_arch = "wasm32")]
+    #[cfg(feature = "with-serde")]
+    #[wasm_bindgen(js_name = getSimilarity)]
+    pub fn similarity_js(&self, other: &Course, options: SimilarityOptions) -> JsResult<JsValue> {
+        let similarity = self.get_layout().similarity(&other.get_layout(), options);
+        Ok(serde_wasm_bindgen::to_value(&similarity)?)
+    }
+}
+
+/// Stable 64 bit mixing function (SplitMix64 finalizer).
+fn mix(mut value: u64) -> u64 {
+    value = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    value ^ (value >> 31)
+}
+
+fn hash_values(values: &[i32]) -> u64 {
+    values
+        .iter()
+        .fold(0, |acc, value| mix(acc ^ (*value as u32 as u64)))
+}
+
+fn hash_pattern(seed: u64, pattern: &[Cell]) -> u64 {
+    pattern.iter().fold(seed, |acc, (dx, dy, kind)| {
+        mix(acc ^ hash_values(&[*dx, *dy, *kind]))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn signature(seed: u64) -> Signature {
+        Signature((0..SIGNATURE_SIZE as u64).map(|i| mix(seed ^ i)).collect())
+    }
+
+    #[test]
+    fn similarity_index_replace() {
+        let mut index = SimilarityIndex::default();
+        index.insert_signature("a".to_string(), signature(0));
+        index.insert_signature("b".to_string(), signature(1));
+        for seed in 2..1000 {
+            index.insert_signature("a".to_string(), signature(seed));
+        }
+
+        assert_eq!(index.entries.len(), 2);
+        for band in index.bands.iter() {
+            assert_eq!(band.values().map(Vec::len).sum::<usize>(), 2);
+        }
+        let matches = index.query_signature(&signature(1), 2);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].id, "b");
+        assert_eq!(index.query_signature(&signature(999), 2)[0].id, "a");
+
+        assert!(index.remove("b"));
+        assert_eq!(index.entries.len(), 1);
+        assert!(index.query_signature(&signature(1), 2).is_empty());
+        assert_eq!(index.query_signature(&signature(999), 2)[0].score, 1.0);
+    }
+}
@@ -0,0 +1,224 @@
+//! Object and tile statistics of courses.
+//!
+//! [CourseStatistics] summarize a course as histograms of object types per area,
+//! which makes it possible to show and filter courses by the parts they use.
+//! Objects are keyed by [ObjectId2] for Super Mario Maker 2 and by [ObjectId] for Super Mario Maker.
+
+#[cfg(target_arch = "wasm32")]
+use crate::JsResult;
+use crate::{
+    area::ObjectId,
+    area2::{AreaType, ObjectId2, OBJECT_TILE_SIZE},
+    proto::SMM2Course::{SMM2CourseArea_AutoScroll, SMM2CourseArea_Orientation},
+    Course, Course2, Result,
+};
+
+use std::collections::BTreeMap;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+/// Size of a single tile in pixels.
+const TILE_PIXELS: u32 = 16;
+
+/// Width of a screen in pixels.
+const SCREEN_WIDTH: u32 = 24 * TILE_PIXELS;
+
+/// Height of a screen in pixels.
+const SCREEN_HEIGHT: u32 = 27 * TILE_PIXELS / 2;
+
+/// Statistics of both areas of a course, whose objects are typed by `T`.
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CourseStatistics<T> {
+    pub areas: Vec<AreaStatistics<T>>,
+    /// At least one clear pipe has been placed.
+    pub uses_clear_pipes: bool,
+    /// The sub area contains any objects or ground tiles.
+    pub has_sub_area: bool,
+    /// At least one area scrolls along a custom path.
+    pub uses_custom_scroll: bool,
+}
+
+impl<T: Ord> CourseStatistics<T> {
+    fn new() -> CourseStatistics<T> {
+        CourseStatistics {
+            areas: vec![],
+            uses_clear_pipes: false,
+            has_sub_area: false,
+            uses_custom_scroll: false,
+        }
+    }
+
+    /// Returns the amount of objects with the given type in both areas.
+    pub fn count(&self, object_type: T) -> u32
+    where
+        T: Copy,
+    {
+        self.areas.iter().map(|area| area.count(object_type)).sum()
+    }
+}
+
+/// Statistics of a single course area.
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct AreaStatistics<T> {
+    pub area: AreaType,
+    /// Amount of objects by object type.
+    ///
+    /// Ground is excluded, because it is counted in [ground](AreaStatistics::ground).
+    pub objects: BTreeMap<T, u32>,
+    /// Amount of objects with an unknown object type.
+    pub unknown: u32,
+    /// Amount of ground tiles.
+    pub ground: u32,
+    /// Amount of objects in each screen along the scroll direction of the area.
+    pub screens: Vec<u32>,
+    /// Average amount of objects per screen.
+    pub density: f64,
+}
+
+impl<T: Ord> AreaStatistics<T> {
+    /// Collects statistics from the screen and object type of every object.
+    ///
+    /// `length` is the size of the area along its scroll direction in screens.
+    fn new(
+        area: AreaType,
+        objects: impl Iterator<Item = (usize, Option<T>)>,
+        ground: u32,
+        length: f64,
+    ) -> AreaStatistics<T> {
+        let mut statistics = AreaStatistics {
+            area,
+            objects: BTreeMap::new(),
+            unknown: 0,
+            ground,
+            screens: vec![0; (length.ceil() as usize).max(1)],
+            density: 0.0,
+        };
+        let last = statistics.screens.len() - 1;
+        let mut total = 0;
+        for (screen, object_type) in objects {
+            match object_type {
+                Some(object_type) => *statistics.objects.entry(object_type).or_default() += 1,
+                None => statistics.unknown += 1,
+            }
+            statistics.screens[screen.min(last)] += 1;
+            total += 1;
+        }
+        statistics.density = total as f64 / length.max(1.0);
+        statistics
+    }
+
+    /// Returns the amount of objects with the given type.
+    pub fn count(&self, object_type: T) -> u32 {
+        self.objects.get(&object_type).copied().unwrap_or_default()
+    }
+}
+
+impl Course2 {
+    /// Compute object and tile statistics of this course.
+    ///
+    /// This fails, if the course has no decrypted course data.
+    pub fn get_statistics(&self) -> Result<CourseStatistics<ObjectId2>> {
+        let course = self.get_course();
+        let mut statistics = CourseStatistics::new();
+        for (area, settings) in [
+            (AreaType::Main, course.get_course_area()),
+            (AreaType::Sub, course.get_course_sub_area()),
+        ] {
+            let entities = self.get_area(area)?;
+            let vertical = settings.get_orientation() == SMM2CourseArea_Orientation::VERTICAL;
+            let length = if vertical {
+                settings
+                    .top_boundary
+                    .saturating_sub(settings.bottom_boundary) as f64
+                    / SCREEN_HEIGHT as f64
+            } else {
+                settings
+                    .right_boundary
+                    .saturating_sub(settings.left_boundary) as f64
+                    / SCREEN_WIDTH as f64
+            };
+            let objects = entities.objects.iter().map(|object| {
+                let (position, screen_size) = if vertical {
+                    (object.y, SCREEN_HEIGHT)
+                } else {
+                    (object.x, SCREEN_WIDTH)
+                };
+                let pixels = position.max(0) as u32 * TILE_PIXELS / OBJECT_TILE_SIZE as u32;
+                ((pixels / screen_size) as usize, object.object_id())
+            });
+            statistics.areas.push(AreaStatistics::new(
+                area,
+                objects,
+                entities.ground.len() as u32,
+                length,
+            ));
+            statistics.uses_clear_pipes |= !entities.clear_pipes.is_empty();
+            statistics.uses_custom_scroll |=
+                settings.get_auto_scroll() == SMM2CourseArea_AutoScroll::CUSTOM;
+            if area == AreaType::Sub {
+                statistics.has_sub_area =
+                    !entities.objects.is_empty() || !entities.ground.is_empty();
+            }
+        }
+        Ok(statistics)
+    }
+}
+
+impl Course {
+    /// Compute object and tile statistics of this course.
+    ///
+    /// Super Mario Maker has neither clear pipes nor custom scroll,
+    /// so only the sub area flag can be set.
+    pub fn get_statistics(&self) -> CourseStatistics<ObjectId> {
+        let course = self.get_course();
+        let mut statistics = CourseStatistics::new();
+        // widths are stored in pixels, e.g. 3840 for the longest courses of 240 tiles
+        for (area, width) in [
+            (AreaType::Main, course.width),
+            (AreaType::Sub, course.width_sub),
+        ] {
+            let objects = self.get_objects(area);
+            let ground = objects
+                .iter()
+                .filter(|object| object.object_id() == Some(ObjectId::Ground))
+                .count() as u32;
+            let objects = objects
+                .iter()
+                .filter(|object| object.object_id() != Some(ObjectId::Ground))
+                .map(|object| {
+                    let pixels = object.x as u64 * TILE_PIXELS as u64 / OBJECT_TILE_SIZE as u64;
+                    ((pixels / SCREEN_WIDTH as u64) as usize, object.object_id())
+                });
+            statistics.areas.push(AreaStatistics::new(
+                area,
+                objects,
+                ground,
+                width as f64 / SCREEN_WIDTH as f64,
+            ));
+        }
+        statistics.has_sub_area = !course.tiles_sub.is_empty();
+        statistics
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Course2 {
+    #[cfg(target_arch = "wasm32")]
+    #[cfg(feature = "with-serde")]
+    #[wasm_bindgen(js_name = getStatistics)]
+    pub fn get_statistics_js(&self) -> JsResult<JsValue> {
+        Ok(serde_wasm_bindgen::to_value(&self.get_statistics()?)?)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Course {
+    #[cfg(target_arch = "wasm32")]
+    #[cfg(feature = "with-serde")]
+    #[wasm_bindgen(js_name = getStatistics)]
+    pub fn get_statistics_js(&self) -> JsResult<JsValue> {
+        Ok(serde_wasm_bindgen::to_value(&self.get_statistics())?)
+    }
+}
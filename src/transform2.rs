@@ -0,0 +1,418 @@
+//! Geometric transforms of Super Mario Maker 2 course areas.
+//!
+//! Transforms rewrite the positions of all entities in the decrypted course data.
+//! A transform is rejected as a whole, if any entity would end up outside of the area,
+//! so a failed transform leaves the course untouched.
+//!
+//! The start and goal grounds of the main area are not entities,
+//! but they follow the transforms of the main area as far as the game allows:
+//! the start is always placed at the left and the goal always keeps its distance to the right boundary.
+
+#[cfg(target_arch = "wasm32")]
+use crate::JsResult;
+use crate::{
+    area2::{Area2, AreaType, EntityTable2, Object2, ObjectId2, OBJECT_TILE_SIZE},
+    constants2::*,
+    errors::Smm2Error,
+    proto::SMM2Course::SMM2CourseArea_Orientation,
+    validation2::{BOUNDARY_TILE_SIZE, HORIZONTAL_WIDTH},
+    Course2, Result,
+};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+/// Tables which store the position of an entry in tiles
+/// together with the offset of the X coordinate, which is directly followed by the Y coordinate.
+const TILE_TABLES: [(EntityTable2, usize); 4] = [
+    (EntityTable2::SoundEffects, 0x1),
+    (EntityTable2::Ground, 0x0),
+    (EntityTable2::Tracks, 0x3),
+    (EntityTable2::Icicles, 0x0),
+];
+
+/// Tables of paths, which only consist of directions, together with their maximum amount of nodes.
+const PATH_TABLES: [(EntityTable2, usize); 3] = [
+    (EntityTable2::PiranhaCreepers, PIRANHA_CREEPER_NODE_MAX),
+    (EntityTable2::ExclamationBlocks, MOVING_BLOCK_NODE_MAX),
+    (EntityTable2::TrackBlocks, MOVING_BLOCK_NODE_MAX),
+];
+
+const TRACK_TYPE_OFFSET: usize = 0x5;
+
+/// Mirror images of the track types.
+///
+/// Tracks are straight horizontal, straight vertical, one of two diagonals or one of four quarter curves.
+const TRACK_MIRROR: [u8; 8] = [0, 1, 3, 2, 7, 6, 5, 4];
+
+/// Directions of clear pipe nodes.
+const CLEAR_PIPE_RIGHT: u8 = 0;
+const CLEAR_PIPE_LEFT: u8 = 1;
+const CLEAR_PIPE_UP: u8 = 2;
+const CLEAR_PIPE_DOWN: u8 = 3;
+
+/// The finish position is stored in tenths of a tile.
+const FINISH_X_TILE_SIZE: i32 = 10;
+
+/// Bits of the object flags of a pipe, which store the direction it is facing.
+///
+/// Directions are right, left, up and down in this order.
+const PIPE_DIRECTION_MASK: u32 = 0x3 << 5;
+const PIPE_LEFT: u32 = 0x1 << 5;
+const PIPE_UP: u32 = 0x2 << 5;
+
+/// Bit of the object flags of a slope, which is set for slopes going down to the right.
+const SLOPE_DOWN: u32 = 0x1 << 20;
+
+#[derive(Clone, Copy, Debug)]
+enum Mapping {
+    /// Move everything by the given amount of tiles.
+    Shift { x: i32, y: i32 },
+    /// Mirror horizontally in an area of the given width in tiles.
+    Mirror { width: i32 },
+}
+
+impl Mapping {
+    fn tile(self, x: i32, y: i32) -> (i32, i32) {
+        match self {
+            Mapping::Shift { x: dx, y: dy } => (x + dx, y + dy),
+            Mapping::Mirror { width } => (width - 1 - x, y),
+        }
+    }
+
+    fn object(self, object: &Object2) -> (i32, i32) {
+        match self {
+            Mapping::Shift { x, y } => (
+                object.x + x * OBJECT_TILE_SIZE,
+                object.y + y * OBJECT_TILE_SIZE,
+            ),
+            Mapping::Mirror { width } => {
+                // the object then ends on the tile on which it used to start
+                let anchor = if is_left_anchored(object) {
+                    (object.width as i32 - 1) * OBJECT_TILE_SIZE
+                } else {
+                    0
+                };
+                (width * OBJECT_TILE_SIZE - object.x - anchor, object.y)
+            }
+        }
+    }
+
+    fn object_flags(self, object: &Object2) -> u32 {
+        match (self, object.object_id()) {
+            (Mapping::Mirror { .. }, Some(ObjectId2::Pipe))
+                if object.flags & PIPE_DIRECTION_MASK < PIPE_UP =>
+            {
+                object.flags ^ PIPE_LEFT
+            }
+            (Mapping::Mirror { .. }, Some(ObjectId2::SlightSlope | ObjectId2::SteepSlope)) => {
+                object.flags ^ SLOPE_DOWN
+            }
+            _ => object.flags,
+        }
+    }
+
+    fn track_type(self, track_type: u8) -> Option<u8> {
+        match self {
+            Mapping::Shift { .. } => Some(track_type),
+            Mapping::Mirror { .. } => TRACK_MIRROR.get(track_type as usize).copied(),
+        }
+    }
+
+    /// Map a clear pipe node to its new position, type and direction.
+    ///
+    /// Straight nodes are positioned at their first tile on the left hand side of their direction
+    /// and extend by their height into their direction and by their width to the right hand side.
+    /// All other nodes are corners, which are positioned at their bottom left tile.
+    fn clear_pipe_node(self, node: &[u8]) -> Option<(i32, i32, u8, u8)> {
+        let (node_type, direction) = (node[0], node[7]);
+        let (x, y) = self.tile(node[2] as i32, node[3] as i32);
+        if let Mapping::Shift { .. } = self {
+            return Some((x, y, node_type, direction));
+        }
+        let node_type = match node_type {
+            // start, end and middle nodes are straight
+            0 | 1 | 11 => node_type,
+            // corners from right to down and up, and from left to down and up
+            3 | 4 => node_type + 2,
+            5 | 6 => node_type - 2,
+            // corners from up to left and right, and from down to left and right
+            7 | 9 => node_type + 1,
+            8 | 10 => node_type - 1,
+            _ => return None,
+        };
+        // the left hand side of a node becomes its right hand side
+        let offset = node[4] as i32 - 1;
+        match (node_type, direction) {
+            (3..=10, _) => Some((x - offset, y, node_type, direction)),
+            (_, CLEAR_PIPE_RIGHT) => Some((x, y - offset, node_type, CLEAR_PIPE_LEFT)),
+            (_, CLEAR_PIPE_LEFT) => Some((x, y + offset, node_type, CLEAR_PIPE_RIGHT)),
+            (_, CLEAR_PIPE_UP) => Some((x - offset, y, node_type, direction)),
+            (_, CLEAR_PIPE_DOWN) => Some((x + offset, y, node_type, direction)),
+            _ => None,
+        }
+    }
+
+    /// Map a direction of a path node.
+    ///
+    /// Horizontal directions come in pairs, which are swapped by mirroring:
+    /// the moves 1 and 2, the turns 5 to 8 and 9 to 12, which start or end with these moves,
+    /// and the ends 13 and 14. The vertical moves 3 and 4 and the ends 15 and 16 are kept.
+    fn path_direction(self, direction: u8) -> Option<u8> {
+        match (self, direction) {
+            (Mapping::Shift { .. }, _) => Some(direction),
+            (Mapping::Mirror { .. }, 1 | 13) => Some(direction + 1),
+            (Mapping::Mirror { .. }, 2 | 14) => Some(direction - 1),
+            (Mapping::Mirror { .. }, 3 | 4 | 15 | 16) => Some(direction),
+            (Mapping::Mirror { .. }, 5..=8) => Some(direction + 4),
+            (Mapping::Mirror { .. }, 9..=12) => Some(direction - 4),
+            _ => None,
+        }
+    }
+
+    /// Map the heights of the start and goal grounds.
+    fn start_goal(self, start_y: i32, finish_y: i32) -> (i32, i32) {
+        match self {
+            Mapping::Shift { y, .. } => (start_y + y, finish_y + y),
+            Mapping::Mirror { .. } => (finish_y, start_y),
+        }
+    }
+}
+
+/// Returns `true` for objects whose position is the center of their leftmost tile
+/// instead of their horizontal center.
+fn is_left_anchored(object: &Object2) -> bool {
+    match object.object_id() {
+        Some(ObjectId2::Pipe) => object.flags & PIPE_DIRECTION_MASK >= PIPE_UP,
+        Some(
+            ObjectId2::SemisolidPlatform
+            | ObjectId2::MushroomPlatform
+            | ObjectId2::Bridge
+            | ObjectId2::SlightSlope
+            | ObjectId2::SteepSlope
+            | ObjectId2::ConveyorBelt
+            | ObjectId2::FastConveyorBelt
+            | ObjectId2::HalfCollisionPlatform,
+        ) => true,
+        _ => false,
+    }
+}
+
+impl Course2 {
+    /// Mirror an area horizontally.
+    ///
+    /// Pipes and slopes are turned around.
+    /// All other objects keep the direction they are facing.
+    /// Tracks, clear pipes and the paths of piranha creepers, exclamation blocks and track blocks
+    /// are mirrored along with their shapes.
+    /// The heights of the start and goal grounds are swapped.
+    ///
+    /// The shapes and directions are taken from the courses of the test saves,
+    /// which contain no snake blocks, so this fails for areas with snake blocks
+    /// and for tracks, clear pipes or paths with unknown shapes or directions.
+    pub fn mirror_area(&mut self, area: AreaType) -> Result<()> {
+        let width = self.get_area_width(area)?;
+        self.transform_area(
+            area,
+            Mapping::Mirror {
+                width: width as i32,
+            },
+            width,
+        )
+    }
+
+    /// Move all entities of an area by the given amount of tiles.
+    ///
+    /// Positive values move to the right and upwards.
+    /// Start and goal grounds only move vertically.
+    pub fn shift_area(&mut self, area: AreaType, x: i32, y: i32) -> Result<()> {
+        let width = self.get_area_width(area)?;
+        self.transform_area(area, Mapping::Shift { x, y }, width)
+    }
+
+    /// Crop a horizontal area to the tiles from `left` up to, but not including, `right`.
+    ///
+    /// Entities are not removed, so this fails, if any entity lies outside of the given range.
+    pub fn crop_area(&mut self, area: AreaType, left: u32, right: u32) -> Result<()> {
+        let width = right.saturating_sub(left);
+        self.check_area_width(area, width)?;
+        self.transform_area(
+            area,
+            Mapping::Shift {
+                x: -(left as i32),
+                y: 0,
+            },
+            width,
+        )
+    }
+
+    /// Extend or shrink a horizontal area to the given width in tiles
+    /// by moving its right boundary.
+    ///
+    /// This fails, if any entity lies outside of the new boundary.
+    pub fn resize_area(&mut self, area: AreaType, width: u32) -> Result<()> {
+        self.check_area_width(area, width)?;
+        self.transform_area(area, Mapping::Shift { x: 0, y: 0 }, width)
+    }
+
+    /// Width of an area in tiles.
+    fn get_area_width(&self, area: AreaType) -> Result<u32> {
+        if !self.has_course_data() {
+            return Err(Smm2Error::CourseDataRequired.into());
+        }
+        let settings = self.get_area_settings(area);
+        Ok(settings.right_boundary / BOUNDARY_TILE_SIZE)
+    }
+
+    /// Only horizontal areas can change their width.
+    fn check_area_width(&self, area: AreaType, width: u32) -> Result<()> {
+        let settings = self.get_area_settings(area);
+        if settings.orientation != SMM2CourseArea_Orientation::HORIZONTAL
+            || !HORIZONTAL_WIDTH.contains(&(width * BOUNDARY_TILE_SIZE))
+        {
+            return Err(Smm2Error::InvalidAreaWidth(width).into());
+        }
+        Ok(())
+    }
+
+    /// Apply a mapping to all entities of an area and set its width in tiles.
+    fn transform_area(&mut self, area: AreaType, mapping: Mapping, width: u32) -> Result<()> {
+        if !self.has_course_data() {
+            return Err(Smm2Error::CourseDataRequired.into());
+        }
+        let mut data = self.get_course_data().to_vec();
+        let table = EntityTable2::SnakeBlocks;
+        if matches!(mapping, Mapping::Mirror { .. }) && table.count_entries(&data, area) != 0 {
+            return Err(Smm2Error::MirrorUnsupported(table).into());
+        }
+        let settings = self.get_area_settings(area);
+        let old_width = (settings.right_boundary / BOUNDARY_TILE_SIZE) as i32;
+        let width = width as i32;
+        let height = (settings.top_boundary / BOUNDARY_TILE_SIZE) as i32;
+
+        let table = EntityTable2::Objects;
+        for (index, object) in Area2::from_course_data(&data, area)
+            .objects
+            .iter()
+            .enumerate()
+        {
+            let (x, y) = mapping.object(object);
+            if !(0..=width * OBJECT_TILE_SIZE).contains(&x)
+                || !(0..=height * OBJECT_TILE_SIZE).contains(&y)
+            {
+                return Err(Smm2Error::EntityOutOfBounds { table, index }.into());
+            }
+            let entry = table.offset(area) + index * table.entry_size();
+            data[entry..entry + 4].copy_from_slice(&x.to_le_bytes());
+            data[entry + 4..entry + 8].copy_from_slice(&y.to_le_bytes());
+            data[entry + OBJECT_FLAGS_OFFSET..entry + OBJECT_FLAGS_OFFSET + 4]
+                .copy_from_slice(&mapping.object_flags(object).to_le_bytes());
+        }
+
+        for (table, position) in TILE_TABLES {
+            let count = (table.read_count(&data, area) as usize).min(table.max());
+            for index in 0..count {
+                let entry = table.offset(area) + index * table.entry_size() + position;
+                let (x, y) = mapping.tile(data[entry] as i32, data[entry + 1] as i32);
+                if !(0..width).contains(&x) || !(0..height).contains(&y) {
+                    return Err(Smm2Error::EntityOutOfBounds { table, index }.into());
+                }
+                data[entry] = x as u8;
+                data[entry + 1] = y as u8;
+                if table == EntityTable2::Tracks {
+                    let entry = entry - position + TRACK_TYPE_OFFSET;
+                    data[entry] = mapping
+                        .track_type(data[entry])
+                        .ok_or(Smm2Error::MirrorUnsupported(table))?;
+                }
+            }
+        }
+
+        let table = EntityTable2::ClearPipes;
+        for index in table.used_entries(&data, area) {
+            let entry = table.offset(area) + index * table.entry_size();
+            let node_count = (data[entry + 1] as usize).min(CLEAR_PIPE_NODE_MAX);
+            for node in 0..node_count {
+                let node = entry + 4 + node * CLEAR_PIPE_NODE_SIZE;
+                let (x, y, node_type, direction) = mapping
+                    .clear_pipe_node(&data[node..node + CLEAR_PIPE_NODE_SIZE])
+                    .ok_or(Smm2Error::MirrorUnsupported(table))?;
+                if !(0..width).contains(&x) || !(0..height).contains(&y) {
+                    return Err(Smm2Error::EntityOutOfBounds { table, index }.into());
+                }
+                data[node] = node_type;
+                data[node + 2] = x as u8;
+                data[node + 3] = y as u8;
+                data[node + 7] = direction;
+            }
+        }
+
+        for (table, node_max) in PATH_TABLES {
+            for index in table.used_entries(&data, area) {
+                let entry = table.offset(area) + index * table.entry_size();
+                let node_count = (data[entry + 2] as usize).min(node_max);
+                for node in 0..node_count {
+                    let node = entry + 4 + node * PATH_NODE_SIZE;
+                    data[node + 1] = mapping
+                        .path_direction(data[node + 1])
+                        .ok_or(Smm2Error::MirrorUnsupported(table))?;
+                }
+            }
+        }
+
+        let offset = RIGHT_BOUNDARY_OFFSET[area.index()];
+        data[offset..offset + 4]
+            .copy_from_slice(&(width as u32 * BOUNDARY_TILE_SIZE).to_le_bytes());
+
+        if area == AreaType::Main {
+            let (start_y, finish_y) =
+                mapping.start_goal(data[START_Y_OFFSET] as i32, data[FINISH_Y_OFFSET] as i32);
+            let finish_x = u16::from_le_bytes(*array_ref!(data, FINISH_X_OFFSET, 2)) as i32
+                + (width - old_width) * FINISH_X_TILE_SIZE;
+            if !(0..height).contains(&start_y)
+                || !(0..height).contains(&finish_y)
+                || !(0..width * FINISH_X_TILE_SIZE).contains(&finish_x)
+            {
+                return Err(Smm2Error::StartGoalOutOfBounds.into());
+            }
+            data[START_Y_OFFSET] = start_y as u8;
+            data[FINISH_Y_OFFSET] = finish_y as u8;
+            data[FINISH_X_OFFSET..FINISH_X_OFFSET + 2]
+                .copy_from_slice(&(finish_x as u16).to_le_bytes());
+            let header = self.get_course_mut().mut_header();
+            header.set_start_y(start_y as u32);
+            header.set_finish_y(finish_y as u32);
+            header.set_finish_x(finish_x as u32);
+        }
+
+        self.get_course_data_mut().copy_from_slice(&data);
+        self.update_areas()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Course2 {
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = mirrorArea)]
+    pub fn mirror_area_js(&mut self, area: AreaType) -> JsResult<()> {
+        Ok(self.mirror_area(area)?)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = shiftArea)]
+    pub fn shift_area_js(&mut self, area: AreaType, x: i32, y: i32) -> JsResult<()> {
+        Ok(self.shift_area(area, x, y)?)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = cropArea)]
+    pub fn crop_area_js(&mut self, area: AreaType, left: u32, right: u32) -> JsResult<()> {
+        Ok(self.crop_area(area, left, right)?)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(js_name = resizeArea)]
+    pub fn resize_area_js(&mut self, area: AreaType, width: u32) -> JsResult<()> {
+        Ok(self.resize_area(area, width)?)
+    }
+}
@@ -0,0 +1,377 @@
+//! Course validation.
+//!
+//! Validators never fail on malformed courses.
+//! Every problem is instead reported as a [Diagnostic], so that all of them can be shown at once.
+//!
+//! Super Mario Maker courses are validated on their raw Wii U files,
+//! because [from_wii_u_files](Course::from_wii_u_files) does not handle malformed files gracefully.
+//! Super Mario Maker 2 courses are validated via [validation2](crate::validation2).
+
+#[cfg(all(target_arch = "wasm32", feature = "with-serde"))]
+use crate::JsResult;
+use crate::{
+    area2::{AreaType, OBJECT_TILE_SIZE},
+    constants::*,
+    proto::SMMCourse::{SMMCourse_AutoScroll, SMMCourse_CourseTheme},
+    Course,
+};
+
+use chrono::naive::{NaiveDate, NaiveTime};
+use crc::{Crc, CRC_32_ISO_HDLC};
+use protobuf::ProtobufEnum;
+use std::fmt::{self, Display};
+use thiserror::Error;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+/// How severe a reported problem is.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    /// The course is loaded by the game, but might not behave as expected.
+    Warning,
+    /// The course is rejected by the game or might crash it.
+    Error,
+}
+
+/// A single problem found by a validator.
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic<K> {
+    pub severity: Severity,
+    /// The area this problem belongs to, if it is specific to one area.
+    pub area: Option<AreaType>,
+    pub kind: K,
+}
+
+impl<K> Diagnostic<K> {
+    pub(crate) fn error(area: Option<AreaType>, kind: K) -> Diagnostic<K> {
+        Diagnostic {
+            severity: Severity::Error,
+            area,
+            kind,
+        }
+    }
+
+    pub(crate) fn warning(area: Option<AreaType>, kind: K) -> Diagnostic<K> {
+        Diagnostic {
+            severity: Severity::Warning,
+            area,
+            kind,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl<K: Display> Display for Diagnostic<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        match self.area {
+            Some(AreaType::Main) => write!(f, "{} (main area): {}", severity, self.kind),
+            Some(AreaType::Sub) => write!(f, "{} (sub area): {}", severity, self.kind),
+            None => write!(f, "{}: {}", severity, self.kind),
+        }
+    }
+}
+
+/// Height of a course area in tiles.
+const AREA_HEIGHT: i32 = 27;
+
+/// Size of a tile in pixels, which is the unit of the course width.
+const WIDTH_TILE_SIZE: i32 = 16;
+
+/// Kind of a problem found in a Super Mario Maker course.
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum Lint {
+    #[error("file has {length} bytes, but {expected} bytes are required")]
+    FileTooShort { length: usize, expected: usize },
+    #[error(
+        "stored checksum {stored:#010x} does not match calculated checksum {calculated:#010x}"
+    )]
+    ChecksumMismatch { stored: u32, calculated: u32 },
+    #[error("{field} can not be parsed")]
+    Unparsable { field: String },
+    #[error("game style {sub} of the sub area does not match game style {main}")]
+    GameStyleMismatch { main: String, sub: String },
+    #[error("tile count {count} exceeds the maximum of {max}")]
+    TileLimitExceeded { count: usize, max: usize },
+    #[error("tile {index} at ({x}, {y}) is outside of the area boundaries")]
+    TileOutOfBounds { index: usize, x: i32, y: i16 },
+    #[error("sound {index} at ({x}, {y}) is outside of the area boundaries")]
+    SoundOutOfBounds { index: usize, x: u8, y: u8 },
+    #[error("{file} is too short")]
+    ThumbnailTooShort { file: String },
+    #[error("{file} does not contain a JPEG image")]
+    ThumbnailNotJpeg { file: String },
+    #[error(
+        "{file} checksum {stored:#010x} does not match calculated checksum {calculated:#010x}"
+    )]
+    ThumbnailChecksumMismatch {
+        file: String,
+        stored: u32,
+        calculated: u32,
+    },
+}
+
+impl Course {
+    /// Validate the raw files of a Wii U course.
+    ///
+    /// This never panics, regardless of the given buffers.
+    /// If no [errors](Severity::Error) are reported,
+    /// the files can safely be passed to [from_wii_u_files](Course::from_wii_u_files).
+    pub fn validate_wii_u_files(
+        course_data: &[u8],
+        course_data_sub: &[u8],
+        thumbnail: &[u8],
+        thumbnail_preview: &[u8],
+    ) -> Vec<Diagnostic<Lint>> {
+        let mut diagnostics = vec![];
+
+        let main_valid =
+            Course::validate_course_file(course_data, AreaType::Main, &mut diagnostics);
+        let sub_valid =
+            Course::validate_course_file(course_data_sub, AreaType::Sub, &mut diagnostics);
+        if main_valid {
+            Course::validate_header(course_data, &mut diagnostics);
+        }
+        if main_valid && sub_valid {
+            let main = &course_data[GAME_STYLE_OFFSET..GAME_STYLE_OFFSET_END];
+            let sub = &course_data_sub[GAME_STYLE_OFFSET..GAME_STYLE_OFFSET_END];
+            if main != sub {
+                diagnostics.push(Diagnostic::warning(
+                    Some(AreaType::Sub),
+                    Lint::GameStyleMismatch {
+                        main: String::from_utf8_lossy(main).into_owned(),
+                        sub: String::from_utf8_lossy(sub).into_owned(),
+                    },
+                ));
+            }
+        }
+
+        Course::validate_thumbnail(THUMBNAIL_0_NAME, thumbnail, &mut diagnostics);
+        Course::validate_thumbnail(THUMBNAIL_1_NAME, thumbnail_preview, &mut diagnostics);
+
+        diagnostics
+    }
+
+    /// Returns `false`, if the file is too short to be checked any further.
+    fn validate_course_file(
+        data: &[u8],
+        area: AreaType,
+        diagnostics: &mut Vec<Diagnostic<Lint>>,
+    ) -> bool {
+        if data.len() < COURSE_SIZE {
+            diagnostics.push(Diagnostic::error(
+                Some(area),
+                Lint::FileTooShort {
+                    length: data.len(),
+                    expected: COURSE_SIZE,
+                },
+            ));
+            return false;
+        }
+
+        let stored = u32::from_be_bytes(*array_ref!(data, CRC_OFFSET, 4));
+        let calculated = Crc::<u32>::new(&CRC_32_ISO_HDLC).checksum(&data[CRC_LENGTH..]);
+        if stored != calculated {
+            diagnostics.push(Diagnostic::error(
+                Some(area),
+                Lint::ChecksumMismatch { stored, calculated },
+            ));
+        }
+
+        if SMMCourse_CourseTheme::from_i32(data[COURSE_THEME_OFFSET] as i32).is_none() {
+            diagnostics.push(Diagnostic::error(
+                Some(area),
+                Lint::unparsable("course theme"),
+            ));
+        }
+        if SMMCourse_AutoScroll::from_i32(data[AUTO_SCROLL_OFFSET] as i32).is_none() {
+            diagnostics.push(Diagnostic::error(
+                Some(area),
+                Lint::unparsable("auto scroll"),
+            ));
+        }
+
+        let width = u16::from_be_bytes(*array_ref!(data, WIDTH_OFFSET, 2)) as i32;
+        Course::validate_tiles(data, area, width, diagnostics);
+        Course::validate_sounds(data, area, width, diagnostics);
+        true
+    }
+
+    fn validate_header(data: &[u8], diagnostics: &mut Vec<Diagnostic<Lint>>) {
+        let year = u16::from_be_bytes(*array_ref!(data, YEAR_OFFSET, 2));
+        let date = NaiveDate::from_ymd_opt(
+            year as i32,
+            data[MONTH_OFFSET] as u32,
+            data[DAY_OFFSET] as u32,
+        );
+        let time = NaiveTime::from_hms_opt(data[HOUR_OFFSET] as u32, data[MINUTE_OFFSET] as u32, 0);
+        if date.is_none() || time.is_none() {
+            diagnostics.push(Diagnostic::error(None, Lint::unparsable("modified")));
+        }
+
+        for (field, bytes) in [
+            ("title", &data[TITLE_OFFSET..TITLE_OFFSET_END]),
+            ("maker", &data[MAKER_OFFSET..MAKER_OFFSET_END]),
+        ] {
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+                .filter(|unit| *unit != 0)
+                .collect();
+            if String::from_utf16(&units).is_err() {
+                diagnostics.push(Diagnostic::error(None, Lint::unparsable(field)));
+            }
+        }
+
+        if !matches!(
+            &data[GAME_STYLE_OFFSET..GAME_STYLE_OFFSET_END],
+            b"M1" | b"M3" | b"MW" | b"WU"
+        ) {
+            diagnostics.push(Diagnostic::error(None, Lint::unparsable("game style")));
+        }
+    }
+
+    fn validate_tiles(
+        data: &[u8],
+        area: AreaType,
+        width: i32,
+        diagnostics: &mut Vec<Diagnostic<Lint>>,
+    ) {
+        let count = u16::from_be_bytes(*array_ref!(data, TILE_AMOUNT_OFFSET, 2)) as usize;
+        if count > TILE_MAX {
+            diagnostics.push(Diagnostic::error(
+                Some(area),
+                Lint::TileLimitExceeded {
+                    count,
+                    max: TILE_MAX,
+                },
+            ));
+            return;
+        }
+
+        // tiles may stick out of the area by one tile
+        let tile_size = OBJECT_TILE_SIZE / WIDTH_TILE_SIZE;
+        let x_range = -OBJECT_TILE_SIZE..=width * tile_size + OBJECT_TILE_SIZE;
+        let y_range = -OBJECT_TILE_SIZE..=(AREA_HEIGHT + 1) * OBJECT_TILE_SIZE;
+        for (index, tile) in data[TILES_OFFSET..TILES_OFFSET + count * TILE_SIZE]
+            .chunks_exact(TILE_SIZE)
+            .enumerate()
+        {
+            let x = i32::from_be_bytes(*array_ref!(tile, 0x0, 4));
+            let y = i16::from_be_bytes(*array_ref!(tile, 0x8, 2));
+            if !x_range.contains(&x) || !y_range.contains(&(y as i32)) {
+                diagnostics.push(Diagnostic::warning(
+                    Some(area),
+                    Lint::TileOutOfBounds { index, x, y },
+                ));
+            }
+        }
+    }
+
+    fn validate_sounds(
+        data: &[u8],
+        area: AreaType,
+        width: i32,
+        diagnostics: &mut Vec<Diagnostic<Lint>>,
+    ) {
+        for (index, sound) in data[SOUND_OFFSET..SOUND_OFFSET_END]
+            .chunks_exact(SOUND_SIZE)
+            .enumerate()
+        {
+            if sound == SOUND_DEFAULT {
+                continue;
+            }
+            let x = sound[SOUND_X_OFFSET];
+            let y = sound[SOUND_Y_OFFSET];
+            if x as i32 >= width / WIDTH_TILE_SIZE || y as i32 >= AREA_HEIGHT {
+                diagnostics.push(Diagnostic::warning(
+                    Some(area),
+                    Lint::SoundOutOfBounds { index, x, y },
+                ));
+            }
+        }
+    }
+
+    fn validate_thumbnail(file: &str, thumbnail: &[u8], diagnostics: &mut Vec<Diagnostic<Lint>>) {
+        let length = thumbnail
+            .get(4..THUMBNAIL_HEADER_SIZE)
+            .map(|length| u32::from_be_bytes(*array_ref!(length, 0, 4)) as usize);
+        let image = length.and_then(|length| {
+            thumbnail.get(THUMBNAIL_HEADER_SIZE..THUMBNAIL_HEADER_SIZE.checked_add(length)?)
+        });
+        let image = match image {
+            Some(image) => image,
+            None => {
+                diagnostics.push(Diagnostic::error(
+                    None,
+                    Lint::ThumbnailTooShort {
+                        file: file.to_string(),
+                    },
+                ));
+                return;
+            }
+        };
+
+        if !image.starts_with(&[0xFF, 0xD8]) {
+            diagnostics.push(Diagnostic::error(
+                None,
+                Lint::ThumbnailNotJpeg {
+                    file: file.to_string(),
+                },
+            ));
+        }
+
+        let stored = u32::from_be_bytes(*array_ref!(thumbnail, 0, 4));
+        let calculated = Crc::<u32>::new(&CRC_32_ISO_HDLC).checksum(&thumbnail[4..]);
+        if stored != calculated {
+            diagnostics.push(Diagnostic::error(
+                None,
+                Lint::ThumbnailChecksumMismatch {
+                    file: file.to_string(),
+                    stored,
+                    calculated,
+                },
+            ));
+        }
+    }
+}
+
+impl Lint {
+    fn unparsable(field: &str) -> Lint {
+        Lint::Unparsable {
+            field: field.to_string(),
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Course {
+    #[cfg(target_arch = "wasm32")]
+    #[cfg(feature = "with-serde")]
+    #[wasm_bindgen(js_name = validateWiiUFiles)]
+    pub fn validate_wii_u_files_js(
+        course_data: &[u8],
+        course_data_sub: &[u8],
+        thumbnail: &[u8],
+        thumbnail_preview: &[u8],
+    ) -> JsResult<JsValue> {
+        Ok(serde_wasm_bindgen::to_value(
+            &Course::validate_wii_u_files(
+                course_data,
+                course_data_sub,
+                thumbnail,
+                thumbnail_preview,
+            ),
+        )?)
+    }
+}
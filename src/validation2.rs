@@ -0,0 +1,402 @@
+//! Validation of Super Mario Maker 2 courses.
+//!
+//! Courses which have been edited outside of the game, e.g. via
+//! [get_course_data_mut](Course2::get_course_data_mut), can crash the console.
+//! The validator checks the decrypted course data, which is exactly what the game reads.
+
+#[cfg(all(target_arch = "wasm32", feature = "with-serde"))]
+use crate::JsResult;
+use crate::{
+    area2::{Area2, AreaType, EntityTable2, ObjectId2, OBJECT_TILE_SIZE},
+    constants2::*,
+    errors::Smm2Error,
+    proto::SMM2Course::{SMM2CourseArea, SMM2CourseArea_Orientation, SMM2CourseHeader_GameStyle},
+    validation::Diagnostic,
+    Course2, Result,
+};
+
+use std::ops::RangeInclusive;
+use thiserror::Error;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+/// Maximum title length in UTF-16 code units.
+pub const TITLE_MAX_LENGTH: usize = TITLE_LENGTH / 2;
+/// Maximum description length in UTF-16 code units.
+pub const DESCRIPTION_MAX_LENGTH: usize = DESCRIPTION_LENGTH / 2;
+/// Allowed time limits in seconds.
+pub const TIME_RANGE: RangeInclusive<u32> = 10..=500;
+
+/// Size of a single tile in pixels, which is the unit of area boundaries.
+pub(crate) const BOUNDARY_TILE_SIZE: u32 = 16;
+const HORIZONTAL_HEIGHT: u32 = 27 * BOUNDARY_TILE_SIZE;
+pub(crate) const HORIZONTAL_WIDTH: RangeInclusive<u32> =
+    24 * BOUNDARY_TILE_SIZE..=240 * BOUNDARY_TILE_SIZE;
+const VERTICAL_WIDTH: u32 = 48 * BOUNDARY_TILE_SIZE;
+const VERTICAL_HEIGHT: RangeInclusive<u32> = 28 * BOUNDARY_TILE_SIZE..=168 * BOUNDARY_TILE_SIZE;
+
+/// The finish position is stored in tenths of a tile
+/// and the goal is always placed this far away from the right boundary.
+const FINISH_X_MARGIN: u32 = 95;
+
+/// Kind of a problem found in a Super Mario Maker 2 course.
+#[cfg_attr(feature = "with-serde", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum Lint2 {
+    #[error("course data can not be parsed: {reason}")]
+    Unparsable { reason: String },
+    #[error("{field} is not valid UTF-16")]
+    InvalidText { field: String },
+    #[error("title is empty")]
+    TitleEmpty,
+    #[error("title has {length} characters, but at most {max} are allowed")]
+    TitleTooLong { length: usize, max: usize },
+    #[error("description has {length} characters, but at most {max} are allowed")]
+    DescriptionTooLong { length: usize, max: usize },
+    #[error("time limit {time} is outside of {min}..={max}")]
+    TimeOutOfRange { time: u32, min: u32, max: u32 },
+    #[error("start height {start_y} is outside of the main area")]
+    StartOutOfBounds { start_y: u32 },
+    #[error("finish position ({finish_x}, {finish_y}) is outside of the main area")]
+    FinishOutOfBounds { finish_x: u32, finish_y: u32 },
+    #[error("finish position {finish_x} does not match the right boundary, expected {expected}")]
+    FinishMisplaced { finish_x: u32, expected: u32 },
+    #[error("main area must not be vertical")]
+    VerticalMainArea,
+    #[error(
+        "boundaries (left {left}, right {right}, top {top}, bottom {bottom}) are invalid for {orientation:?} areas"
+    )]
+    InvalidBoundaries {
+        orientation: SMM2CourseArea_Orientation,
+        left: u32,
+        right: u32,
+        top: u32,
+        bottom: u32,
+    },
+    #[error("{table:?} count {count} exceeds the maximum of {max}")]
+    EntityLimitExceeded {
+        table: EntityTable2,
+        count: u32,
+        max: usize,
+    },
+    #[error("{table:?} count {count} does not match the {used} used entries")]
+    UnusedEntries {
+        table: EntityTable2,
+        count: u32,
+        used: usize,
+    },
+    #[error("object {index} has unknown type {id}")]
+    UnknownObject { index: usize, id: i16 },
+    #[error("object {index} of type {object:?} is not available in game style {game_style:?}")]
+    ObjectNotAvailable {
+        index: usize,
+        object: ObjectId2,
+        game_style: SMM2CourseHeader_GameStyle,
+    },
+    #[error("object {index} at ({x}, {y}) is outside of the area boundaries")]
+    ObjectOutOfBounds { index: usize, x: i32, y: i32 },
+}
+
+pub type Diagnostic2 = Diagnostic<Lint2>;
+
+impl Course2 {
+    /// Validate this course.
+    ///
+    /// An empty list means that no problems have been found.
+    /// Problems are sorted by the order in which they have been checked.
+    ///
+    /// This fails, if the course has no decrypted course data.
+    pub fn validate(&self) -> Result<Vec<Diagnostic2>> {
+        if !self.has_course_data() {
+            return Err(Smm2Error::CourseDataRequired.into());
+        }
+        let data = self.get_course_data();
+        let mut diagnostics = vec![];
+
+        self.validate_text(&mut diagnostics);
+        let game_style = Course2::validate_header(data, &mut diagnostics);
+        let main_area = Course2::get_course_area(data, AreaType::Main.index());
+        for area in AreaType::ALL {
+            Course2::validate_entity_tables(data, area, &mut diagnostics);
+            match Course2::get_course_area(data, area.index()) {
+                Ok(settings) => {
+                    let settings = settings.unwrap();
+                    Course2::validate_boundaries(area, &settings, &mut diagnostics);
+                    let entities = Area2::from_course_data(data, area);
+                    Course2::validate_objects(
+                        area,
+                        &settings,
+                        &entities,
+                        game_style,
+                        &mut diagnostics,
+                    );
+                }
+                Err(err) => diagnostics.push(Diagnostic::error(
+                    Some(area),
+                    Lint2::Unparsable {
+                        reason: err.to_string(),
+                    },
+                )),
+            }
+        }
+        if let Ok(main_area) = main_area {
+            Course2::validate_positions(data, &main_area.unwrap(), &mut diagnostics);
+        }
+
+        Ok(diagnostics)
+    }
+
+    /// Title and description can not exceed their length in course data,
+    /// so lengths are checked on the course header instead.
+    fn validate_text(&self, diagnostics: &mut Vec<Diagnostic2>) {
+        let data = self.get_course_data();
+        for (field, bytes) in [
+            ("title", &data[TITLE_OFFSET..TITLE_OFFSET_END]),
+            (
+                "description",
+                &data[DESCRIPTION_OFFSET..DESCRIPTION_OFFSET_END],
+            ),
+        ] {
+            if decode_utf16(bytes).is_none() {
+                diagnostics.push(Diagnostic::error(
+                    None,
+                    Lint2::InvalidText {
+                        field: field.to_string(),
+                    },
+                ));
+            }
+        }
+        if data[TITLE_OFFSET..TITLE_OFFSET + 2] == [0, 0] {
+            diagnostics.push(Diagnostic::error(None, Lint2::TitleEmpty));
+        }
+
+        let header = self.get_course().get_header();
+        let length = header.title.encode_utf16().count();
+        if length > TITLE_MAX_LENGTH {
+            diagnostics.push(Diagnostic::error(
+                None,
+                Lint2::TitleTooLong {
+                    length,
+                    max: TITLE_MAX_LENGTH,
+                },
+            ));
+        }
+        let length = header.description.encode_utf16().count();
+        if length > DESCRIPTION_MAX_LENGTH {
+            diagnostics.push(Diagnostic::error(
+                None,
+                Lint2::DescriptionTooLong {
+                    length,
+                    max: DESCRIPTION_MAX_LENGTH,
+                },
+            ));
+        }
+    }
+
+    /// Returns the game style, if the header could be parsed.
+    fn validate_header(
+        data: &[u8],
+        diagnostics: &mut Vec<Diagnostic2>,
+    ) -> Option<SMM2CourseHeader_GameStyle> {
+        if diagnostics
+            .iter()
+            .any(|diagnostic| matches!(diagnostic.kind, Lint2::InvalidText { .. }))
+        {
+            // parsing the header would fail on invalid text
+            return None;
+        }
+        match Course2::get_course_header(data) {
+            Ok(header) => {
+                let header = header.unwrap();
+                if !TIME_RANGE.contains(&header.time) {
+                    diagnostics.push(Diagnostic::error(
+                        None,
+                        Lint2::TimeOutOfRange {
+                            time: header.time,
+                            min: *TIME_RANGE.start(),
+                            max: *TIME_RANGE.end(),
+                        },
+                    ));
+                }
+                Some(header.game_style)
+            }
+            Err(err) => {
+                diagnostics.push(Diagnostic::error(
+                    None,
+                    Lint2::Unparsable {
+                        reason: err.to_string(),
+                    },
+                ));
+                None
+            }
+        }
+    }
+
+    fn validate_positions(
+        data: &[u8],
+        main_area: &SMM2CourseArea,
+        diagnostics: &mut Vec<Diagnostic2>,
+    ) {
+        let height = main_area.top_boundary / BOUNDARY_TILE_SIZE;
+        let width = main_area.right_boundary / BOUNDARY_TILE_SIZE;
+        let start_y = data[START_Y_OFFSET] as u32;
+        let finish_y = data[FINISH_Y_OFFSET] as u32;
+        let finish_x = u16::from_le_bytes(*array_ref!(data, FINISH_X_OFFSET, 2)) as u32;
+
+        if start_y >= height {
+            diagnostics.push(Diagnostic::error(
+                Some(AreaType::Main),
+                Lint2::StartOutOfBounds { start_y },
+            ));
+        }
+        if finish_y >= height || finish_x >= width * 10 {
+            diagnostics.push(Diagnostic::error(
+                Some(AreaType::Main),
+                Lint2::FinishOutOfBounds { finish_x, finish_y },
+            ));
+        } else if finish_x + FINISH_X_MARGIN != width * 10 {
+            diagnostics.push(Diagnostic::warning(
+                Some(AreaType::Main),
+                Lint2::FinishMisplaced {
+                    finish_x,
+                    expected: (width * 10).saturating_sub(FINISH_X_MARGIN),
+                },
+            ));
+        }
+    }
+
+    fn validate_boundaries(
+        area: AreaType,
+        settings: &SMM2CourseArea,
+        diagnostics: &mut Vec<Diagnostic2>,
+    ) {
+        let orientation = settings.orientation;
+        if area == AreaType::Main && orientation == SMM2CourseArea_Orientation::VERTICAL {
+            diagnostics.push(Diagnostic::error(Some(area), Lint2::VerticalMainArea));
+        }
+        let valid = settings.left_boundary == 0
+            && settings.bottom_boundary == 0
+            && match orientation {
+                SMM2CourseArea_Orientation::HORIZONTAL => {
+                    settings.top_boundary == HORIZONTAL_HEIGHT
+                        && HORIZONTAL_WIDTH.contains(&settings.right_boundary)
+                }
+                SMM2CourseArea_Orientation::VERTICAL => {
+                    settings.right_boundary == VERTICAL_WIDTH
+                        && VERTICAL_HEIGHT.contains(&settings.top_boundary)
+                }
+            };
+        if !valid {
+            diagnostics.push(Diagnostic::error(
+                Some(area),
+                Lint2::InvalidBoundaries {
+                    orientation,
+                    left: settings.left_boundary,
+                    right: settings.right_boundary,
+                    top: settings.top_boundary,
+                    bottom: settings.bottom_boundary,
+                },
+            ));
+        }
+    }
+
+    fn validate_entity_tables(data: &[u8], area: AreaType, diagnostics: &mut Vec<Diagnostic2>) {
+        for table in EntityTable2::ALL {
+            let count = table.read_count(data, area);
+            if count as usize > table.max() {
+                diagnostics.push(Diagnostic::error(
+                    Some(area),
+                    Lint2::EntityLimitExceeded {
+                        table,
+                        count,
+                        max: table.max(),
+                    },
+                ));
+                continue;
+            }
+            if table.is_indexed() {
+                continue;
+            }
+            let offset = table.offset(area);
+            let size = table.entry_size();
+            let used = data[offset..offset + table.max() * size]
+                .chunks_exact(size)
+                .rposition(|entry| entry.iter().any(|byte| *byte != 0))
+                .map_or(0, |index| index + 1);
+            if used > count as usize {
+                diagnostics.push(Diagnostic::warning(
+                    Some(area),
+                    Lint2::UnusedEntries { table, count, used },
+                ));
+            }
+        }
+    }
+
+    fn validate_objects(
+        area: AreaType,
+        settings: &SMM2CourseArea,
+        entities: &Area2,
+        game_style: Option<SMM2CourseHeader_GameStyle>,
+        diagnostics: &mut Vec<Diagnostic2>,
+    ) {
+        // boundaries are not trusted, invalid ones are reported by `validate_boundaries`
+        let tile_size = (OBJECT_TILE_SIZE / BOUNDARY_TILE_SIZE as i32) as i64;
+        let width = settings.right_boundary as i64 * tile_size;
+        let height = settings.top_boundary as i64 * tile_size;
+        for (index, object) in entities.objects.iter().enumerate() {
+            match (object.object_id(), game_style) {
+                (None, _) => diagnostics.push(Diagnostic::error(
+                    Some(area),
+                    Lint2::UnknownObject {
+                        index,
+                        id: object.id,
+                    },
+                )),
+                (Some(object), Some(game_style)) if !object.is_available(game_style) => diagnostics
+                    .push(Diagnostic::error(
+                        Some(area),
+                        Lint2::ObjectNotAvailable {
+                            index,
+                            object,
+                            game_style,
+                        },
+                    )),
+                _ => {}
+            }
+            if !(0..=width).contains(&(object.x as i64))
+                || !(0..=height).contains(&(object.y as i64))
+            {
+                diagnostics.push(Diagnostic::warning(
+                    Some(area),
+                    Lint2::ObjectOutOfBounds {
+                        index,
+                        x: object.x,
+                        y: object.y,
+                    },
+                ));
+            }
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Course2 {
+    #[cfg(target_arch = "wasm32")]
+    #[cfg(feature = "with-serde")]
+    #[wasm_bindgen(js_name = validate)]
+    pub fn validate_js(&self) -> JsResult<JsValue> {
+        Ok(serde_wasm_bindgen::to_value(&self.validate()?)?)
+    }
+}
+
+/// Decode a zero terminated UTF-16 string.
+///
+/// Returns `None` for invalid UTF-16.
+fn decode_utf16(bytes: &[u8]) -> Option<String> {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .take_while(|unit| *unit != 0)
+        .collect();
+    String::from_utf16(&units).ok()
+}
@@ -0,0 +1,96 @@
+extern crate smmdb;
+
+use smmdb::{anonymize::*, course::*, course2::*};
+use std::fs::read;
+
+fn get_course2() -> Course2 {
+    let mut course_data = read("tests/assets/saves/smm2/save1/course_data_120.bcd").unwrap();
+    Course2::from_switch_files(&mut course_data, None, true).unwrap()
+}
+
+fn reload(course: &Course2) -> Course2 {
+    let mut course_data = course.get_course_data().to_vec();
+    Course2::encrypt(&mut course_data);
+    Course2::from_switch_files(&mut course_data, None, true).unwrap()
+}
+
+#[test]
+fn course2_anonymize() {
+    let mut course = get_course2();
+    course
+        .set_smmdb_id("5d4b2d2fdd1ce6a8e4b5f1a0".to_string())
+        .unwrap();
+
+    let (anonymized, changes) = course.anonymize(AnonymizeOptions::default()).unwrap();
+
+    assert_eq!(
+        changes,
+        vec![
+            AnonymizedField::UploadId,
+            AnonymizedField::CreationId,
+            AnonymizedField::ClearCheck,
+            AnonymizedField::SmmdbId,
+        ]
+    );
+    let header = anonymized.get_course().get_header();
+    assert_eq!(header.upload_id, 0);
+    assert_eq!(header.creation_id, 0);
+    assert_eq!(header.clear_check_tries, 0);
+    assert!(!anonymized.is_cleared());
+    assert_eq!(anonymized.get_smmdb_id(), None);
+    assert_eq!(header.modified, course.get_course().get_header().modified);
+    assert_eq!(reload(&anonymized).get_course(), anonymized.get_course());
+    assert_ne!(course.get_course().get_header().upload_id, 0);
+}
+
+#[test]
+fn course2_anonymize_modified() {
+    let course = get_course2();
+
+    let (anonymized, changes) = course
+        .anonymize(AnonymizeOptions::new(Some(1_600_000_042)))
+        .unwrap();
+
+    assert!(changes.contains(&AnonymizedField::Modified));
+    assert_eq!(anonymized.get_course().get_header().modified, 1_600_000_020);
+    assert_eq!(reload(&anonymized).get_course(), anonymized.get_course());
+
+    let (_, changes) = anonymized
+        .anonymize(AnonymizeOptions::new(Some(1_600_000_020)))
+        .unwrap();
+    assert!(changes.is_empty());
+}
+
+#[test]
+fn course2_anonymize_invalid_modified() {
+    let course = get_course2();
+
+    assert!(course
+        .anonymize(AnonymizeOptions::new(Some(u64::MAX)))
+        .is_err());
+}
+
+#[test]
+fn course2_anonymize_without_course_data() {
+    let course = Course2::from_proto(&get_course2().get_proto(), None).unwrap();
+
+    let (anonymized, changes) = course.anonymize(AnonymizeOptions::default()).unwrap();
+
+    assert!(!changes.contains(&AnonymizedField::SmmdbId));
+    assert_eq!(anonymized.get_course().get_header().upload_id, 0);
+}
+
+#[test]
+fn course_anonymize() {
+    let course = Course::from_proto(include_bytes!("assets/courses/course001/course"));
+
+    let (anonymized, changes) = course.anonymize(AnonymizeOptions::new(Some(0)));
+
+    assert_eq!(
+        changes,
+        vec![AnonymizedField::Maker, AnonymizedField::Modified]
+    );
+    assert_eq!(anonymized.get_course().maker, "");
+    assert_eq!(anonymized.get_course().modified, 0);
+    assert_eq!(anonymized.get_course().tiles, course.get_course().tiles);
+}
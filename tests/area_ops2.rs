@@ -0,0 +1,166 @@
+extern crate smmdb;
+
+use smmdb::{area2::*, constants2::*, course2::*, errors::Smm2Error, Error};
+use std::fs::read;
+
+fn get_course2(index: u8) -> Course2 {
+    let mut course_data = read(format!(
+        "tests/assets/saves/smm2/save1/course_data_{}.bcd",
+        index
+    ))
+    .unwrap();
+    Course2::from_switch_files(&mut course_data, None, true).unwrap()
+}
+
+fn reload(course: &Course2) -> Course2 {
+    let mut course_data = course.get_course_data().to_vec();
+    Course2::from_switch_files(&mut course_data, None, false).unwrap()
+}
+
+fn pipe_links(course: &Course2, area: AreaType) -> Vec<u32> {
+    course
+        .get_area(area)
+        .unwrap()
+        .objects
+        .iter()
+        .filter(|object| object.object_id() == Some(ObjectId2::Pipe))
+        .map(|object| (object.flags >> 20) & 0xF)
+        .collect()
+}
+
+#[test]
+fn area_ops2_swap_areas() {
+    let course = get_course2(121);
+    let mut swapped = course.clone();
+
+    swapped.swap_areas().unwrap();
+
+    assert_eq!(
+        swapped.get_area(AreaType::Main).unwrap(),
+        course.get_area(AreaType::Sub).unwrap()
+    );
+    assert_eq!(
+        swapped.get_area(AreaType::Sub).unwrap(),
+        course.get_area(AreaType::Main).unwrap()
+    );
+    assert_eq!(
+        swapped.get_course().get_course_area(),
+        course.get_course().get_course_sub_area()
+    );
+    assert_eq!(reload(&swapped).get_course(), swapped.get_course());
+
+    swapped.swap_areas().unwrap();
+    assert_eq!(swapped.get_course_data(), course.get_course_data());
+    assert_eq!(swapped.get_course(), course.get_course());
+}
+
+#[test]
+fn area_ops2_clear_area() {
+    let mut course = get_course2(121);
+    let settings = course.get_course().get_course_sub_area().clone();
+    assert_eq!(pipe_links(&course, AreaType::Main), vec![1, 2]);
+
+    course.clear_area(AreaType::Sub).unwrap();
+
+    assert_eq!(course.get_area(AreaType::Sub).unwrap(), Area2::default());
+    let cleared = course.get_course().get_course_sub_area();
+    assert_eq!(cleared.object_count, 0);
+    assert_eq!(cleared.tile_count, 0);
+    assert_eq!(cleared.course_theme, settings.course_theme);
+    assert_eq!(cleared.right_boundary, settings.right_boundary);
+    assert_eq!(pipe_links(&course, AreaType::Main), vec![0, 0]);
+    assert_eq!(reload(&course).get_course(), course.get_course());
+}
+
+#[test]
+fn area_ops2_copy_area_from() {
+    let source = get_course2(130);
+    let mut course = get_course2(121);
+
+    course
+        .copy_area_from(&source, AreaType::Main, AreaType::Sub)
+        .unwrap();
+
+    let copied = course.get_area(AreaType::Sub).unwrap();
+    let objects = source.get_area(AreaType::Main).unwrap().objects;
+    assert_eq!(copied.objects.len(), objects.len());
+    assert_eq!(
+        copied.ground,
+        source.get_area(AreaType::Main).unwrap().ground
+    );
+    assert_eq!(
+        course.get_course().get_course_sub_area(),
+        source.get_course().get_course_area()
+    );
+    assert_eq!(pipe_links(&course, AreaType::Main), vec![0, 0]);
+    assert_eq!(pipe_links(&course, AreaType::Sub), vec![0, 0]);
+    assert_eq!(reload(&course).get_course(), course.get_course());
+}
+
+#[test]
+fn area_ops2_copy_area_renumbers_pipes() {
+    let mut source = get_course2(121);
+    let objects = source.get_area(AreaType::Main).unwrap().objects;
+    for (index, object) in objects.iter().enumerate() {
+        if object.object_id() == Some(ObjectId2::Pipe) {
+            let offset = OBJECTS_OFFSET[0] + index * OBJECT_SIZE + OBJECT_FLAGS_OFFSET;
+            let flags = (object.flags & !(0xF << 20)) | (1 << 20);
+            source.get_course_data_mut()[offset..offset + 4].copy_from_slice(&flags.to_le_bytes());
+        }
+    }
+    let source = reload(&source);
+    let mut course = get_course2(121);
+
+    course
+        .copy_area_from(&source, AreaType::Main, AreaType::Sub)
+        .unwrap();
+
+    assert_eq!(pipe_links(&course, AreaType::Main), vec![0, 0]);
+    assert_eq!(pipe_links(&course, AreaType::Sub), vec![3, 3]);
+    assert_eq!(reload(&course).get_course(), course.get_course());
+}
+
+#[test]
+fn area_ops2_vertical_main_area() {
+    let source = get_course2(122);
+    let mut course = get_course2(122);
+
+    assert!(matches!(
+        course.swap_areas(),
+        Err(Error::Smm2Error(Smm2Error::VerticalMainArea))
+    ));
+    assert!(matches!(
+        course.copy_area_from(&source, AreaType::Sub, AreaType::Main),
+        Err(Error::Smm2Error(Smm2Error::VerticalMainArea))
+    ));
+    assert_eq!(course.get_course_data(), source.get_course_data());
+}
+
+#[test]
+fn area_ops2_copy_area_unlinks_pipes() {
+    let source = get_course2(121);
+    let mut course = get_course2(133);
+
+    course
+        .copy_area_from(&source, AreaType::Sub, AreaType::Sub)
+        .unwrap();
+
+    assert!(pipe_links(&course, AreaType::Main)
+        .iter()
+        .all(|link| *link == 0));
+    assert!(pipe_links(&course, AreaType::Sub)
+        .iter()
+        .all(|link| *link == 0));
+}
+
+#[test]
+fn area_ops2_main_area_too_small() {
+    let source = get_course2(121);
+    let mut course = get_course2(133);
+
+    assert!(matches!(
+        course.copy_area_from(&source, AreaType::Main, AreaType::Main),
+        Err(Error::Smm2Error(Smm2Error::StartGoalOutOfBounds))
+    ));
+    assert_eq!(course.get_course_data(), get_course2(133).get_course_data());
+}
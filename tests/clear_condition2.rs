@@ -0,0 +1,117 @@
+extern crate smmdb;
+
+use smmdb::{clear_condition2::*, course2::*, proto::SMM2Course::*};
+use std::{collections::HashSet, fs::read};
+
+fn get_course2(index: u8) -> Course2 {
+    let mut course_data = read(format!(
+        "tests/assets/saves/smm2/save1/course_data_{}.bcd",
+        index
+    ))
+    .unwrap();
+    Course2::from_switch_files(&mut course_data, None, true).unwrap()
+}
+
+fn reload(course: &Course2) -> Course2 {
+    let mut course_data = course.get_course_data().to_vec();
+    Course2::encrypt(&mut course_data);
+    Course2::from_switch_files(&mut course_data, None, true).unwrap()
+}
+
+#[test]
+fn clear_condition2_decode() {
+    let condition = get_course2(136).get_clear_condition().unwrap();
+    assert_eq!(condition.condition, ClearCondition2::GrabCoin);
+    assert_eq!(condition.amount, 8);
+    assert_eq!(condition.describe(), "Grab 8 Coins");
+
+    let condition = get_course2(133).get_clear_condition().unwrap();
+    assert_eq!(condition.condition, ClearCondition2::SpinyMario);
+    assert_eq!(condition.describe(), "Reach the goal as Spiny Mario");
+
+    assert_eq!(get_course2(120).get_clear_condition(), None);
+}
+
+#[test]
+fn clear_condition2_shared_hash() {
+    let hash = ClearCondition2::HitPSwitch.hash();
+    assert_eq!(hash, ClearCondition2::HoldPSwitch.hash());
+
+    assert_eq!(
+        ClearCondition2::from_hash(hash, SMM2CourseHeader_ClearConditionType::PARTS),
+        Some(ClearCondition2::HitPSwitch)
+    );
+    assert_eq!(
+        ClearCondition2::from_hash(hash, SMM2CourseHeader_ClearConditionType::ACTION),
+        Some(ClearCondition2::HoldPSwitch)
+    );
+    assert_eq!(
+        ClearCondition2::from_hash(0, SMM2CourseHeader_ClearConditionType::PARTS),
+        None
+    );
+}
+
+#[test]
+fn clear_condition2_table() {
+    let mut keys = HashSet::new();
+    for condition in ClearCondition2::ALL {
+        assert!(keys.insert((condition.hash(), condition.condition_type())));
+        assert_eq!(
+            ClearCondition2::from_hash(condition.hash(), condition.condition_type()),
+            Some(*condition)
+        );
+        assert_eq!(condition.has_amount(), condition.template().contains("{n}"));
+    }
+}
+
+#[test]
+fn clear_condition2_set() {
+    let mut course = get_course2(120);
+
+    course
+        .set_clear_condition(Some(ClearCondition2::DefeatGoomba), 12)
+        .unwrap();
+
+    let course = reload(&course);
+    let header = course.get_course().get_header();
+    assert_eq!(
+        header.get_clear_condition_type(),
+        SMM2CourseHeader_ClearConditionType::PARTS
+    );
+    assert_eq!(header.clear_condition, ClearCondition2::DefeatGoomba.hash());
+    assert_eq!(
+        course.get_clear_condition().unwrap().describe(),
+        "Defeat 12 Goombas/Galoombas"
+    );
+}
+
+#[test]
+fn clear_condition2_set_without_amount() {
+    let mut course = get_course2(136);
+
+    course
+        .set_clear_condition(Some(ClearCondition2::HoldPSwitch), 5)
+        .unwrap();
+    let reloaded = reload(&course);
+    let condition = reloaded.get_clear_condition().unwrap();
+    assert_eq!(condition.condition, ClearCondition2::HoldPSwitch);
+    assert_eq!(condition.amount, 0);
+
+    course.set_clear_condition(None, 0).unwrap();
+    let reloaded = reload(&course);
+    assert_eq!(reloaded.get_clear_condition(), None);
+    assert_eq!(reloaded.get_course().get_header().clear_condition, 0);
+}
+
+#[test]
+fn clear_condition2_set_invalid_amount() {
+    let mut course = get_course2(120);
+
+    assert!(course
+        .set_clear_condition(Some(ClearCondition2::GrabCoin), 0)
+        .is_err());
+    assert!(course
+        .set_clear_condition(Some(ClearCondition2::GrabCoin), 70000)
+        .is_err());
+    assert_eq!(course.get_clear_condition(), None);
+}
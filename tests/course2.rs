@@ -2,7 +2,7 @@ extern crate smmdb;
 
 #[cfg(not(target_arch = "wasm32"))]
 use smmdb::constants2::*;
-use smmdb::{course2::*, errors::Smm2Error, Error};
+use smmdb::{area2::*, course2::*, errors::Smm2Error, Error};
 use std::{
     collections::HashSet,
     fs::{read, read_dir},
@@ -319,3 +319,156 @@ fn course2_reset_clear_check() {
         new_course.get_course().get_header()
     );
 }
+
+#[test]
+fn course2_get_area() {
+    let mut course_data = read("tests/assets/saves/smm2/save1/course_data_120.bcd").unwrap();
+    let course = Course2::from_switch_files(&mut course_data, None, true).unwrap();
+
+    for area_type in AreaType::ALL {
+        let area = course.get_area(area_type).unwrap();
+        let settings = match area_type {
+            AreaType::Main => course.get_course().get_course_area(),
+            AreaType::Sub => course.get_course().get_course_sub_area(),
+        };
+        assert_eq!(area.objects.len() as u32, settings.object_count);
+        assert_eq!(area.ground.len() as u32, settings.tile_count);
+        assert_eq!(area.tracks.len() as u32, settings.track_count);
+        assert_eq!(area.sound_effects.len() as u32, settings.sound_effect_count);
+        assert!(area.objects.iter().all(|object| object.width > 0));
+    }
+}
+
+#[test]
+fn course2_get_area_requires_course_data() {
+    let mut course_data = read("tests/assets/saves/smm2/save1/course_data_120.bcd").unwrap();
+    let course = Course2::from_switch_files(&mut course_data, None, true).unwrap();
+    let course = Course2::from_proto(&course.get_proto(), None).unwrap();
+
+    assert!(course.get_area(AreaType::Main).is_err());
+}
+
+#[test]
+#[cfg(not(target_arch = "wasm32"))]
+fn course2_recount() {
+    let mut course_data = read("tests/assets/saves/smm2/save1/course_data_120.bcd").unwrap();
+    let mut course = Course2::from_switch_files(&mut course_data, None, true).unwrap();
+    let count = course.get_course().get_course_area().object_count as usize;
+
+    let table = EntityTable2::Objects;
+    let offset = table.offset(AreaType::Main);
+    let data = course.get_course_data_mut();
+    data.copy_within(offset..offset + OBJECT_SIZE, offset + count * OBJECT_SIZE);
+    course.recount().unwrap();
+
+    let area = course.get_area(AreaType::Main).unwrap();
+    assert_eq!(area.objects.len(), count + 1);
+    assert_eq!(area.objects[count], area.objects[0]);
+    assert_eq!(
+        course.get_course().get_course_area().object_count as usize,
+        count + 1
+    );
+    let reloaded = Course2::from_switch_files(course.get_course_data_mut(), None, false).unwrap();
+    assert_eq!(reloaded.get_course(), course.get_course());
+}
+
+#[test]
+#[cfg(not(target_arch = "wasm32"))]
+fn course2_recount_on_encrypt() {
+    let mut course_data = read("tests/assets/saves/smm2/save1/course_data_120.bcd").unwrap();
+    let course = Course2::from_switch_files(&mut course_data, None, true).unwrap();
+    let settings = course.get_course().get_course_area();
+
+    let mut course_data = course.get_course_data().to_vec();
+    let offset = EntityTable2::Ground.offset(AreaType::Main);
+    let last = settings.tile_count as usize - 1;
+    course_data[offset + last * GROUND_SIZE..offset + (last + 1) * GROUND_SIZE].fill(0);
+
+    let mut encrypted = course_data.clone();
+    Course2::encrypt(&mut encrypted);
+    let reencrypted = Course2::from_switch_files(&mut encrypted, None, true).unwrap();
+    let reencrypted_settings = reencrypted.get_course().get_course_area();
+    assert_eq!(reencrypted_settings.tile_count, settings.tile_count);
+
+    Course2::encrypt_recounted(&mut course_data);
+    let reencrypted = Course2::from_switch_files(&mut course_data, None, true).unwrap();
+    let reencrypted_settings = reencrypted.get_course().get_course_area();
+    assert_eq!(reencrypted_settings.tile_count, settings.tile_count - 1);
+    assert_eq!(reencrypted_settings.object_count, settings.object_count);
+}
+
+#[test]
+#[cfg(not(target_arch = "wasm32"))]
+fn course2_indexed_table_with_gap() {
+    let mut course_data = read("tests/assets/saves/smm2/save1/course_data_167.bcd").unwrap();
+    let mut course = Course2::from_switch_files(&mut course_data, None, true).unwrap();
+    let clear_pipes = course.get_area(AreaType::Sub).unwrap().clear_pipes;
+    assert_eq!(clear_pipes.len(), 5);
+
+    let table = EntityTable2::ClearPipes;
+    let offset = table.offset(AreaType::Sub);
+    let last = offset + (table.max() - 1) * CLEAR_PIPE_SIZE;
+    let data = course.get_course_data_mut();
+    data.copy_within(offset..offset + CLEAR_PIPE_SIZE, last);
+    data[offset..offset + CLEAR_PIPE_SIZE].fill(0);
+
+    let area = course.get_area(AreaType::Sub).unwrap();
+    assert_eq!(area.clear_pipes.len(), 5);
+    assert_eq!(area.clear_pipes[..4], clear_pipes[1..]);
+    assert_eq!(area.clear_pipes[4], clear_pipes[0]);
+    assert_eq!(
+        table.used_entries(course.get_course_data(), AreaType::Sub),
+        vec![1, 2, 3, 4, table.max() - 1]
+    );
+    assert_eq!(
+        table.count_entries(course.get_course_data(), AreaType::Sub),
+        5
+    );
+
+    let expected = course.clone();
+    course.recount().unwrap();
+    assert_eq!(course, expected);
+}
+
+#[test]
+fn course2_recount_unchanged() {
+    for index in 120..180 {
+        let mut course_data = read(format!(
+            "tests/assets/saves/smm2/save1/course_data_{}.bcd",
+            index
+        ))
+        .unwrap();
+        let mut course = Course2::from_switch_files(&mut course_data, None, true).unwrap();
+        let expected = course.clone();
+
+        course.recount().unwrap();
+
+        assert_eq!(course, expected);
+    }
+}
+
+#[test]
+fn course2_count_entries_matches_stored_counts() {
+    for index in 120..180 {
+        let mut course_data = read(format!(
+            "tests/assets/saves/smm2/save1/course_data_{}.bcd",
+            index
+        ))
+        .unwrap();
+        let course = Course2::from_switch_files(&mut course_data, None, true).unwrap();
+        let course_data = course.get_course_data();
+
+        for area in AreaType::ALL {
+            for table in EntityTable2::ALL {
+                assert_eq!(
+                    table.count_entries(course_data, area),
+                    table.read_count(course_data, area),
+                    "{} {:?} {:?}",
+                    index,
+                    area,
+                    table
+                );
+            }
+        }
+    }
+}
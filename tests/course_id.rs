@@ -0,0 +1,69 @@
+extern crate smmdb;
+
+use smmdb::{course2::*, course_id::*};
+use std::fs::read;
+
+fn get_upload_id(index: u8) -> u64 {
+    let mut course_data = read(format!(
+        "tests/assets/saves/smm2/save1/course_data_{}.bcd",
+        index
+    ))
+    .unwrap();
+    let course = Course2::from_switch_files(&mut course_data, None, true).unwrap();
+    course.get_course().get_header().upload_id
+}
+
+#[test]
+fn course_code2_from_upload_id() {
+    let code = CourseCode2::from_upload_id(get_upload_id(120)).unwrap();
+
+    assert_eq!(code.get_data_id(), 3300600);
+    assert_eq!(code.get_code_type(), CodeType2::Course);
+    assert_eq!(code.to_string(), "GPJ-RJ1-52G");
+    assert_eq!("GPJ-RJ1-52G".parse::<CourseCode2>().unwrap(), code);
+    assert_eq!("gpjrj152g".parse::<CourseCode2>().unwrap(), code);
+}
+
+#[test]
+fn course_code2_fixtures() {
+    for index in 120..180 {
+        let upload_id = get_upload_id(index);
+        let code = CourseCode2::from_upload_id(upload_id).unwrap().to_string();
+
+        assert!(
+            code.ends_with(['F', 'G', 'H']),
+            "{}: {} {}",
+            index,
+            upload_id,
+            code
+        );
+        let parsed: CourseCode2 = code.parse().unwrap();
+        assert_eq!(parsed.get_data_id() as u64, upload_id);
+    }
+}
+
+#[test]
+fn course_code2_maker() {
+    let code = CourseCode2::new(3300600, CodeType2::Maker);
+
+    assert_eq!(code.to_string(), "JG8-M1T-J2G");
+    assert_eq!("JG8-M1T-J2G".parse::<CourseCode2>().unwrap(), code);
+    assert_ne!(code, CourseCode2::new(3300600, CodeType2::Course));
+}
+
+#[test]
+fn course_code2_invalid() {
+    for code in [
+        "GPJ-RJ1-52",
+        "GPJ-RJ1-52GG",
+        "GPA-RJ1-52G",
+        "GPJ-RJ1-52O",
+        // checksum does not match
+        "GPJ-RK1-52G",
+        // prefix is not 8
+        "GPJ-RJ1-520",
+    ] {
+        assert!(code.parse::<CourseCode2>().is_err(), "{}", code);
+    }
+    assert!(CourseCode2::from_upload_id(1 << 32).is_err());
+}
@@ -0,0 +1,83 @@
+extern crate smmdb;
+
+use smmdb::{area2::AreaType, constants::*, course::*};
+
+static COURSE_DATA: &[u8] = include_bytes!("assets/courses/course001/course_data.cdt");
+static COURSE_DATA_SUB: &[u8] = include_bytes!("assets/courses/course001/course_data_sub.cdt");
+static THUMBNAIL_0: &[u8] = include_bytes!("assets/courses/course001/thumbnail0.tnl");
+static THUMBNAIL_1: &[u8] = include_bytes!("assets/courses/course001/thumbnail1.tnl");
+
+fn get_course(course_data: &[u8], course_data_sub: &[u8]) -> Course {
+    Course::from_wii_u_files(course_data, course_data_sub, THUMBNAIL_0, THUMBNAIL_1).unwrap()
+}
+
+#[test]
+fn diff_identical() {
+    let course = get_course(COURSE_DATA, COURSE_DATA_SUB);
+
+    let diff = course.diff(&get_course(COURSE_DATA, COURSE_DATA_SUB));
+
+    assert!(diff.is_empty());
+    assert_eq!(diff.areas.len(), 2);
+}
+
+#[test]
+fn diff_objects_and_sounds() {
+    let course = get_course(COURSE_DATA, COURSE_DATA_SUB);
+    let objects = course.get_objects(AreaType::Main);
+
+    // move last tile of sub area one tile to the right
+    let mut course_data_sub = COURSE_DATA_SUB.to_vec();
+    let tile_amount = u16::from_be_bytes([
+        course_data_sub[TILE_AMOUNT_OFFSET],
+        course_data_sub[TILE_AMOUNT_OFFSET + 1],
+    ]) as usize;
+    let offset = TILES_OFFSET + (tile_amount - 1) * TILE_SIZE;
+    let x = u32::from_be_bytes([
+        course_data_sub[offset],
+        course_data_sub[offset + 1],
+        course_data_sub[offset + 2],
+        course_data_sub[offset + 3],
+    ]) + 160;
+    course_data_sub[offset..offset + 4].copy_from_slice(&x.to_be_bytes());
+    // remove last tile of main area
+    let mut course_data = COURSE_DATA.to_vec();
+    let tile_amount = objects.len() as u16 - 1;
+    course_data[TILE_AMOUNT_OFFSET..TILE_AMOUNT_OFFSET + 2]
+        .copy_from_slice(&tile_amount.to_be_bytes());
+    let changed = get_course(&course_data, &course_data_sub);
+
+    let diff = course.diff(&changed);
+
+    assert!(diff.metadata.is_empty());
+    assert_eq!(diff.areas[0].area, AreaType::Main);
+    assert_eq!(
+        diff.areas[0].objects.removed,
+        vec![objects.last().unwrap().clone()]
+    );
+    assert!(diff.areas[0].objects.added.is_empty());
+    assert!(diff.areas[0].sounds.is_empty());
+    assert_eq!(diff.areas[1].objects.moved.len(), 1);
+    assert_eq!(diff.areas[1].objects.moved[0].to.x, x);
+    assert!(diff.areas[1].objects.added.is_empty());
+    assert!(diff.areas[1].objects.removed.is_empty());
+}
+
+#[test]
+fn diff_metadata() {
+    let course = get_course(COURSE_DATA, COURSE_DATA_SUB);
+    let mut changed = get_course(COURSE_DATA, COURSE_DATA_SUB);
+    changed.get_course_mut().set_title("New title".to_string());
+    changed.get_course_mut().set_time(100);
+
+    let diff = course.diff(&changed);
+
+    let fields: Vec<_> = diff
+        .metadata
+        .iter()
+        .map(|change| change.field.as_str())
+        .collect();
+    assert_eq!(fields, vec!["title", "time"]);
+    assert_eq!(diff.metadata[0].new, "New title");
+    assert_eq!(diff.metadata[1].new, "100");
+}
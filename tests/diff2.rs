@@ -0,0 +1,89 @@
+extern crate smmdb;
+
+use smmdb::{area2::*, constants2::*, course2::*, diff::*, diff2::*};
+use std::fs::read;
+
+fn get_course(index: u8) -> Course2 {
+    let mut course_data = read(format!(
+        "tests/assets/saves/smm2/save1/course_data_{}.bcd",
+        index
+    ))
+    .unwrap();
+    Course2::from_switch_files(&mut course_data, None, true).unwrap()
+}
+
+fn reload(course: &mut Course2) -> Course2 {
+    Course2::from_switch_files(course.get_course_data_mut(), None, false).unwrap()
+}
+
+#[test]
+fn diff2_identical() {
+    let course = get_course(120);
+
+    let diff = course.diff(&course.clone()).unwrap();
+
+    assert!(diff.is_empty());
+    assert_eq!(diff.areas.len(), 2);
+}
+
+#[test]
+fn diff2_header() {
+    let course = get_course(120);
+    let mut changed = course.clone();
+    changed.set_description("Hey there!".to_string()).unwrap();
+    let changed = reload(&mut changed);
+
+    let diff = course.diff(&changed).unwrap();
+
+    assert_eq!(
+        diff.header,
+        vec![FieldChange {
+            field: "description".to_string(),
+            old: course
+                .get_course()
+                .get_header()
+                .get_description()
+                .to_string(),
+            new: "Hey there!".to_string(),
+        }]
+    );
+    assert!(diff.areas.iter().all(AreaDiff2::is_empty));
+}
+
+#[test]
+fn diff2_entities() {
+    let course = get_course(120);
+    let area = course.get_area(AreaType::Main).unwrap();
+    let mut changed = course.clone();
+
+    // move first object one tile to the right
+    let offset = OBJECTS_OFFSET[0];
+    let x = area.objects[0].x + OBJECT_TILE_SIZE;
+    changed.get_course_data_mut()[offset..offset + 4].copy_from_slice(&x.to_le_bytes());
+    // remove last ground tile
+    let tile_count = area.ground.len() as u32 - 1;
+    let offset = TILE_COUNT_OFFSET[0];
+    changed.get_course_data_mut()[offset..offset + 4].copy_from_slice(&tile_count.to_le_bytes());
+    let changed = reload(&mut changed);
+
+    let diff = course.diff(&changed).unwrap();
+
+    let main = &diff.areas[0];
+    assert_eq!(main.area, AreaType::Main);
+    assert!(main.objects.added.is_empty());
+    assert!(main.objects.removed.is_empty());
+    assert_eq!(main.objects.moved.len(), 1);
+    assert_eq!(main.objects.moved[0].from, area.objects[0]);
+    assert_eq!(main.objects.moved[0].to.x, x);
+    assert!(main.ground.added.is_empty());
+    assert_eq!(
+        main.ground.removed,
+        vec![area.ground.last().unwrap().clone()]
+    );
+    assert!(diff.areas[1].is_empty());
+
+    let diff = changed.diff(&course).unwrap();
+
+    assert_eq!(diff.areas[0].ground.added.len(), 1);
+    assert_eq!(diff.areas[0].objects.moved[0].to, area.objects[0]);
+}
@@ -0,0 +1,103 @@
+extern crate smmdb;
+
+use smmdb::{constants2::*, course::*, course2::*, fingerprint::*};
+use std::fs::read;
+
+fn get_course2() -> Course2 {
+    let mut course_data = read("tests/assets/saves/smm2/save1/course_data_120.bcd").unwrap();
+    Course2::from_switch_files(&mut course_data, None, true).unwrap()
+}
+
+fn reload(course: &Course2) -> Course2 {
+    let mut course_data = course.get_course_data().to_vec();
+    Course2::encrypt(&mut course_data);
+    Course2::from_switch_files(&mut course_data, None, true).unwrap()
+}
+
+#[test]
+fn course2_fingerprint_ignores_encryption() {
+    let course = get_course2();
+    let reencrypted = reload(&course);
+
+    assert_eq!(
+        course.fingerprint(FingerprintOptions::default()).unwrap(),
+        reencrypted
+            .fingerprint(FingerprintOptions::default())
+            .unwrap()
+    );
+}
+
+#[test]
+fn course2_fingerprint_differs() {
+    let course = get_course2();
+    let other = Course2::from_switch_files(
+        &mut read("tests/assets/saves/smm2/save1/course_data_121.bcd").unwrap(),
+        None,
+        true,
+    )
+    .unwrap();
+
+    assert_ne!(
+        course.fingerprint(FingerprintOptions::default()).unwrap(),
+        other.fingerprint(FingerprintOptions::default()).unwrap()
+    );
+}
+
+#[test]
+fn course2_fingerprint_options() {
+    let course = get_course2();
+    let mut changed = course.clone();
+    changed.get_course_data_mut()[MINUTE_OFFSET] =
+        (course.get_course_data()[MINUTE_OFFSET] + 1) % 60;
+    changed.reset_clear_check();
+    changed
+        .set_smmdb_id("5f6850b100284286006b7c68".to_string())
+        .unwrap();
+    let changed = reload(&changed);
+
+    let options = FingerprintOptions::new(true, true, false);
+    assert_ne!(
+        course.fingerprint(options).unwrap(),
+        changed.fingerprint(options).unwrap()
+    );
+
+    let options = FingerprintOptions::new(true, true, true);
+    assert_eq!(
+        course.fingerprint(options).unwrap(),
+        changed.fingerprint(options).unwrap()
+    );
+    assert_ne!(
+        course.fingerprint(FingerprintOptions::default()).unwrap(),
+        changed.fingerprint(FingerprintOptions::default()).unwrap()
+    );
+}
+
+#[test]
+fn course2_fingerprint_requires_course_data() {
+    let course = get_course2();
+    let course = Course2::from_proto(&course.get_proto(), None).unwrap();
+
+    assert!(course.fingerprint(FingerprintOptions::default()).is_err());
+}
+
+#[test]
+fn course_fingerprint() {
+    let course = Course::from_proto(include_bytes!("assets/courses/course001/course"));
+    let course_packed = Course::from_packed(include_bytes!("assets/courses/course001/course.zip"))
+        .unwrap()
+        .pop()
+        .unwrap();
+
+    let options = FingerprintOptions {
+        ignore_modified: true,
+        ..FingerprintOptions::default()
+    };
+    assert_eq!(
+        course.fingerprint(options),
+        course_packed.fingerprint(options)
+    );
+    assert_ne!(
+        course.fingerprint(FingerprintOptions::default()),
+        course_packed.fingerprint(FingerprintOptions::default())
+    );
+}
@@ -0,0 +1,97 @@
+extern crate smmdb;
+
+use smmdb::{constants2::*, course2::*, flags2::*};
+use std::fs::read;
+
+fn get_course2(index: u8) -> Course2 {
+    let mut course_data = read(format!(
+        "tests/assets/saves/smm2/save1/course_data_{}.bcd",
+        index
+    ))
+    .unwrap();
+    Course2::from_switch_files(&mut course_data, None, true).unwrap()
+}
+
+fn reload(course: &Course2) -> Course2 {
+    let mut course_data = course.get_course_data().to_vec();
+    Course2::encrypt(&mut course_data);
+    Course2::from_switch_files(&mut course_data, None, true).unwrap()
+}
+
+#[test]
+fn flags2_get() {
+    let course = get_course2(121);
+
+    assert_eq!(
+        course.get_game_version(),
+        GameVersion2::V1_0_0 | GameVersion2::V1_0_1
+    );
+    assert_eq!(
+        course.get_management_flags(),
+        ManagementFlags2::SAVED
+            | ManagementFlags2::CLEARED
+            | ManagementFlags2::from_bits_retain(0x40)
+    );
+    assert!(course.is_cleared());
+    assert!(course.is_uploaded());
+}
+
+#[test]
+fn flags2_set_cleared() {
+    let mut course = get_course2(121);
+
+    course.set_cleared(false);
+
+    assert!(!course.is_cleared());
+    let reloaded = reload(&course);
+    assert_eq!(reloaded.get_course().get_header().management_flags, 0x41);
+    assert_eq!(
+        reloaded.get_course().get_header(),
+        course.get_course().get_header()
+    );
+
+    course.set_cleared(true);
+    assert_eq!(
+        reload(&course).get_course().get_header().management_flags,
+        0x43
+    );
+}
+
+#[test]
+fn flags2_set_game_version() {
+    let mut course = get_course2(137);
+
+    course.set_game_version(course.get_game_version() | GameVersion2::V3_0_0);
+
+    let reloaded = reload(&course);
+    assert_eq!(reloaded.get_course().get_header().game_version, 0x11);
+    assert!(reloaded.get_game_version().contains(GameVersion2::V3_0_0));
+}
+
+#[test]
+fn flags2_reset_clear_check_when_not_cleared() {
+    let mut course = get_course2(121);
+    course.set_cleared(false);
+
+    course.reset_clear_check();
+
+    assert_eq!(
+        course.get_management_flags(),
+        ManagementFlags2::from_bits_retain(0x41)
+    );
+    assert_eq!(
+        course.get_course_data()[MANAGEMENT_FLAGS_OFFSET..MANAGEMENT_FLAGS_OFFSET + 4],
+        0x41u32.to_le_bytes()
+    );
+}
+
+#[test]
+fn flags2_without_course_data() {
+    let course = get_course2(121);
+    let mut course = Course2::from_proto(&course.get_proto(), None).unwrap();
+
+    course.reset_clear_check();
+
+    assert!(!course.is_cleared());
+    assert_eq!(course.get_course().get_header().clear_check_tries, 0);
+}
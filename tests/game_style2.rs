@@ -0,0 +1,209 @@
+extern crate smmdb;
+
+use smmdb::{
+    area2::*, clear_condition2::*, course2::*, proto::SMM2Course::SMM2CourseHeader_GameStyle,
+};
+use std::fs::read;
+
+fn get_course2(save: u8, index: u8) -> Course2 {
+    let mut course_data = read(format!(
+        "tests/assets/saves/smm2/save{}/course_data_{}.bcd",
+        save, index
+    ))
+    .unwrap();
+    Course2::from_switch_files(&mut course_data, None, true).unwrap()
+}
+
+fn reload(course: &Course2) -> Course2 {
+    let mut course_data = course.get_course_data().to_vec();
+    Course2::from_switch_files(&mut course_data, None, false).unwrap()
+}
+
+fn count_objects(course: &Course2, id: ObjectId2) -> usize {
+    AreaType::ALL
+        .iter()
+        .map(|area| {
+            course
+                .get_area(*area)
+                .unwrap()
+                .objects
+                .iter()
+                .filter(|object| object.object_id() == Some(id))
+                .count()
+        })
+        .sum()
+}
+
+fn assert_no_unavailable_objects(course: &Course2, game_style: SMM2CourseHeader_GameStyle) {
+    for area in AreaType::ALL {
+        for object in course.get_area(area).unwrap().objects {
+            if let Some(id) = object.object_id() {
+                assert!(id.is_available(game_style), "{:?}", id);
+            }
+            if let Some(id) = ObjectId2::from_id(object.child_id) {
+                assert!(id.is_available(game_style), "{:?}", id);
+            }
+        }
+    }
+}
+
+#[test]
+fn game_style2_convert_to_3d_world() {
+    let original = get_course2(1, 172);
+    let mut course = original.clone();
+    let clown_cars = count_objects(&original, ObjectId2::ClownCar);
+    assert_ne!(clown_cars, 0);
+
+    let conversion = course
+        .convert_game_style(SMM2CourseHeader_GameStyle::W3)
+        .unwrap();
+
+    assert!(conversion.substitutions.iter().any(|substitution| {
+        substitution.object == ObjectId2::ClownCar
+            && substitution.replacement == Some(ObjectId2::KoopaCar)
+    }));
+    assert!(conversion.substitutions.iter().any(|substitution| {
+        substitution.object == ObjectId2::ShoeGoomba
+            && substitution.replacement == Some(ObjectId2::Goomba)
+    }));
+    assert_eq!(count_objects(&course, ObjectId2::ClownCar), 0);
+    assert_eq!(count_objects(&course, ObjectId2::ShoeGoomba), 0);
+    assert_no_unavailable_objects(&course, SMM2CourseHeader_GameStyle::W3);
+    assert_eq!(
+        course.get_course().get_header().get_game_style(),
+        SMM2CourseHeader_GameStyle::W3
+    );
+    assert_eq!(reload(&course).get_course(), course.get_course());
+}
+
+#[test]
+fn game_style2_convert_reports_clear_condition() {
+    let mut course = get_course2(1, 172);
+    course
+        .set_clear_condition(Some(ClearCondition2::DefeatGoomba), 3)
+        .unwrap();
+    let conversion = course
+        .clone()
+        .convert_game_style(SMM2CourseHeader_GameStyle::W3)
+        .unwrap();
+    assert_eq!(conversion.clear_condition, None);
+
+    course
+        .set_clear_condition(Some(ClearCondition2::ClownCar), 0)
+        .unwrap();
+    let conversion = course
+        .convert_game_style(SMM2CourseHeader_GameStyle::W3)
+        .unwrap();
+
+    assert_eq!(conversion.clear_condition, Some(ClearCondition2::ClownCar));
+    assert_eq!(
+        course.get_clear_condition().unwrap().condition,
+        ClearCondition2::ClownCar
+    );
+}
+
+#[test]
+fn game_style2_convert_clears_tracks() {
+    let mut course = get_course2(1, 123);
+    assert_ne!(course.get_area(AreaType::Main).unwrap().tracks.len(), 0);
+
+    let conversion = course
+        .convert_game_style(SMM2CourseHeader_GameStyle::W3)
+        .unwrap();
+
+    assert!(conversion
+        .cleared_tables
+        .contains(&(AreaType::Main, EntityTable2::Tracks)));
+    assert!(course.get_area(AreaType::Main).unwrap().tracks.is_empty());
+    assert_no_unavailable_objects(&course, SMM2CourseHeader_GameStyle::W3);
+    assert_eq!(reload(&course).get_course(), course.get_course());
+}
+
+#[test]
+fn game_style2_convert_from_3d_world() {
+    let original = get_course2(2, 149);
+    let mut course = original.clone();
+    let objects = original.get_area(AreaType::Main).unwrap().objects.len()
+        + original.get_area(AreaType::Sub).unwrap().objects.len();
+
+    let conversion = course
+        .convert_game_style(SMM2CourseHeader_GameStyle::M3)
+        .unwrap();
+
+    assert!(conversion.substitutions.iter().any(|substitution| {
+        substitution.object == ObjectId2::KoopaCar
+            && substitution.replacement == Some(ObjectId2::ClownCar)
+    }));
+    assert!(conversion.substitutions.iter().any(|substitution| {
+        substitution.object == ObjectId2::PiranhaCreeper && substitution.replacement.is_none()
+    }));
+    let removed = conversion
+        .substitutions
+        .iter()
+        .filter(|substitution| !substitution.child && substitution.replacement.is_none())
+        .count();
+    assert_eq!(
+        course.get_area(AreaType::Main).unwrap().objects.len()
+            + course.get_area(AreaType::Sub).unwrap().objects.len(),
+        objects - removed
+    );
+    for area in AreaType::ALL {
+        let original_objects = original.get_area(area).unwrap().objects;
+        let objects = course.get_area(area).unwrap().objects;
+        let substitutions: Vec<_> = conversion
+            .substitutions
+            .iter()
+            .filter(|substitution| substitution.area == area)
+            .collect();
+        let kept: Vec<_> = original_objects
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| {
+                !substitutions.iter().any(|substitution| {
+                    substitution.index == *index && substitution.new_index.is_none()
+                })
+            })
+            .map(|(_, object)| (object.x, object.y, object.flags))
+            .collect();
+        let positions: Vec<_> = objects
+            .iter()
+            .map(|object| (object.x, object.y, object.flags))
+            .collect();
+        assert_eq!(positions, kept);
+        for substitution in substitutions {
+            let original_object = &original_objects[substitution.index];
+            match substitution.new_index {
+                Some(new_index) => {
+                    let object = &objects[new_index];
+                    assert_eq!((object.x, object.y), (original_object.x, original_object.y));
+                    let id = if substitution.child {
+                        object.child_id
+                    } else {
+                        object.id
+                    };
+                    assert_eq!(ObjectId2::from_id(id), substitution.replacement);
+                }
+                None => assert!(substitution.replacement.is_none()),
+            }
+        }
+    }
+    assert!(conversion
+        .cleared_tables
+        .iter()
+        .any(|(_, table)| *table == EntityTable2::PiranhaCreepers));
+    assert_no_unavailable_objects(&course, SMM2CourseHeader_GameStyle::M3);
+    assert_eq!(reload(&course).get_course(), course.get_course());
+}
+
+#[test]
+fn game_style2_convert_to_same_style() {
+    let original = get_course2(1, 121);
+    let mut course = original.clone();
+    let game_style = original.get_course().get_header().get_game_style();
+
+    let conversion = course.convert_game_style(game_style).unwrap();
+
+    assert_eq!(conversion, Default::default());
+    assert_eq!(course.get_course_data(), original.get_course_data());
+    assert_eq!(course.get_course(), original.get_course());
+}
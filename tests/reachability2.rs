@@ -0,0 +1,134 @@
+extern crate smmdb;
+
+use smmdb::{area2::*, constants2::*, course2::*, reachability2::*};
+use std::fs::read;
+
+fn get_course(index: u8) -> Course2 {
+    let mut course_data = read(format!(
+        "tests/assets/saves/smm2/save1/course_data_{}.bcd",
+        index
+    ))
+    .unwrap();
+    Course2::from_switch_files(&mut course_data, None, true).unwrap()
+}
+
+fn assert_connected(path: &[ReachabilityStep2]) {
+    for steps in path.windows(2) {
+        if steps[1].movement == Movement2::Warp {
+            continue;
+        }
+        assert_eq!(steps[0].area, steps[1].area);
+        assert!((steps[0].x as i32 - steps[1].x as i32).abs() <= 1);
+        assert!((steps[0].y as i32 - steps[1].y as i32).abs() <= 1);
+    }
+}
+
+#[test]
+fn reachability2_reachable() {
+    for index in [120, 128, 131] {
+        let course = get_course(index);
+        let header = course.get_course().get_header();
+
+        let reachability = course.analyze_reachability().unwrap();
+
+        assert_eq!(
+            reachability.verdict,
+            ReachabilityVerdict2::Reachable,
+            "{}",
+            index
+        );
+        let start = reachability.path.first().unwrap();
+        assert_eq!(start.movement, Movement2::Start);
+        assert_eq!((start.area, start.y), (AreaType::Main, header.start_y));
+        let goal = reachability.path.last().unwrap();
+        assert_eq!(goal.area, AreaType::Main);
+        assert!(goal.x >= header.finish_x / 10);
+        assert_connected(&reachability.path);
+        assert!(reachability.explored >= reachability.path.len());
+    }
+}
+
+/// Course 120 without any ground and objects.
+fn get_empty_course() -> Course2 {
+    let mut course = get_course(120);
+    for (table, area) in [
+        (EntityTable2::Objects, AreaType::Main),
+        (EntityTable2::Ground, AreaType::Main),
+        (EntityTable2::Objects, AreaType::Sub),
+    ] {
+        let offset = table.count_offset(area);
+        course.get_course_data_mut()[offset..offset + 4].copy_from_slice(&0u32.to_le_bytes());
+    }
+    course
+}
+
+#[test]
+fn reachability2_unreachable() {
+    let course = get_empty_course();
+    let finish_x = course.get_course().get_header().finish_x;
+
+    let reachability = course.analyze_reachability().unwrap();
+
+    assert_eq!(reachability.verdict, ReachabilityVerdict2::Unreachable);
+    let furthest = reachability.path.last().unwrap();
+    assert_eq!(furthest.area, AreaType::Main);
+    assert!(furthest.x < finish_x / 10);
+    assert!(reachability
+        .path
+        .iter()
+        .all(|step| step.movement != Movement2::Warp));
+    assert_connected(&reachability.path);
+}
+
+#[test]
+fn reachability2_unknown() {
+    let mut course = get_empty_course();
+    let count_offset = EntityTable2::Objects.count_offset(AreaType::Main);
+    let offset = EntityTable2::Objects.offset(AreaType::Main);
+    let course_data = course.get_course_data_mut();
+    course_data[count_offset..count_offset + 4].copy_from_slice(&1u32.to_le_bytes());
+    course_data[offset + 0x18..offset + 0x1A]
+        .copy_from_slice(&(ObjectId2::Goomba as i16).to_le_bytes());
+    course_data[offset + 0x1A..offset + 0x1C].copy_from_slice(&(-1i16).to_le_bytes());
+
+    let reachability = course.analyze_reachability().unwrap();
+
+    assert_eq!(reachability.verdict, ReachabilityVerdict2::Unknown);
+}
+
+#[test]
+fn reachability2_corrupted_boundaries() {
+    let mut course = get_course(120);
+    let offset = RIGHT_BOUNDARY_OFFSET[1];
+    course.get_course_data_mut()[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    let course = Course2::from_switch_files(course.get_course_data_mut(), None, false).unwrap();
+
+    let reachability = course.analyze_reachability().unwrap();
+
+    assert_eq!(reachability.verdict, ReachabilityVerdict2::Unknown);
+    assert!(reachability.path.is_empty());
+}
+
+#[test]
+fn reachability2_fixtures_not_unreachable() {
+    for index in 120..180 {
+        let course = get_course(index);
+
+        let reachability = course.analyze_reachability().unwrap();
+
+        assert_ne!(
+            reachability.verdict,
+            ReachabilityVerdict2::Unreachable,
+            "{}",
+            index
+        );
+    }
+}
+
+#[test]
+fn reachability2_requires_course_data() {
+    let course = get_course(120);
+    let course = Course2::from_proto(&course.get_proto(), None).unwrap();
+
+    assert!(course.analyze_reachability().is_err());
+}
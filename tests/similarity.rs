@@ -0,0 +1,118 @@
+extern crate smmdb;
+
+use smmdb::{area2::*, constants2::*, course::*, course2::*, similarity::*};
+use std::fs::read;
+
+fn get_course2(index: u8) -> Course2 {
+    let mut course_data = read(format!(
+        "tests/assets/saves/smm2/save1/course_data_{}.bcd",
+        index
+    ))
+    .unwrap();
+    Course2::from_switch_files(&mut course_data, None, true).unwrap()
+}
+
+/// Mirror all objects and ground tiles of the main area and drop the last object.
+fn mirror_and_edit(course: &Course2) -> Course2 {
+    let area = course.get_area(AreaType::Main).unwrap();
+    let mut course = course.clone();
+    let data = course.get_course_data_mut();
+    for (index, object) in area.objects.iter().enumerate() {
+        let offset = OBJECTS_OFFSET[0] + index * OBJECT_SIZE;
+        let x = 256 * OBJECT_TILE_SIZE - object.x;
+        data[offset..offset + 4].copy_from_slice(&x.to_le_bytes());
+    }
+    for (index, ground) in area.ground.iter().enumerate() {
+        let offset = GROUND_OFFSET[0] + index * GROUND_SIZE;
+        data[offset] = 255 - ground.x;
+    }
+    let object_count = area.objects.len() as u32 - 1;
+    data[OBJECT_COUNT_OFFSET[0]..OBJECT_COUNT_OFFSET[0] + 4]
+        .copy_from_slice(&object_count.to_le_bytes());
+    Course2::from_switch_files(course.get_course_data_mut(), None, false).unwrap()
+}
+
+#[test]
+fn course2_similarity_identical() {
+    let course = get_course2(120);
+    let layout = course.get_layout().unwrap();
+
+    let similarity = layout.similarity(&layout, SimilarityOptions::default());
+
+    assert_eq!(similarity.score, 1.0);
+    assert_eq!(similarity.alignments.len(), 2);
+    assert!(similarity
+        .alignments
+        .iter()
+        .all(|alignment| alignment.shift_x == 0 && alignment.shift_y == 0 && !alignment.mirrored));
+}
+
+#[test]
+fn course2_similarity_mirrored() {
+    let course = get_course2(120);
+    let layout = course.get_layout().unwrap();
+    let mirrored = mirror_and_edit(&course).get_layout().unwrap();
+
+    let similarity = layout.similarity(&mirrored, SimilarityOptions::default());
+    assert!(similarity.score > 0.95, "score {}", similarity.score);
+    assert!(similarity.score < 1.0);
+    assert!(similarity.alignments[0].mirrored);
+
+    let similarity = layout.similarity(&mirrored, SimilarityOptions::new(4, 2, false));
+    assert!(similarity.score < 0.5, "score {}", similarity.score);
+}
+
+#[test]
+fn course2_similarity_different() {
+    let layout = get_course2(120).get_layout().unwrap();
+    let other = get_course2(121).get_layout().unwrap();
+
+    let similarity = layout.similarity(&other, SimilarityOptions::default());
+
+    assert!(similarity.score < 0.5, "score {}", similarity.score);
+}
+
+#[test]
+fn similarity_index_query() {
+    let mut index = SimilarityIndex::default();
+    for i in 120..180 {
+        index.insert(i.to_string(), &get_course2(i).get_layout().unwrap());
+    }
+    assert_eq!(index.len(), 60);
+
+    let course = get_course2(120);
+    let matches = index.query(&course.get_layout().unwrap(), 3);
+    assert_eq!(matches[0].id, "120");
+    assert_eq!(matches[0].score, 1.0);
+    assert!(matches.len() <= 3);
+
+    let matches = index.query(&mirror_and_edit(&course).get_layout().unwrap(), 3);
+    assert_eq!(matches[0].id, "120");
+    assert!(matches[0].score > 0.5, "score {}", matches[0].score);
+
+    assert!(index.remove("120"));
+    assert!(!index.remove("120"));
+    let matches = index.query(&course.get_layout().unwrap(), 3);
+    assert!(matches.iter().all(|m| m.id != "120"));
+    assert_eq!(index.len(), 59);
+}
+
+#[test]
+fn course_similarity() {
+    let course = Course::from_proto(include_bytes!("assets/courses/course001/course"));
+    let other = Course::from_proto(include_bytes!("assets/courses/course002/course"));
+    let layout = course.get_layout();
+
+    assert_eq!(
+        layout
+            .similarity(&layout, SimilarityOptions::default())
+            .score,
+        1.0
+    );
+    assert!(
+        layout
+            .similarity(&other.get_layout(), SimilarityOptions::default())
+            .score
+            < 0.5
+    );
+}
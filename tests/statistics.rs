@@ -0,0 +1,116 @@
+extern crate smmdb;
+
+use smmdb::{area::*, area2::*, course::*, course2::*, statistics::*};
+use std::fs::read;
+
+fn get_course2(index: u8) -> Course2 {
+    let mut course_data = read(format!(
+        "tests/assets/saves/smm2/save1/course_data_{}.bcd",
+        index
+    ))
+    .unwrap();
+    Course2::from_switch_files(&mut course_data, None, true).unwrap()
+}
+
+#[test]
+fn course2_statistics() {
+    let course = get_course2(121);
+    let area = course.get_area(AreaType::Main).unwrap();
+
+    let statistics = course.get_statistics().unwrap();
+
+    assert_eq!(statistics.areas.len(), 2);
+    let main = &statistics.areas[0];
+    assert_eq!(main.area, AreaType::Main);
+    assert_eq!(main.ground as usize, area.ground.len());
+    assert_eq!(
+        main.objects.values().sum::<u32>() as usize,
+        area.objects.len()
+    );
+    assert_eq!(
+        main.screens.iter().sum::<u32>() as usize,
+        area.objects.len()
+    );
+    assert_eq!(main.screens.len(), 10);
+    assert_eq!(main.density, area.objects.len() as f64 / (3680.0 / 384.0));
+    let goombas = area
+        .objects
+        .iter()
+        .filter(|object| object.object_id() == Some(ObjectId2::Goomba))
+        .count();
+    assert_eq!(main.count(ObjectId2::Goomba) as usize, goombas);
+    assert_eq!(main.unknown, 0);
+    assert!(statistics.has_sub_area);
+    assert!(!statistics.uses_custom_scroll);
+}
+
+#[test]
+fn course2_statistics_empty_sub_area() {
+    let statistics = get_course2(120).get_statistics().unwrap();
+
+    let sub = &statistics.areas[1];
+    assert!(sub.objects.is_empty());
+    assert_eq!(sub.screens, vec![0; 4]);
+    assert_eq!(sub.density, 0.0);
+    assert!(!statistics.has_sub_area);
+    assert!(!statistics.uses_clear_pipes);
+}
+
+#[test]
+fn course2_statistics_vertical() {
+    let course = get_course2(130);
+    let area = course.get_area(AreaType::Sub).unwrap();
+
+    let statistics = course.get_statistics().unwrap();
+
+    let sub = &statistics.areas[1];
+    assert_eq!(sub.screens.len(), 13);
+    assert_eq!(sub.screens.iter().sum::<u32>() as usize, area.objects.len());
+}
+
+#[test]
+fn course2_statistics_requires_course_data() {
+    let course = get_course2(120);
+    let course = Course2::from_proto(&course.get_proto(), None).unwrap();
+
+    assert!(course.get_statistics().is_err());
+}
+
+#[test]
+fn course_statistics() {
+    let course = Course::from_proto(include_bytes!("assets/courses/course001/course"));
+    let objects = course.get_objects(AreaType::Main);
+
+    let statistics = course.get_statistics();
+
+    let main = &statistics.areas[0];
+    assert_eq!(main.screens.len(), 10);
+    assert_eq!(
+        (main.ground + main.objects.values().sum::<u32>()) as usize,
+        objects.len()
+    );
+    assert_eq!(main.count(ObjectId::Ground), 0);
+    assert_eq!(main.unknown, 0);
+    assert_eq!(
+        statistics.count(ObjectId::Block),
+        main.count(ObjectId::Block) + statistics.areas[1].count(ObjectId::Block)
+    );
+    assert!(statistics.has_sub_area);
+    assert!(!statistics.uses_clear_pipes);
+    assert!(!statistics.uses_custom_scroll);
+}
+
+#[test]
+fn course_width_in_pixels() {
+    let course = Course::from_proto(include_bytes!("assets/courses/course001/course"));
+    let width = course.get_course().width;
+    let objects = course.get_objects(AreaType::Main);
+
+    // the longest courses are 240 tiles of 16 pixels
+    assert_eq!(width, 240 * 16);
+    let right = objects.iter().map(|object| object.x).max().unwrap();
+    assert!(right / OBJECT_TILE_SIZE as u32 * 16 < width);
+    assert!(right / OBJECT_TILE_SIZE as u32 * 16 > width - 24 * 16);
+    let statistics: CourseStatistics<ObjectId> = course.get_statistics();
+    assert_eq!(statistics.areas[0].screens.len(), 10);
+}
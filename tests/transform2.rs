@@ -0,0 +1,214 @@
+extern crate smmdb;
+
+use smmdb::{area2::*, course2::*, errors::Smm2Error, Error};
+use std::{collections::HashSet, fs::read};
+
+fn get_course2(index: u8) -> Course2 {
+    let mut course_data = read(format!(
+        "tests/assets/saves/smm2/save1/course_data_{}.bcd",
+        index
+    ))
+    .unwrap();
+    Course2::from_switch_files(&mut course_data, None, true).unwrap()
+}
+
+fn reload(course: &Course2) -> Course2 {
+    let mut course_data = course.get_course_data().to_vec();
+    Course2::from_switch_files(&mut course_data, None, false).unwrap()
+}
+
+fn assert_unchanged(course: &Course2, original: &Course2) {
+    assert_eq!(course.get_course_data(), original.get_course_data());
+    assert_eq!(course.get_course(), original.get_course());
+}
+
+#[test]
+fn transform2_mirror_area() {
+    let original = get_course2(121);
+    let mut course = original.clone();
+    let width = (original.get_course().get_course_area().right_boundary / 16) as u8;
+
+    course.mirror_area(AreaType::Main).unwrap();
+
+    let ground = &original.get_area(AreaType::Main).unwrap().ground[0];
+    let mirrored = &course.get_area(AreaType::Main).unwrap().ground[0];
+    assert_eq!((mirrored.x, mirrored.y), (width - 1 - ground.x, ground.y));
+    let header = course.get_course().get_header();
+    let original_header = original.get_course().get_header();
+    assert_eq!(header.start_y, original_header.finish_y);
+    assert_eq!(header.finish_y, original_header.start_y);
+    assert_eq!(reload(&course).get_course(), course.get_course());
+
+    course.mirror_area(AreaType::Main).unwrap();
+    assert_unchanged(&course, &original);
+}
+
+#[test]
+fn transform2_mirror_area_with_tracks() {
+    let original = get_course2(137);
+    let mut course = original.clone();
+    let width = (original.get_course().get_course_sub_area().right_boundary / 16) as u8;
+
+    course.mirror_area(AreaType::Sub).unwrap();
+
+    let tracks = original.get_area(AreaType::Sub).unwrap().tracks;
+    let mirrored = course.get_area(AreaType::Sub).unwrap().tracks;
+    assert_eq!(
+        tracks
+            .iter()
+            .map(|track| track.track_type)
+            .collect::<Vec<_>>(),
+        vec![6, 7, 7, 6]
+    );
+    assert_eq!(
+        mirrored
+            .iter()
+            .map(|track| track.track_type)
+            .collect::<Vec<_>>(),
+        vec![5, 4, 4, 5]
+    );
+    for (track, mirrored) in tracks.iter().zip(mirrored.iter()) {
+        assert_eq!((mirrored.x, mirrored.y), (width - 1 - track.x, track.y));
+    }
+    assert_eq!(reload(&course).get_course(), course.get_course());
+
+    course.mirror_area(AreaType::Sub).unwrap();
+    assert_unchanged(&course, &original);
+}
+
+/// All tiles covered by the nodes of a clear pipe.
+fn clear_pipe_tiles(clear_pipe: &ClearPipe2) -> HashSet<(i32, i32)> {
+    let mut tiles = HashSet::new();
+    for node in clear_pipe.nodes.iter() {
+        let (x, y) = (node.x as i32, node.y as i32);
+        let (width, height) = (node.width as i32, node.height as i32);
+        let (xs, ys) = match (node.node_type, node.direction) {
+            (3..=10, _) => (x..x + 2, y..y + 2),
+            (_, 0) => (x..x + height, y - width + 1..y + 1),
+            (_, 1) => (x - height + 1..x + 1, y..y + width),
+            (_, 2) => (x..x + width, y..y + height),
+            _ => (x - width + 1..x + 1, y - height + 1..y + 1),
+        };
+        for x in xs {
+            for y in ys.clone() {
+                assert!(tiles.insert((x, y)));
+            }
+        }
+    }
+    tiles
+}
+
+#[test]
+fn transform2_mirror_area_with_clear_pipes() {
+    let original = get_course2(167);
+    let mut course = original.clone();
+    let width = (original.get_course().get_course_sub_area().right_boundary / 16) as i32;
+
+    course.mirror_area(AreaType::Sub).unwrap();
+
+    let area = original.get_area(AreaType::Sub).unwrap();
+    let mirrored = course.get_area(AreaType::Sub).unwrap();
+    assert_eq!(mirrored.clear_pipes.len(), 5);
+    for (clear_pipe, mirrored) in area.clear_pipes.iter().zip(mirrored.clear_pipes.iter()) {
+        let tiles: HashSet<_> = clear_pipe_tiles(clear_pipe)
+            .into_iter()
+            .map(|(x, y)| (width - 1 - x, y))
+            .collect();
+        assert_eq!(clear_pipe_tiles(mirrored), tiles);
+    }
+    let directions = |path: &Path2| path.nodes.iter().map(|node| node.direction).collect();
+    let path: Vec<u8> = directions(&area.exclamation_blocks[0]);
+    assert_eq!(path, vec![1, 1, 1, 5, 6, 1, 1, 1, 1, 14]);
+    let path: Vec<u8> = directions(&mirrored.exclamation_blocks[0]);
+    assert_eq!(path, vec![2, 2, 2, 9, 10, 2, 2, 2, 2, 13]);
+    assert_eq!(reload(&course).get_course(), course.get_course());
+
+    course.mirror_area(AreaType::Sub).unwrap();
+    assert_unchanged(&course, &original);
+}
+
+#[test]
+fn transform2_shift_area() {
+    let original = get_course2(121);
+    let mut course = original.clone();
+    let width = original.get_course().get_course_area().right_boundary / 16;
+    course.resize_area(AreaType::Main, width + 1).unwrap();
+    let header = course.get_course().get_header().clone();
+
+    course.shift_area(AreaType::Main, 1, 0).unwrap();
+
+    let objects = original.get_area(AreaType::Main).unwrap().objects;
+    let shifted = course.get_area(AreaType::Main).unwrap().objects;
+    assert_eq!(shifted[0].x, objects[0].x + OBJECT_TILE_SIZE);
+    assert_eq!(shifted[0].y, objects[0].y);
+    assert_eq!(course.get_course().get_header(), &header);
+
+    course.shift_area(AreaType::Main, -1, 0).unwrap();
+    course.resize_area(AreaType::Main, width).unwrap();
+    assert_unchanged(&course, &original);
+}
+
+#[test]
+fn transform2_shift_area_out_of_bounds() {
+    let original = get_course2(121);
+    let mut course = original.clone();
+
+    let res = course.shift_area(AreaType::Main, 0, -30);
+
+    assert!(matches!(
+        res,
+        Err(Error::Smm2Error(Smm2Error::EntityOutOfBounds { .. }))
+    ));
+    assert_unchanged(&course, &original);
+}
+
+#[test]
+fn transform2_resize_area() {
+    let original = get_course2(121);
+    let mut course = original.clone();
+    let settings = original.get_course().get_course_area();
+    let width = settings.right_boundary / 16;
+
+    course.resize_area(AreaType::Main, 240).unwrap();
+
+    assert_eq!(course.get_course().get_course_area().right_boundary, 3840);
+    assert_eq!(
+        course.get_course().get_header().finish_x,
+        original.get_course().get_header().finish_x + (240 - width) * 10
+    );
+    assert!(course.validate().unwrap().is_empty());
+    assert_eq!(reload(&course).get_course(), course.get_course());
+
+    course.resize_area(AreaType::Main, width).unwrap();
+    assert_unchanged(&course, &original);
+
+    let res = course.resize_area(AreaType::Main, 24);
+    assert!(matches!(
+        res,
+        Err(Error::Smm2Error(Smm2Error::EntityOutOfBounds { .. }))
+    ));
+    let res = course.resize_area(AreaType::Main, 241);
+    assert!(matches!(
+        res,
+        Err(Error::Smm2Error(Smm2Error::InvalidAreaWidth(241)))
+    ));
+    assert_unchanged(&course, &original);
+}
+
+#[test]
+fn transform2_crop_area() {
+    let original = get_course2(121);
+    let mut course = original.clone();
+    let width = original.get_course().get_course_area().right_boundary / 16;
+    course.resize_area(AreaType::Main, width + 10).unwrap();
+    course.shift_area(AreaType::Main, 10, 0).unwrap();
+
+    let res = course.crop_area(AreaType::Main, 5, width + 5);
+    assert!(matches!(
+        res,
+        Err(Error::Smm2Error(Smm2Error::EntityOutOfBounds { .. }))
+    ));
+    course.crop_area(AreaType::Main, 10, width + 10).unwrap();
+
+    assert_unchanged(&course, &original);
+}
@@ -0,0 +1,209 @@
+extern crate smmdb;
+
+use smmdb::{area2::AreaType, constants::*, course::*, validation::*};
+use std::fs::read;
+
+struct WiiUFiles {
+    course_data: Vec<u8>,
+    course_data_sub: Vec<u8>,
+    thumbnail: Vec<u8>,
+    thumbnail_preview: Vec<u8>,
+}
+
+impl WiiUFiles {
+    fn read(index: u8) -> WiiUFiles {
+        let path = format!("tests/assets/courses/course{:03}", index);
+        WiiUFiles {
+            course_data: read(format!("{}/course_data.cdt", path)).unwrap(),
+            course_data_sub: read(format!("{}/course_data_sub.cdt", path)).unwrap(),
+            thumbnail: read(format!("{}/{}", path, THUMBNAIL_0_NAME)).unwrap(),
+            thumbnail_preview: read(format!("{}/{}", path, THUMBNAIL_1_NAME)).unwrap(),
+        }
+    }
+
+    fn validate(&self) -> Vec<Diagnostic<Lint>> {
+        Course::validate_wii_u_files(
+            &self.course_data,
+            &self.course_data_sub,
+            &self.thumbnail,
+            &self.thumbnail_preview,
+        )
+    }
+}
+
+fn fix_crc(data: &mut [u8]) {
+    let crc = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC).checksum(&data[CRC_LENGTH..]);
+    data[CRC_OFFSET..CRC_OFFSET + 4].copy_from_slice(&crc.to_be_bytes());
+}
+
+#[test]
+fn validate_valid_courses() {
+    for index in 0..6 {
+        let files = WiiUFiles::read(index);
+
+        let diagnostics = files.validate();
+
+        assert!(diagnostics.is_empty(), "{}: {:?}", index, diagnostics);
+        Course::from_wii_u_files(
+            &files.course_data,
+            &files.course_data_sub,
+            &files.thumbnail,
+            &files.thumbnail_preview,
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn validate_checksum() {
+    let mut files = WiiUFiles::read(0);
+    files.course_data_sub[TILES_OFFSET] ^= 0xFF;
+
+    let diagnostics = files.validate();
+
+    assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+    assert_eq!(diagnostics[0].area, Some(AreaType::Sub));
+    assert!(matches!(diagnostics[0].kind, Lint::ChecksumMismatch { .. }));
+}
+
+#[test]
+fn validate_header() {
+    let mut files = WiiUFiles::read(0);
+    files.course_data[GAME_STYLE_OFFSET..GAME_STYLE_OFFSET_END].copy_from_slice(b"XX");
+    files.course_data[MONTH_OFFSET] = 13;
+    files.course_data[COURSE_THEME_OFFSET] = 6;
+    fix_crc(&mut files.course_data);
+
+    let diagnostics = files.validate();
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic {
+                severity: Severity::Error,
+                area: Some(AreaType::Main),
+                kind: Lint::Unparsable {
+                    field: "course theme".to_string()
+                },
+            },
+            Diagnostic {
+                severity: Severity::Error,
+                area: None,
+                kind: Lint::Unparsable {
+                    field: "modified".to_string()
+                },
+            },
+            Diagnostic {
+                severity: Severity::Error,
+                area: None,
+                kind: Lint::Unparsable {
+                    field: "game style".to_string()
+                },
+            },
+            Diagnostic {
+                severity: Severity::Warning,
+                area: Some(AreaType::Sub),
+                kind: Lint::GameStyleMismatch {
+                    main: "XX".to_string(),
+                    sub: String::from_utf8(
+                        files.course_data_sub[GAME_STYLE_OFFSET..GAME_STYLE_OFFSET_END].to_vec()
+                    )
+                    .unwrap(),
+                },
+            },
+        ]
+    );
+}
+
+#[test]
+fn validate_tiles_and_sounds() {
+    let mut files = WiiUFiles::read(0);
+    let data = &mut files.course_data;
+    let width = u16::from_be_bytes([data[WIDTH_OFFSET], data[WIDTH_OFFSET + 1]]) as i32;
+    let x = width * 10 + 800;
+    data[TILES_OFFSET..TILES_OFFSET + 4].copy_from_slice(&x.to_be_bytes());
+    data[SOUND_OFFSET..SOUND_OFFSET + SOUND_SIZE].copy_from_slice(&[0, 0, 0, 10, 30, 0, 0, 0]);
+    fix_crc(data);
+    files.course_data_sub[TILE_AMOUNT_OFFSET..TILE_AMOUNT_OFFSET + 2]
+        .copy_from_slice(&(TILE_MAX as u16 + 1).to_be_bytes());
+    fix_crc(&mut files.course_data_sub);
+
+    let diagnostics = files.validate();
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic {
+                severity: Severity::Warning,
+                area: Some(AreaType::Main),
+                kind: Lint::TileOutOfBounds {
+                    index: 0,
+                    x,
+                    y: i16::from_be_bytes([
+                        files.course_data[TILES_OFFSET + 8],
+                        files.course_data[TILES_OFFSET + 9]
+                    ]),
+                },
+            },
+            Diagnostic {
+                severity: Severity::Warning,
+                area: Some(AreaType::Main),
+                kind: Lint::SoundOutOfBounds {
+                    index: 0,
+                    x: 10,
+                    y: 30
+                },
+            },
+            Diagnostic {
+                severity: Severity::Error,
+                area: Some(AreaType::Sub),
+                kind: Lint::TileLimitExceeded {
+                    count: TILE_MAX + 1,
+                    max: TILE_MAX,
+                },
+            },
+        ]
+    );
+}
+
+#[test]
+fn validate_thumbnails() {
+    let mut files = WiiUFiles::read(0);
+    files.thumbnail[THUMBNAIL_HEADER_SIZE] = 0;
+    files.thumbnail_preview[4..THUMBNAIL_HEADER_SIZE].copy_from_slice(&u32::MAX.to_be_bytes());
+
+    let diagnostics = files.validate();
+
+    assert_eq!(diagnostics.len(), 3, "{:?}", diagnostics);
+    assert!(matches!(
+        &diagnostics[0].kind,
+        Lint::ThumbnailNotJpeg { file } if file == THUMBNAIL_0_NAME
+    ));
+    assert!(matches!(
+        &diagnostics[1].kind,
+        Lint::ThumbnailChecksumMismatch { file, .. } if file == THUMBNAIL_0_NAME
+    ));
+    assert!(matches!(
+        &diagnostics[2].kind,
+        Lint::ThumbnailTooShort { file } if file == THUMBNAIL_1_NAME
+    ));
+}
+
+#[test]
+fn validate_short_files() {
+    let diagnostics = Course::validate_wii_u_files(&[0; 16], &[], &[], &[0; 4]);
+
+    assert_eq!(diagnostics.len(), 4, "{:?}", diagnostics);
+    assert_eq!(
+        diagnostics[0],
+        Diagnostic {
+            severity: Severity::Error,
+            area: Some(AreaType::Main),
+            kind: Lint::FileTooShort {
+                length: 16,
+                expected: COURSE_SIZE,
+            },
+        }
+    );
+    assert!(diagnostics.iter().all(Diagnostic::is_error));
+}
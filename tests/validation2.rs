@@ -0,0 +1,175 @@
+extern crate smmdb;
+
+use smmdb::{
+    area2::*, constants2::*, course2::*, proto::SMM2Course::SMM2CourseHeader_GameStyle,
+    validation::*, validation2::*,
+};
+use std::fs::read;
+
+fn get_course(index: u8) -> Course2 {
+    let mut course_data = read(format!(
+        "tests/assets/saves/smm2/save1/course_data_{}.bcd",
+        index
+    ))
+    .unwrap();
+    Course2::from_switch_files(&mut course_data, None, true).unwrap()
+}
+
+fn write_u32(course: &mut Course2, offset: usize, value: u32) {
+    course.get_course_data_mut()[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+#[test]
+fn validate2_valid_courses() {
+    for index in 120..180 {
+        let diagnostics = get_course(index).validate().unwrap();
+
+        assert!(diagnostics.is_empty(), "{}: {:?}", index, diagnostics);
+    }
+}
+
+#[test]
+fn validate2_header() {
+    let mut course = get_course(120);
+    course.get_course_data_mut()[TIME_OFFSET..TIME_OFFSET + 2].copy_from_slice(&5u16.to_le_bytes());
+    course.get_course_data_mut()[START_Y_OFFSET] = 200;
+    course.get_course_data_mut()[TITLE_OFFSET..TITLE_OFFSET + 2].copy_from_slice(&[0, 0]);
+
+    let diagnostics = course.validate().unwrap();
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic {
+                severity: Severity::Error,
+                area: None,
+                kind: Lint2::TitleEmpty,
+            },
+            Diagnostic {
+                severity: Severity::Error,
+                area: None,
+                kind: Lint2::TimeOutOfRange {
+                    time: 5,
+                    min: 10,
+                    max: 500,
+                },
+            },
+            Diagnostic {
+                severity: Severity::Error,
+                area: Some(AreaType::Main),
+                kind: Lint2::StartOutOfBounds { start_y: 200 },
+            },
+        ]
+    );
+}
+
+#[test]
+fn validate2_boundaries() {
+    let mut course = get_course(120);
+    write_u32(&mut course, RIGHT_BOUNDARY_OFFSET[0], 2048);
+    course.get_course_data_mut()[ORIENTATION_OFFSET[1]] = 1;
+
+    let diagnostics = course.validate().unwrap();
+
+    assert!(diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.area == Some(AreaType::Sub)
+            && matches!(diagnostic.kind, Lint2::InvalidBoundaries { .. })));
+    assert!(diagnostics.contains(&Diagnostic {
+        severity: Severity::Warning,
+        area: Some(AreaType::Main),
+        kind: Lint2::FinishMisplaced {
+            finish_x: 745,
+            expected: 1185,
+        },
+    }));
+    assert!(!diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.area == Some(AreaType::Main)
+            && matches!(diagnostic.kind, Lint2::InvalidBoundaries { .. })));
+}
+
+#[test]
+fn validate2_corrupted_boundaries() {
+    let mut course = get_course(120);
+    write_u32(&mut course, RIGHT_BOUNDARY_OFFSET[0], 0x4000_0000);
+    write_u32(&mut course, TOP_BOUNDARY_OFFSET[1], u32::MAX);
+
+    let diagnostics = course.validate().unwrap();
+
+    for area in AreaType::ALL {
+        assert!(diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.area == Some(area)
+                && diagnostic.severity == Severity::Error
+                && matches!(diagnostic.kind, Lint2::InvalidBoundaries { .. })));
+    }
+    assert!(!diagnostics
+        .iter()
+        .any(|diagnostic| matches!(diagnostic.kind, Lint2::ObjectOutOfBounds { .. })));
+}
+
+#[test]
+fn validate2_entities() {
+    let mut course = get_course(120);
+    assert_eq!(
+        course.get_course().get_header().get_game_style(),
+        SMM2CourseHeader_GameStyle::M1
+    );
+    let object_count = course.get_area(AreaType::Main).unwrap().objects.len();
+    let clear_pipe = OBJECTS_OFFSET[0] + 0x18;
+    course.get_course_data_mut()[clear_pipe..clear_pipe + 2].copy_from_slice(&93i16.to_le_bytes());
+    let unknown = OBJECTS_OFFSET[0] + OBJECT_SIZE + 0x18;
+    course.get_course_data_mut()[unknown..unknown + 2].copy_from_slice(&500i16.to_le_bytes());
+    write_u32(&mut course, OBJECT_COUNT_OFFSET[0], object_count as u32 - 1);
+    write_u32(&mut course, ICICLE_COUNT_OFFSET[1], 301);
+
+    let diagnostics = course.validate().unwrap();
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic {
+                severity: Severity::Warning,
+                area: Some(AreaType::Main),
+                kind: Lint2::UnusedEntries {
+                    table: EntityTable2::Objects,
+                    count: object_count as u32 - 1,
+                    used: object_count,
+                },
+            },
+            Diagnostic {
+                severity: Severity::Error,
+                area: Some(AreaType::Main),
+                kind: Lint2::ObjectNotAvailable {
+                    index: 0,
+                    object: ObjectId2::ClearPipe,
+                    game_style: SMM2CourseHeader_GameStyle::M1,
+                },
+            },
+            Diagnostic {
+                severity: Severity::Error,
+                area: Some(AreaType::Main),
+                kind: Lint2::UnknownObject { index: 1, id: 500 },
+            },
+            Diagnostic {
+                severity: Severity::Error,
+                area: Some(AreaType::Sub),
+                kind: Lint2::EntityLimitExceeded {
+                    table: EntityTable2::Icicles,
+                    count: 301,
+                    max: 300,
+                },
+            },
+        ]
+    );
+    assert!(diagnostics.iter().any(Diagnostic::is_error));
+}
+
+#[test]
+fn validate2_requires_course_data() {
+    let course = get_course(120);
+    let course = Course2::from_proto(&course.get_proto(), None).unwrap();
+
+    assert!(course.validate().is_err());
+}